@@ -3,7 +3,7 @@
 //! TODO: refactor this module with Result as outputs. (issue-21)
 
 use crate::{Buffer, Error, Result};
-use opcodes::{for_each_cancun_operator, Cancun as OpCode, OpCode as _};
+use opcodes::{for_each_cancun_operator, Cancun as OpCode, OpCode as _, Upgrade};
 
 const MAX_STACK_SIZE: u16 = 1024;
 
@@ -14,7 +14,8 @@ pub struct Assembler {
     buffer: Buffer,
     /// Gas counter.
     ///
-    /// This is used to calculate the gas cost of the generated code.
+    /// This is used to estimate the gas cost of the generated code,
+    /// only the more expensive branch of an if-else is counted.
     ///
     /// TODO: use a more precise type, eq `u256`. (issue-20)
     gas: u128,
@@ -22,9 +23,19 @@ pub struct Assembler {
     pub mp: usize,
     /// Stack pointer, maximum `MAX_STACK_SIZE` items.
     pub sp: u16,
+    /// The fork that the bytecode targets.
+    pub target: Upgrade,
 }
 
 impl Assembler {
+    /// New assembler targeting the provided fork.
+    pub fn new(target: Upgrade) -> Self {
+        Self {
+            target,
+            ..Default::default()
+        }
+    }
+
     /// Buffer of the assembler.
     pub fn buffer(&self) -> &[u8] {
         &self.buffer
@@ -35,6 +46,11 @@ impl Assembler {
         &mut self.buffer
     }
 
+    /// Get the gas counter.
+    pub fn gas(&self) -> u128 {
+        self.gas
+    }
+
     /// Increment the gas counter.
     ///
     /// TODO: use number bigger than `u256` for throwing proper errors. (#21)
@@ -42,6 +58,23 @@ impl Assembler {
         self.gas += gas;
     }
 
+    /// Count the gas of the `PUSH` inserted by the relocation for the
+    /// jump target or the return address at the current program counter.
+    pub fn increment_relocation_gas(&mut self) {
+        self.increment_gas(OpCode::PUSH2.gas().into());
+    }
+
+    /// Uncount the gas of the `PUSH` of a jump which is not relocated,
+    /// i.e. the jumps to the `PC` of a trap.
+    pub fn decrement_relocation_gas(&mut self) {
+        self.decrement_gas(OpCode::PUSH2.gas().into());
+    }
+
+    /// Decrement the gas counter.
+    pub fn decrement_gas(&mut self, gas: u128) {
+        self.gas = self.gas.saturating_sub(gas);
+    }
+
     /// Increment stack pointer
     pub fn increment_sp(&mut self, items: u16) -> Result<()> {
         if items == 0 {
@@ -119,16 +152,40 @@ impl Assembler {
     /// Emit a single opcode.
     ///
     /// Mock the stack input and output for checking
-    /// the stack usages.
+    /// the stack usages, opcodes introduced after the
+    /// target fork are substituted or refused.
     pub fn emit_op(&mut self, opcode: OpCode) -> Result<()> {
+        if opcode.since() > self.target {
+            return self.emit_substitute(opcode);
+        }
+
         tracing::trace!("emit opcode: {:?}", opcode);
         self.decrement_sp(opcode.stack_in())?;
         self.emit(opcode.into());
         self.increment_gas(opcode.gas().into());
         self.increment_sp(opcode.stack_out())?;
 
+        // jumps to dynamic targets are overestimated.
+        if matches!(opcode, OpCode::JUMP | OpCode::JUMPI) {
+            self.increment_relocation_gas();
+        }
+
         Ok(())
     }
+
+    /// Emit the substitute of an opcode which is not available
+    /// on the target fork.
+    fn emit_substitute(&mut self, opcode: OpCode) -> Result<()> {
+        tracing::trace!("substitute opcode {:?} on {}", opcode, self.target);
+        match opcode {
+            OpCode::PUSH0 => {
+                self.emit_op(OpCode::PUSH1)?;
+                self.emit(0);
+                Ok(())
+            }
+            _ => Err(Error::UnavailableOpcode(opcode, self.target)),
+        }
+    }
 }
 
 macro_rules! impl_opcodes {
@@ -1,9 +1,38 @@
 //! Backtrace support for the code generation.
 use std::collections::BTreeMap;
 
+/// Source of a range of EVM program counters.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Source {
+    /// The first program counter of the range.
+    pub start: u16,
+    /// The program counter right after the range.
+    pub end: u16,
+    /// Index of the WASM function.
+    pub func: u32,
+    /// Byte offset of the WASM operator in the module.
+    pub offset: usize,
+}
+
+impl Source {
+    /// Shift the range for `offset` bytes inserted at `pc`.
+    ///
+    /// The bytes inserted at the start of the range belong to it.
+    pub fn shift(&mut self, pc: u16, offset: u16) {
+        if self.start > pc {
+            self.start += offset;
+        }
+
+        if self.end > pc {
+            self.end += offset;
+        }
+    }
+}
+
 /// Backtrace implementation for the code generation.
 ///
-/// TODO: full implementation (#21)
+/// The sources of the emitted code are tracked by the jump table
+/// instead, since their program counters move with relocations.
 #[derive(Debug, Default)]
 pub struct Backtrace {
     /// Compiled instructions.
@@ -1,6 +1,8 @@
 //! Table for the code section.
 
+use crate::{MacroAssembler, Result};
 use indexmap::IndexMap;
+use opcodes::Upgrade;
 
 /// Code section for EVM.
 #[derive(Clone, Default, Debug)]
@@ -9,6 +11,8 @@ pub struct Code {
     offset: usize,
     /// Function table.
     funcs: IndexMap<ExtFunc, usize>,
+    /// Data segments by their indexes in the data section.
+    data: IndexMap<u32, (Vec<u8>, usize)>,
 }
 
 impl Code {
@@ -17,6 +21,7 @@ impl Code {
         Self {
             offset: 0,
             funcs: Default::default(),
+            data: Default::default(),
         }
     }
 
@@ -31,6 +36,7 @@ impl Code {
         let offset = offset as usize;
         self.offset += offset;
         self.funcs.values_mut().for_each(|pc| *pc += offset);
+        self.data.values_mut().for_each(|(_, pc)| *pc += offset);
     }
 
     /// Add a function to the code section.
@@ -45,6 +51,25 @@ impl Code {
         self.offset += len;
     }
 
+    /// Get the data segments in the code section.
+    pub fn data(&self) -> Vec<(u32, Vec<u8>)> {
+        self.data
+            .iter()
+            .map(|(index, (data, _))| (*index, data.clone()))
+            .collect()
+    }
+
+    /// Add a data segment to the code section.
+    pub fn try_add_data(&mut self, index: u32, data: Vec<u8>) {
+        if self.data.contains_key(&index) {
+            return;
+        }
+
+        let len = data.len();
+        self.data.insert(index, (data, self.offset));
+        self.offset += len;
+    }
+
     /// Get the current offset of the code section.
     pub fn offset(&self) -> usize {
         self.offset
@@ -55,14 +80,32 @@ impl Code {
         self.funcs.get(func).and_then(|i| (*i).try_into().ok())
     }
 
+    /// Get the offset of a data segment.
+    pub fn offset_of_data(&self, index: u32) -> Option<u16> {
+        self.data
+            .get(&index)
+            .and_then(|(_, pc)| (*pc).try_into().ok())
+    }
+
     /// Get the bytecode of the code section.
     pub fn finish(&self) -> Vec<u8> {
-        let mut code = Vec::new();
-        for func in self.funcs.keys() {
+        let mut sections = Vec::new();
+        for (func, pc) in self.funcs.iter() {
             tracing::trace!("add function to code section: {:?}", func);
-            code.extend(func.bytecode.clone());
+            sections.push((*pc, func.bytecode.as_slice()));
+        }
+
+        for (index, (data, pc)) in self.data.iter() {
+            tracing::trace!("add data segment {index} to code section");
+            sections.push((*pc, data.as_slice()));
         }
-        code
+
+        sections.sort_by_key(|(pc, _)| *pc);
+        sections
+            .into_iter()
+            .flat_map(|(_, code)| code)
+            .copied()
+            .collect()
     }
 }
 
@@ -75,4 +118,61 @@ pub struct ExtFunc {
     pub stack_in: u8,
     /// The bytecode of the external function.
     pub bytecode: Vec<u8>,
+    /// If the function returns to the caller.
+    pub returning: bool,
+}
+
+impl ExtFunc {
+    /// Create an external function from the emitter of its body.
+    ///
+    /// The body takes `stack_in` items from the stack and leaves one
+    /// item as the result, the return address is kept under them.
+    pub fn new(
+        target: Upgrade,
+        stack_in: u8,
+        body: impl FnOnce(&mut MacroAssembler) -> Result<()>,
+    ) -> Result<Self> {
+        let mut masm = MacroAssembler::new(target);
+        masm.increment_sp(stack_in as u16 + 1)?;
+        masm._jumpdest()?;
+
+        // [PC, inputs..]
+        masm.swap(stack_in as u16)?;
+        for index in (1..stack_in).rev() {
+            masm.swap(index as u16)?;
+        }
+
+        body(&mut masm)?;
+
+        // [output, PC]
+        masm._swap1()?;
+        masm._jump()?;
+
+        Ok(Self {
+            stack_out: 1,
+            stack_in,
+            bytecode: masm.buffer().into(),
+            returning: true,
+        })
+    }
+
+    /// Create a stub which never returns from the emitter of its body.
+    ///
+    /// The stub is jumped to without a return address, i.e. a shared
+    /// revert, it takes nothing from the stack.
+    pub fn stub(
+        target: Upgrade,
+        body: impl FnOnce(&mut MacroAssembler) -> Result<()>,
+    ) -> Result<Self> {
+        let mut masm = MacroAssembler::new(target);
+        masm._jumpdest()?;
+        body(&mut masm)?;
+
+        Ok(Self {
+            stack_out: 0,
+            stack_in: 0,
+            bytecode: masm.buffer().into(),
+            returning: false,
+        })
+    }
 }
@@ -1,6 +1,7 @@
 //! Contract constructor.
 
-use crate::{wasm::ToLSBytes, Buffer, MacroAssembler, Result};
+use crate::{eof::Container, wasm::ToLSBytes, Buffer, MacroAssembler, Result};
+use opcodes::Upgrade;
 use smallvec::SmallVec;
 use std::collections::HashMap;
 
@@ -15,6 +16,13 @@ pub struct Constructor {
 }
 
 impl Constructor {
+    /// New constructor targeting the provided fork.
+    pub fn new(target: Upgrade) -> Self {
+        Self {
+            masm: MacroAssembler::new(target),
+        }
+    }
+
     /// preset storage for the contract
     pub fn storage(&mut self, mapping: InitStorage) -> Result<()> {
         tracing::debug!("Building storage in constructor ...");
@@ -37,7 +45,7 @@ impl Constructor {
         let runtime_bytecode_len = runtime_bytecode.len();
         let runtime_bytecode_size = runtime_bytecode_len.to_ls_bytes();
         let runtime_bytecode_offset =
-            Self::runtime_bytcode_offset(init_code_len, runtime_bytecode_size.len());
+            self.runtime_bytcode_offset(init_code_len, runtime_bytecode_size.len());
 
         tracing::trace!("length of bytecode: {:?}", runtime_bytecode_len);
         tracing::trace!(
@@ -61,6 +69,13 @@ impl Constructor {
         Ok(masm.buffer().into())
     }
 
+    /// Concat the constructor code into an EOF initcode container,
+    /// which returns the runtime container with `RETURNCONTRACT`.
+    pub fn finish_eof(&self, runtime_container: Buffer) -> Result<Buffer> {
+        let container = Container::initcode(self.masm.buffer(), runtime_container.to_vec())?;
+        Ok(container.encode()?.into())
+    }
+
     /// Returns the offset of runtime bytecode.
     ///
     /// [
@@ -69,8 +84,19 @@ impl Constructor {
     ///   pushn, runtime_bytecode_size, push0, return,
     ///   <OFFSET>
     /// ]
-    fn runtime_bytcode_offset(init_code_len: usize, runtime_bytecode_size_len: usize) -> usize {
-        let mut offset = init_code_len + runtime_bytecode_size_len * 2 + 8;
+    ///
+    /// `push0` takes 2 bytes on the forks before shanghai.
+    fn runtime_bytcode_offset(
+        &self,
+        init_code_len: usize,
+        runtime_bytecode_size_len: usize,
+    ) -> usize {
+        let push0 = if self.masm.target < Upgrade::Shanghai {
+            2
+        } else {
+            1
+        };
+        let mut offset = init_code_len + runtime_bytecode_size_len * 2 + 6 + push0 * 2;
         if (offset <= 0xff) && (offset + offset.to_ls_bytes().len() > 0xff) {
             offset += 1;
         }
@@ -2,12 +2,61 @@
 
 use crate::{
     wasm::{self, Env, Functions},
-    JumpTable, MacroAssembler, Result,
+    GasBlock, JumpTable, MacroAssembler, Result,
 };
-use std::collections::BTreeMap;
+use std::{collections::BTreeMap, fmt, str::FromStr};
 use wasmparser::FuncType;
 use zabi::Abi;
 
+/// The maximum selectors compared one after another in the binary search.
+const SEARCH_LEAF: usize = 4;
+
+/// Strategy of the selector dispatcher.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "kebab-case")
+)]
+pub enum DispatchStrategy {
+    /// Compare the selector against the functions one after another.
+    #[default]
+    Linear,
+    /// Binary search the selector in the sorted selectors, the gas of
+    /// dispatching grows logarithmically with the count of functions.
+    BinarySearch,
+}
+
+impl DispatchStrategy {
+    /// All of the strategies.
+    pub const ALL: [DispatchStrategy; 2] = [Self::Linear, Self::BinarySearch];
+
+    /// The name of the strategy.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Linear => "linear",
+            Self::BinarySearch => "binary-search",
+        }
+    }
+}
+
+impl fmt::Display for DispatchStrategy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for DispatchStrategy {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|strategy| strategy.name().eq_ignore_ascii_case(s))
+            .ok_or_else(|| format!("unknown dispatch strategy {s}"))
+    }
+}
+
 /// Code generator for EVM dispatcher.
 pub struct Dispatcher {
     /// ABI for the current function
@@ -18,6 +67,10 @@ pub struct Dispatcher {
     pub env: Env,
     /// Module functions
     pub funcs: BTreeMap<u32, FuncType>,
+    /// Code run from the start of the dispatcher to each callee.
+    pub paths: BTreeMap<u32, Vec<GasBlock>>,
+    /// Strategy of dispatching
+    pub strategy: DispatchStrategy,
     /// Jump table
     pub table: JumpTable,
 }
@@ -32,13 +85,21 @@ impl Dispatcher {
 
         Ok(Self {
             abi: Default::default(),
-            asm: Default::default(),
+            asm: MacroAssembler::new(env.target),
             env,
             funcs,
+            paths: Default::default(),
+            strategy: Default::default(),
             table: Default::default(),
         })
     }
 
+    /// With the strategy of dispatching.
+    pub fn strategy(mut self, strategy: DispatchStrategy) -> Self {
+        self.strategy = strategy;
+        self
+    }
+
     /// Emit compiled code to the given buffer.
     pub fn finish(&mut self, selectors: Functions<'_>, table: &mut JumpTable) -> Result<Vec<u8>> {
         if selectors.is_empty() {
@@ -49,18 +110,33 @@ impl Dispatcher {
         self.asm._calldataload()?;
         self.asm.push(&[0xe0])?;
         self.asm._shr()?;
-        let mut len = selectors.len();
-        for (_, func) in selectors.iter() {
-            self.emit_selector(func, len == 1)?;
-            len -= 1;
+
+        match self.strategy {
+            DispatchStrategy::Linear => {
+                let mut len = selectors.len();
+                for (_, func) in selectors.iter() {
+                    let (selector, func) = self.load_selector(func)?;
+                    self.emit_selector(selector, func, len == 1)?;
+                    self.paths.insert(func, vec![self.block((0, 0))]);
+                    len -= 1;
+                }
+            }
+            DispatchStrategy::BinarySearch => {
+                let mut targets = selectors
+                    .values()
+                    .map(|func| self.load_selector(func))
+                    .collect::<Result<Vec<_>>>()?;
+                targets.sort();
+                self.emit_search(&targets, &[], (0, 0))?;
+            }
         }
 
         table.merge(self.table.clone(), 0)?;
         Ok(self.asm.buffer().into())
     }
 
-    /// Emit selector to buffer.
-    fn emit_selector(&mut self, selector: &wasm::Function<'_>, last: bool) -> Result<()> {
+    /// Load the selector and the callee function of a selector function.
+    fn load_selector(&mut self, selector: &wasm::Function<'_>) -> Result<([u8; 4], u32)> {
         let abi = self.env.load_abi(selector)?;
         self.abi.push(abi.clone());
 
@@ -71,7 +147,25 @@ impl Dispatcher {
             abi.signature(),
         );
 
-        let func = self.env.query_func(&abi.name)?;
+        Ok((selector_bytes, self.env.query_func(&abi.name)?))
+    }
+
+    /// The code emitted since `start`, the program counter and the gas.
+    fn block(&self, start: (u16, u128)) -> GasBlock {
+        GasBlock {
+            range: start.0..self.asm.pc(),
+            gas: (self.asm.gas() - start.1) as u64,
+        }
+    }
+
+    /// Emit selector to buffer.
+    fn emit_selector(&mut self, selector: [u8; 4], func: u32, last: bool) -> Result<()> {
+        // EOF jumps to the callee function with `RJUMPI` and `JUMPF`,
+        // the selector is kept on the stack.
+        if self.env.eof {
+            return self.emit_case(selector, func);
+        }
+
         self.asm.increment_sp(1)?;
 
         // Prepare the `PC` of the callee function.
@@ -83,11 +177,68 @@ impl Dispatcher {
             self.asm._dup2()?;
         }
 
-        self.asm.push(&selector_bytes)?;
+        self.asm.push(&selector)?;
         self.asm._eq()?;
         self.asm._swap1()?;
         self.asm._jumpi()?;
 
         Ok(())
     }
+
+    /// Jump to the callee function if the selector matches, the selector
+    /// is kept on the stack.
+    ///
+    /// STACK: [selector] -> [selector]
+    fn emit_case(&mut self, selector: [u8; 4], func: u32) -> Result<()> {
+        self.asm._dup1()?;
+        self.asm.push(&selector)?;
+        self.asm._eq()?;
+        self.table.call(self.asm.pc(), func);
+        self.asm.increment_sp(1)?;
+        self.asm._jumpi()
+    }
+
+    /// Binary search the selector in the sorted selectors, the unknown
+    /// selectors revert.
+    ///
+    /// `path` is the code run before the block which starts at `start`.
+    ///
+    /// STACK: [selector] -> [selector]
+    fn emit_search(
+        &mut self,
+        targets: &[([u8; 4], u32)],
+        path: &[GasBlock],
+        start: (u16, u128),
+    ) -> Result<()> {
+        if targets.len() <= SEARCH_LEAF {
+            for (selector, func) in targets {
+                self.emit_case(*selector, *func)?;
+                self.paths
+                    .insert(*func, [path, &[self.block(start)]].concat());
+            }
+
+            self.asm._push0()?;
+            self.asm._push0()?;
+            return self.asm._revert();
+        }
+
+        let sp = self.asm.sp();
+        let (lower, upper) = targets.split_at(targets.len() / 2);
+
+        // jump to the lower half if the selector is less than the pivot.
+        self.asm._dup1()?;
+        self.asm.push(&upper[0].0)?;
+        self.asm._lt()?;
+        let jump = self.asm.pc();
+        self.asm.increment_sp(1)?;
+        self.asm._jumpi()?;
+        let taken = [path, &[self.block(start)]].concat();
+        self.emit_search(upper, path, start)?;
+
+        self.table.label(jump, self.asm.pc());
+        self.asm.asm.sp = sp;
+        let start = (self.asm.pc(), self.asm.gas());
+        self.asm._jumpdest()?;
+        self.emit_search(lower, &taken, start)
+    }
 }
@@ -2,12 +2,13 @@
 use crate::{
     backtrace::Backtrace,
     control::ControlStack,
+    jump::Jump,
     jump::JumpTable,
     local::{LocalSlot, LocalSlotType, Locals},
     masm::MacroAssembler,
     validator::ValidateThenVisit,
-    wasm::Env,
-    Buffer, Error, Result,
+    wasm::{self, Env},
+    Buffer, Error, Gas, GasBlock, Result, Source,
 };
 use opcodes::ShangHai as OpCode;
 use wasmparser::{FuncType, FuncValidator, LocalsReader, OperatorsReader, ValidatorResources};
@@ -19,18 +20,34 @@ pub struct Function {
     pub abi: Option<Abi>,
     /// The backtrace.
     pub backtrace: Backtrace,
+    /// Gas of both branches of each if-else.
+    pub branches: Vec<[GasBlock; 2]>,
     /// Control stack frames.
     pub control: ControlStack,
+    /// Width of the value on the top of the stack if it could
+    /// carry overflowed bits.
+    pub dirty: Option<usize>,
     /// WASM environment.
     pub env: Env,
     /// The defined locals for a function.
     pub locals: Locals,
+    /// Gas of one iteration of each loop.
+    pub loops: Vec<GasBlock>,
     /// The macro assembler.
     pub masm: MacroAssembler,
+    /// The highest EVM memory offset the linear memory and the heap
+    /// accessed by the function could reach.
+    pub memory: usize,
+    /// Byte offset of the current operator in the module.
+    pub offset: usize,
+    /// If the current operator is reachable.
+    pub reachable: bool,
     /// The jump table.
     pub table: JumpTable,
     /// The function type.
     pub ty: FuncType,
+    /// Count of the control frames opened in unreachable code.
+    pub unreachable_depth: u32,
     /// If this function is the main function.
     pub is_main: bool,
 }
@@ -39,15 +56,23 @@ impl Function {
     /// Create a new code generator.
     pub fn new(env: Env, ty: FuncType, abi: Option<Abi>, is_main: bool) -> Result<Self> {
         let is_external = abi.is_some();
+        let masm = MacroAssembler::new(env.target);
         let mut codegen = Self {
             abi,
             backtrace: Backtrace::default(),
+            branches: Default::default(),
             control: ControlStack::default(),
+            dirty: None,
             env,
             ty,
             locals: Default::default(),
-            masm: Default::default(),
+            loops: Default::default(),
+            masm,
+            memory: 0,
+            offset: 0,
+            reachable: true,
             table: Default::default(),
+            unreachable_depth: 0,
             is_main,
         };
 
@@ -86,6 +111,10 @@ impl Function {
     ) -> Result<()> {
         let mut sp = if self.is_main { 0 } else { 1 };
 
+        for ty in self.ty.params().iter().chain(self.ty.results()) {
+            wasm::check_type(ty)?;
+        }
+
         // Define locals in function parameters.
         for param in self.ty.params() {
             self.locals
@@ -97,6 +126,7 @@ impl Function {
         //
         // Record the offset for validation.
         while let Ok((count, val)) = locals.read() {
+            wasm::check_type(&val)?;
             for _ in 0..count {
                 // Define locals.
                 self.locals
@@ -109,6 +139,17 @@ impl Function {
             validator.define_locals(validation_offset, count, val)?;
         }
 
+        // Reset the locals in function body since their memory slots are
+        // shared by all calls of this function, the memory of the main
+        // function is clean anyway.
+        if !self.is_main {
+            for index in self.ty.params().len()..self.locals.len() {
+                self.masm._push0()?;
+                self.masm.push(&self.env.alloc(index as u32))?;
+                self.masm._mstore()?;
+            }
+        }
+
         tracing::trace!("{:?}", self.locals);
         Ok(())
     }
@@ -120,20 +161,89 @@ impl Function {
         validator: &mut FuncValidator<ValidatorResources>,
     ) -> Result<()> {
         while !ops.eof() {
-            let offset = ops.original_position();
-            let mut validate_then_visit = ValidateThenVisit(validator.visitor(offset), self);
+            self.offset = ops.original_position();
+            let op = ops.clone().read()?;
+            let start = self.masm.pc();
+            let overflow = if self.reachable {
+                self.check_soft_float(&op)?;
+                self.wrap_operands(&op)?
+            } else {
+                None
+            };
+
+            let mut validate_then_visit = ValidateThenVisit(validator.visitor(self.offset), self);
             ops.visit_operator(&mut validate_then_visit)???;
+            self.dirty = overflow;
+            self.source(start);
         }
 
         if (self.abi.is_some() || self.is_main)
+            && self.reachable
             && self.masm.buffer().last() != Some(&OpCode::RETURN.into())
         {
+            let start = self.masm.pc();
             self._end()?;
+            self.source(start);
         }
 
         Ok(())
     }
 
+    /// Register the code emitted since `start` as the source of the
+    /// current operator.
+    fn source(&mut self, start: u16) {
+        self.table.source(Source {
+            start,
+            end: self.masm.pc(),
+            func: self.env.index.unwrap_or_default(),
+            offset: self.offset,
+        });
+    }
+
+    /// Error of an unsupported operator at the current offset.
+    pub fn unsupported(&self, operator: &str) -> Error {
+        let function = self
+            .env
+            .index
+            .map(|index| match self.env.exports.get(&index) {
+                Some(name) => name.clone(),
+                None => index.to_string(),
+            })
+            .unwrap_or_default();
+
+        Error::UnsupportedOperator(operator.into(), function, self.offset)
+    }
+
+    /// Estimate the gas of the function, excluding the functions
+    /// it calls.
+    pub fn gas(&self) -> Gas {
+        let mut calls = Vec::new();
+        for jump in self.table.jump.values() {
+            if let Jump::Func(index) = jump {
+                calls.push(*index);
+            }
+        }
+
+        // the params of the main and the external functions are read
+        // from the calldata.
+        let external = self.is_main || self.abi.is_some();
+        let locals = if external && self.locals.len() == self.ty.params().len() {
+            0
+        } else {
+            (self.env.reserved() as usize + self.locals.len()) * 0x20
+        };
+
+        Gas {
+            fixed: self.masm.asm.gas() as u64,
+            loops: self.loops.iter().map(|block| block.gas).collect(),
+            memory: self.masm.mp.max(locals).max(self.memory),
+            calls,
+            size: self.masm.pc(),
+            loop_ranges: self.loops.iter().map(|block| block.range.clone()).collect(),
+            branches: self.branches.clone(),
+        }
+    }
+
     /// Finish code generation.
     pub fn finish(self, jump_table: &mut JumpTable, pc: u16) -> Result<Buffer> {
         let sp = self.masm.sp();
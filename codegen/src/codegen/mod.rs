@@ -1,6 +1,7 @@
 //! Code generators
 //!
 //! - CONSTRUCTOR
+//! - PROLOGUE
 //! - DISPATCHER
 //! - FUNCTION
 //! - CODE
@@ -9,10 +10,12 @@ mod code;
 mod constructor;
 mod dispatcher;
 mod function;
+mod prologue;
 
 pub use self::{
     code::{Code, ExtFunc},
     constructor::{Constructor, InitStorage},
-    dispatcher::Dispatcher,
+    dispatcher::{DispatchStrategy, Dispatcher},
     function::Function,
+    prologue::Prologue,
 };
@@ -0,0 +1,75 @@
+//! Code generator for the entry prologue.
+
+use crate::{
+    wasm::{Env, ToLSBytes},
+    Error, GasBlock, JumpTable, MacroAssembler, Result,
+};
+
+/// Code generator for the entry prologue.
+///
+/// The active data segments are appended to the code section reversed,
+/// the same as the linear memory, and copied into the initial pages
+/// with `CODECOPY` before the dispatcher, so the loads from them, i.e.
+/// the constant tables of rustc, see their bytes at runtime.
+pub struct Prologue {
+    /// Code buffer
+    pub asm: MacroAssembler,
+    /// WASM environment
+    pub env: Env,
+    /// Jump table
+    pub table: JumpTable,
+}
+
+impl Prologue {
+    /// Create the prologue of the active data segments.
+    pub fn new(env: Env) -> Self {
+        Self {
+            asm: MacroAssembler::new(env.target),
+            env,
+            table: Default::default(),
+        }
+    }
+
+    /// Emit the copies of the active segments and merge the jump table
+    /// of them into the provided one.
+    pub fn finish(&mut self, table: &mut JumpTable) -> Result<Vec<u8>> {
+        let memory = self.env.memory;
+        for (index, offset, data) in self.env.data.active_segments() {
+            if data.is_empty() {
+                continue;
+            }
+
+            let end = usize::try_from(offset)
+                .ok()
+                .and_then(|offset| offset.checked_add(data.len()))
+                .filter(|end| *end <= memory.initial())
+                .ok_or(Error::MemoryOutOfBounds)?;
+
+            let len = data.len();
+            let mut data = data.to_vec();
+            data.reverse();
+
+            // [size, code, dst]
+            self.asm.push(&len.to_ls_bytes())?;
+            self.table.data(self.asm.pc(), index, data);
+            self.asm.increment_relocation_gas();
+            self.asm.increment_sp(1)?;
+            self.asm.push(&(memory.bound() - end).to_ls_bytes())?;
+            self.asm._codecopy()?;
+
+            // the copy cost of `CODECOPY`, 3 for each word.
+            self.asm.increment_gas(3 * len.div_ceil(0x20) as u128);
+        }
+
+        table.merge(self.table.clone(), 0)?;
+        Ok(self.asm.buffer().into())
+    }
+
+    /// Base gas of the emitted code, the memory expansion is excluded.
+    pub fn gas(&self) -> GasBlock {
+        GasBlock {
+            range: 0..self.asm.pc(),
+            gas: self.asm.gas() as u64,
+        }
+    }
+}
@@ -32,32 +32,44 @@ pub struct ControlStackFrame {
     pub ty: ControlStackFrameType,
     /// The program counter offset at the beginning of if.
     pub original_pc_offset: u16,
-    /// The return values of the block.
-    ///
-    /// Could be useful for validation.
+    /// The type of the block.
     result: BlockType,
+    /// The count of the params of the block.
+    params: u16,
+    /// The count of the results of the block.
+    results: u16,
 
-    /// Original stack pointer.
+    /// Stack pointer at the entry of the frame, below the params.
     pub original_sp: u16,
 
-    /// Flag to mark frames that might contain early returns
-    pub might_return_early: bool,
+    /// Gas counter at the entry of the frame.
+    pub gas: u128,
+
+    /// Program counters of the forward branches to the end of the
+    /// frame, and whether they are entries of computed jump tables.
+    pub branches: Vec<(u16, bool)>,
 }
 
 impl ControlStackFrame {
     /// Create a new control stack frame.
+    ///
+    /// `io` is the counts of the params and the results of the block.
     pub fn new(
         ty: ControlStackFrameType,
         original_pc_offset: u16,
         original_sp: u16,
         result: BlockType,
+        (params, results): (u16, u16),
     ) -> Self {
         Self {
             ty,
             original_pc_offset,
             original_sp,
             result,
-            might_return_early: false,
+            params,
+            results,
+            gas: 0,
+            branches: Default::default(),
         }
     }
 
@@ -71,15 +83,41 @@ impl ControlStackFrame {
         self.result
     }
 
-    /// Set the flag indicating this frame might contain early returns
-    pub fn set_might_return_early(&mut self, value: bool) {
-        self.might_return_early = value;
+    /// The counts of the params and the results of the frame.
+    pub fn io(&self) -> (u16, u16) {
+        (self.params, self.results)
+    }
+
+    /// The count of the values that the frame takes from the stack.
+    pub fn params(&self) -> u16 {
+        self.params
+    }
+
+    /// The count of the values that the frame leaves on the stack.
+    pub fn results(&self) -> u16 {
+        self.results
+    }
+
+    /// The count of the values that branches to this frame carry.
+    ///
+    /// Branches to a loop restart it, so they carry the params of
+    /// the loop.
+    pub fn arity(&self) -> u16 {
+        if self.ty == ControlStackFrameType::Loop {
+            return self.params;
+        }
+
+        self.results
+    }
+
+    /// The stack height that branches to this frame should leave.
+    pub fn height(&self) -> u16 {
+        self.original_sp + self.arity()
     }
 
-    /// Check if this frame is at a function boundary
-    pub fn is_function_boundary(&self) -> bool {
-        // For now, we consider Block frames as potential function boundaries
-        matches!(self.ty, ControlStackFrameType::Block)
+    /// The stack height at the end of the frame.
+    pub fn end(&self) -> u16 {
+        self.original_sp + self.results
     }
 }
 
@@ -123,32 +161,28 @@ impl ControlStack {
         self.stack.pop().ok_or_else(|| Error::ControlStackUnderflow)
     }
 
-    /// Get the label of the control stack frame at given depth.
-    pub fn label_from_depth(&self, mut depth: u32) -> Result<u16> {
-        for frame in self.stack.iter().rev() {
-            if frame.ty == ControlStackFrameType::Else {
-                continue;
-            }
-
-            if depth == 0 {
-                return Ok(frame.pc_offset());
-            }
-
-            depth -= 1;
-        }
+    /// Get a reference to the frame at the given depth
+    pub fn frame_from_depth(&self, depth: u32) -> Result<&ControlStackFrame> {
+        let index = self.index_from_depth(depth)?;
+        Ok(&self.stack[index])
+    }
 
-        Err(Error::InvalidDepth(depth as usize))
+    /// Get a mutable reference to the frame at the given depth
+    pub fn frame_from_depth_mut(&mut self, depth: u32) -> Result<&mut ControlStackFrame> {
+        let index = self.index_from_depth(depth)?;
+        Ok(&mut self.stack[index])
     }
 
-    /// Get a reference to the frame at the given depth
-    pub fn frame_from_depth(&self, mut depth: u32) -> Result<&ControlStackFrame> {
-        for (i, frame) in self.stack.iter().rev().enumerate() {
+    /// Get the index of the frame at the given depth, else frames
+    /// are skipped since they share the label of their if frames.
+    fn index_from_depth(&self, mut depth: u32) -> Result<usize> {
+        for (i, frame) in self.stack.iter().enumerate().rev() {
             if frame.ty == ControlStackFrameType::Else {
                 continue;
             }
 
             if depth == 0 {
-                return Ok(&self.stack[self.stack.len() - 1 - i]);
+                return Ok(i);
             }
 
             depth -= 1;
@@ -157,60 +191,6 @@ impl ControlStack {
         Err(Error::InvalidDepth(depth as usize))
     }
 
-    /// Check if a branch at the given depth would exit the function
-    pub fn is_exit_branch(&self, depth: u32) -> bool {
-        // If depth exceeds our stack, it's definitely an exit
-        if depth as usize >= self.depth() {
-            return true;
-        }
-
-        // Get the frame that would be the target
-        if let Ok(frame) = self.frame_from_depth(depth) {
-            // If it's a Block at the outermost level (index 0), it might be a function boundary
-            if matches!(frame.ty, ControlStackFrameType::Block) {
-                for (i, f) in self.stack.iter().enumerate() {
-                    if f.original_pc_offset == frame.original_pc_offset {
-                        return i == 0; // It's an exit if it's the outermost block
-                    }
-                }
-            }
-        }
-
-        false
-    }
-
-    /// Mark frames as potentially having early returns
-    pub fn mark_frames_with_early_return(&mut self, depth: u32) {
-        let target_idx = if depth as usize >= self.depth() {
-            // If targeting beyond the stack, mark all frames
-            0 // Start from the first frame
-        } else {
-            // Find the target frame index
-            let mut target_idx = self.depth();
-            let mut current_depth = 0;
-
-            for (i, frame) in self.stack.iter().rev().enumerate() {
-                if frame.ty == ControlStackFrameType::Else {
-                    continue;
-                }
-
-                if current_depth == depth {
-                    target_idx = self.depth() - 1 - i;
-                    break;
-                }
-
-                current_depth += 1;
-            }
-
-            target_idx
-        };
-
-        // Mark frames from target to the end
-        for i in target_idx..self.depth() {
-            self.stack[i].set_might_return_early(true);
-        }
-    }
-
     /// Get the return type of the control stack frame at given depth.
     pub fn ret_ty(&self, depth: usize) -> Result<BlockType> {
         if depth == 0 {
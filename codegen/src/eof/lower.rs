@@ -0,0 +1,329 @@
+//! Lowering of the legacy bytecode to EOF code sections.
+
+use crate::{
+    codegen::ExtFunc,
+    eof::{Section, NON_RETURNING},
+    jump::Jump,
+    Error, JumpTable, Result,
+};
+use opcodes::{OpCode as _, Prague as OpCode};
+use std::collections::BTreeMap;
+
+/// Opcodes rejected by the validation of EOF.
+const DISABLED: [OpCode; 16] = [
+    OpCode::CODESIZE,
+    OpCode::CODECOPY,
+    OpCode::EXTCODESIZE,
+    OpCode::EXTCODECOPY,
+    OpCode::EXTCODEHASH,
+    OpCode::JUMP,
+    OpCode::JUMPI,
+    OpCode::PC,
+    OpCode::GAS,
+    OpCode::CREATE,
+    OpCode::CALL,
+    OpCode::CALLCODE,
+    OpCode::DELEGATECALL,
+    OpCode::CREATE2,
+    OpCode::STATICCALL,
+    OpCode::SELFDESTRUCT,
+];
+
+/// Destination of a relative jump.
+enum Target {
+    /// A program counter of the legacy bytecode.
+    Pc(u16),
+    /// A stub appended to the code section.
+    Stub(usize),
+}
+
+/// Lowers the legacy bytecode of the functions to code sections.
+pub struct Lower<'l> {
+    /// Code sections of the functions.
+    pub funcs: &'l BTreeMap<u32, u16>,
+    /// Code sections of the functions in the code section.
+    pub exts: &'l [(ExtFunc, u16)],
+    /// Inputs and outputs of the code sections.
+    pub types: &'l [(u8, u8)],
+}
+
+impl Lower<'_> {
+    /// Lower the bytecode of a function.
+    ///
+    /// - jumps to labels turn into `RJUMP` and `RJUMPI`.
+    /// - calls turn into `CALLF`, return addresses into `PUSH0` which
+    ///   keeps the layout of the stack, the `JUMP` back to the return
+    ///   address into `POP RETF`.
+    /// - conditional jumps to functions and traps branch to stubs
+    ///   appended to the code, which `JUMPF` or halt.
+    /// - jump destinations are dropped.
+    pub fn code(&self, buffer: &[u8], table: &JumpTable, returning: bool) -> Result<Vec<u8>> {
+        let instrs = instrs(buffer);
+        let mut code = Vec::with_capacity(buffer.len());
+        let mut pcs = BTreeMap::new();
+        let mut fixups: Vec<(usize, Target)> = Vec::new();
+        let mut stubs: Vec<Vec<u8>> = Vec::new();
+        let mut terminated = false;
+
+        let mut index = 0;
+        while let Some((pc, instr)) = instrs.get(index).copied() {
+            index += 1;
+            pcs.insert(pc, code.len());
+
+            let op = OpCode::from(instr[0]);
+            if let Some(jump) = table.jump.get(&pc) {
+                let relative = match (jump, op) {
+                    (Jump::Label(target), OpCode::JUMP | OpCode::JUMPI) => {
+                        Some((op == OpCode::JUMP, Target::Pc(*target)))
+                    }
+                    (Jump::Func(func), OpCode::JUMPI) => {
+                        let section = self.func(*func)?;
+                        stubs.push(self.call(section).to_vec());
+                        Some((false, Target::Stub(stubs.len() - 1)))
+                    }
+                    (Jump::Func(func), OpCode::JUMP) => {
+                        let call = self.call(self.func(*func)?);
+                        terminated = call[0] == OpCode::JUMPF.into();
+                        code.extend(call);
+                        continue;
+                    }
+                    (Jump::ExtFunc(ext), OpCode::JUMP) => {
+                        let call = self.call(self.ext(ext)?);
+                        terminated = call[0] == OpCode::JUMPF.into();
+                        code.extend(call);
+                        continue;
+                    }
+                    (Jump::Entry(_), _) => {
+                        return Err(Error::Eof(format!("computed jump at 0x{pc:x}")));
+                    }
+                    (Jump::Data(index), _) => {
+                        return Err(Error::Eof(format!("data segment {index} at 0x{pc:x}")));
+                    }
+                    // the return address of a call.
+                    _ => {
+                        code.push(OpCode::PUSH0.into());
+                        None
+                    }
+                };
+
+                if let Some((unconditional, target)) = relative {
+                    code.push(if unconditional {
+                        OpCode::RJUMP.into()
+                    } else {
+                        OpCode::RJUMPI.into()
+                    });
+                    fixups.push((code.len(), target));
+                    code.extend([0, 0]);
+                    terminated = unconditional;
+                    continue;
+                }
+            }
+
+            match op {
+                OpCode::JUMPDEST => continue,
+                OpCode::JUMP if returning => {
+                    code.extend([u8::from(OpCode::POP), OpCode::RETF.into()]);
+                    terminated = true;
+                    continue;
+                }
+                // trap, `PC JUMPI`
+                OpCode::PC
+                    if instrs.get(index).map(|(_, instr)| instr[0])
+                        == Some(OpCode::JUMPI.into()) =>
+                {
+                    index += 1;
+                    stubs.push(vec![OpCode::INVALID.into()]);
+                    code.push(OpCode::RJUMPI.into());
+                    fixups.push((code.len(), Target::Stub(stubs.len() - 1)));
+                    code.extend([0, 0]);
+                }
+                op if DISABLED.contains(&op) => {
+                    return Err(Error::Eof(format!("{op:?} at 0x{pc:x}")));
+                }
+                _ => code.extend_from_slice(instr),
+            }
+
+            terminated = is_terminating(op);
+        }
+
+        // jumps to the end of the code land on a trap.
+        let end = code.len();
+        pcs.insert(buffer.len() as u16, end);
+        let to_end = fixups
+            .iter()
+            .any(|(_, target)| matches!(target, Target::Pc(pc) if pcs.get(pc) == Some(&end)));
+        if !terminated || to_end {
+            code.push(OpCode::INVALID.into());
+        }
+
+        let mut offsets = Vec::with_capacity(stubs.len());
+        for stub in stubs {
+            offsets.push(code.len());
+            code.extend(stub);
+        }
+
+        for (pos, target) in fixups {
+            let dest = match target {
+                Target::Pc(pc) => *pcs
+                    .get(&pc)
+                    .ok_or_else(|| Error::Eof(format!("jump into immediates at 0x{pc:x}")))?,
+                Target::Stub(stub) => offsets[stub],
+            };
+
+            let offset = i16::try_from(dest as isize - (pos as isize + 2))
+                .map_err(|_| Error::Eof(format!("relative jump from 0x{pos:x} to 0x{dest:x}")))?;
+            code[pos..pos + 2].copy_from_slice(&offset.to_be_bytes());
+        }
+
+        Ok(code)
+    }
+
+    /// The bytecode calling the code section.
+    fn call(&self, section: u16) -> [u8; 3] {
+        let op = if self.types[section as usize].1 == NON_RETURNING {
+            OpCode::JUMPF
+        } else {
+            OpCode::CALLF
+        };
+
+        let [hi, lo] = section.to_be_bytes();
+        [op.into(), hi, lo]
+    }
+
+    /// Get the code section of a function.
+    fn func(&self, func: u32) -> Result<u16> {
+        self.funcs
+            .get(&func)
+            .copied()
+            .ok_or(Error::FuncNotFound(func))
+    }
+
+    /// Get the code section of a function in the code section.
+    fn ext(&self, ext: &ExtFunc) -> Result<u16> {
+        self.exts
+            .iter()
+            .find(|(func, _)| func == ext)
+            .map(|(_, section)| *section)
+            .ok_or(Error::ExtFuncNotFound)
+    }
+}
+
+/// Calculate the maximum stack height of a code section, in the same
+/// way of the validation of EOF.
+///
+/// The stack heights only flow forwards, the backward jumps are
+/// expected to be balanced.
+pub fn max_stack_height(section: &Section, types: &[(u8, u8)]) -> Result<u16> {
+    let code = &section.code;
+    let mut heights = vec![i32::MIN; code.len()];
+    let mut next = section.inputs as i32;
+    let mut terminated = false;
+    let mut max = 0;
+
+    let mut pc = 0;
+    while pc < code.len() {
+        if !terminated {
+            heights[pc] = heights[pc].max(next);
+        }
+
+        let height = heights[pc];
+        max = max.max(height);
+
+        let op = OpCode::from(code[pc]);
+        let immediate = |at: usize| -> Result<usize> {
+            code.get(pc + at..pc + at + 2)
+                .map(|bytes| u16::from_be_bytes([bytes[0], bytes[1]]) as usize)
+                .ok_or_else(|| Error::Eof(format!("missing immediates at 0x{pc:x}")))
+        };
+
+        let mut targets = Vec::new();
+        let (diff, size) = match op {
+            OpCode::RJUMP | OpCode::RJUMPI => {
+                let offset = immediate(1)? as i16 as isize;
+                targets.push(pc as isize + 3 + offset);
+                (-((op == OpCode::RJUMPI) as i32), 3)
+            }
+            OpCode::RJUMPV => {
+                let len = *code
+                    .get(pc + 1)
+                    .ok_or_else(|| Error::Eof(format!("missing immediates at 0x{pc:x}")))?
+                    as usize
+                    + 1;
+                let size = 2 + len * 2;
+                for entry in 0..len {
+                    let offset = immediate(2 + entry * 2)? as i16 as isize;
+                    targets.push((pc + size) as isize + offset);
+                }
+                (-1, size)
+            }
+            OpCode::CALLF => {
+                let (inputs, outputs) = types
+                    .get(immediate(1)?)
+                    .ok_or_else(|| Error::Eof(format!("invalid code section at 0x{pc:x}")))?;
+                (*outputs as i32 - *inputs as i32, 3)
+            }
+            OpCode::JUMPF | OpCode::DATALOADN => (op.stack_out() as i32, 3),
+            OpCode::DUPN => (1, 2),
+            OpCode::SWAPN | OpCode::EXCHANGE | OpCode::EOFCREATE | OpCode::RETURNCONTRACT => {
+                (op.stack_out() as i32 - op.stack_in() as i32, 2)
+            }
+            _ => {
+                let byte = u8::from(op);
+                let pushed = if (0x60..=0x7f).contains(&byte) {
+                    (byte - 0x5f) as usize
+                } else {
+                    0
+                };
+                (op.stack_out() as i32 - op.stack_in() as i32, 1 + pushed)
+            }
+        };
+
+        next = height.saturating_add(diff);
+        for target in targets {
+            if target > pc as isize && (target as usize) < code.len() {
+                let target = &mut heights[target as usize];
+                *target = (*target).max(next);
+            }
+        }
+
+        terminated = is_terminating(op);
+        pc += size;
+    }
+
+    u16::try_from(max).map_err(|_| Error::Eof(format!("invalid max stack height {max}")))
+}
+
+/// If the opcode terminates the execution of the code section.
+fn is_terminating(op: OpCode) -> bool {
+    matches!(
+        op,
+        OpCode::STOP
+            | OpCode::RETURN
+            | OpCode::REVERT
+            | OpCode::INVALID
+            | OpCode::SELFDESTRUCT
+            | OpCode::RJUMP
+            | OpCode::RETF
+            | OpCode::JUMPF
+            | OpCode::RETURNCONTRACT
+    )
+}
+
+/// Split the legacy bytecode into instructions with their program
+/// counters.
+fn instrs(buffer: &[u8]) -> Vec<(u16, &[u8])> {
+    let mut instrs = Vec::new();
+    let mut pc = 0;
+    while pc < buffer.len() {
+        let immediates = match buffer[pc] {
+            op @ 0x60..=0x7f => (op - 0x5f) as usize,
+            _ => 0,
+        };
+
+        let end = (pc + 1 + immediates).min(buffer.len());
+        instrs.push((pc as u16, &buffer[pc..end]));
+        pc = end;
+    }
+
+    instrs
+}
@@ -0,0 +1,276 @@
+//! EVM Object Format (EOF)
+//!
+//! Lowers the functions compiled for the legacy bytecode to the code
+//! sections of an EOF container. The jumps registered in the jump tables
+//! are resolved to relative jumps and the calls to `CALLF`/`RETF`, the
+//! relocation of the jump table is not needed at all.
+
+use crate::{codegen::ExtFunc, jump::Jump, Buffer, Error, JumpTable, Result};
+use lower::Lower;
+use std::collections::BTreeMap;
+
+mod lower;
+
+/// The magic and the version of EOF containers.
+const MAGIC: [u8; 3] = [0xef, 0x00, 0x01];
+
+/// The outputs of non-returning code sections.
+pub const NON_RETURNING: u8 = 0x80;
+
+/// Kinds of the sections in the header.
+const KIND_TYPES: u8 = 0x01;
+const KIND_CODE: u8 = 0x02;
+const KIND_CONTAINER: u8 = 0x03;
+const KIND_DATA: u8 = 0x04;
+
+/// A code section of the container.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Section {
+    /// Stack items taken by the section.
+    pub inputs: u8,
+    /// Stack items returned by the section, `0x80` if it never returns.
+    pub outputs: u8,
+    /// Maximum stack height of the section.
+    pub max_stack_height: u16,
+    /// The bytecode of the section.
+    pub code: Vec<u8>,
+}
+
+/// EOF container.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Container {
+    /// Code sections, the first one is the entry.
+    pub sections: Vec<Section>,
+    /// Subcontainers.
+    pub containers: Vec<Vec<u8>>,
+    /// The data section.
+    pub data: Vec<u8>,
+}
+
+impl Container {
+    /// Create the initcode container which deploys the runtime container.
+    ///
+    /// The code runs before `RETURNCONTRACT`, it should leave nothing on
+    /// the stack.
+    pub fn initcode(code: &[u8], runtime: Vec<u8>) -> Result<Self> {
+        let mut section = Section {
+            inputs: 0,
+            outputs: NON_RETURNING,
+            max_stack_height: 0,
+            code: code.into(),
+        };
+        // PUSH0 PUSH0 RETURNCONTRACT 0
+        section.code.extend_from_slice(&[0x5f, 0x5f, 0xee, 0x00]);
+        section.max_stack_height = lower::max_stack_height(&section, &[])?;
+
+        Ok(Self {
+            sections: vec![section],
+            containers: vec![runtime],
+            data: Default::default(),
+        })
+    }
+
+    /// Encode the container to bytes.
+    pub fn encode(&self) -> Result<Vec<u8>> {
+        let size = |len: usize| -> Result<[u8; 2]> {
+            u16::try_from(len)
+                .map(u16::to_be_bytes)
+                .map_err(|_| Error::Eof(format!("section size {len} exceeds 0xffff")))
+        };
+
+        let mut bytes = MAGIC.to_vec();
+        bytes.push(KIND_TYPES);
+        bytes.extend(size(self.sections.len() * 4)?);
+
+        bytes.push(KIND_CODE);
+        bytes.extend(size(self.sections.len())?);
+        for section in &self.sections {
+            bytes.extend(size(section.code.len())?);
+        }
+
+        if !self.containers.is_empty() {
+            bytes.push(KIND_CONTAINER);
+            bytes.extend(size(self.containers.len())?);
+            for container in &self.containers {
+                bytes.extend(size(container.len())?);
+            }
+        }
+
+        bytes.push(KIND_DATA);
+        bytes.extend(size(self.data.len())?);
+        bytes.push(0);
+
+        for section in &self.sections {
+            bytes.push(section.inputs);
+            bytes.push(section.outputs);
+            bytes.extend(section.max_stack_height.to_be_bytes());
+        }
+
+        for section in &self.sections {
+            bytes.extend_from_slice(&section.code);
+        }
+
+        for container in &self.containers {
+            bytes.extend_from_slice(container);
+        }
+
+        bytes.extend_from_slice(&self.data);
+        Ok(bytes)
+    }
+}
+
+/// Code compiled for the legacy bytecode with its jump table.
+struct Unit {
+    /// The bytecode before relocation.
+    buffer: Buffer,
+    /// The jump table of the bytecode.
+    table: JumpTable,
+    /// Results of the function, `None` if it never returns.
+    results: Option<u8>,
+}
+
+/// Keys of the code sections.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+enum Key {
+    /// The entry of the contract.
+    Entry,
+    /// A function of the module.
+    Func(u32),
+    /// A function of the code section.
+    Ext(ExtFunc),
+}
+
+/// EOF container emitter.
+///
+/// Only the code sections reachable from the entry are emitted, since
+/// the validation of EOF rejects code sections never accessed.
+#[derive(Default)]
+pub struct Eof {
+    /// The dispatcher or the main function.
+    entry: Option<Unit>,
+    /// The functions of the module.
+    funcs: BTreeMap<u32, Unit>,
+}
+
+impl Eof {
+    /// Set the entry of the contract, the dispatcher or the main function.
+    pub fn entry(&mut self, buffer: Buffer, table: JumpTable) {
+        self.entry = Some(Unit {
+            buffer,
+            table,
+            results: None,
+        });
+    }
+
+    /// Add a function, `results` is `None` if the function never returns.
+    pub fn func(&mut self, index: u32, buffer: Buffer, table: JumpTable, results: Option<u8>) {
+        self.funcs.insert(
+            index,
+            Unit {
+                buffer,
+                table,
+                results,
+            },
+        );
+    }
+
+    /// Emit the container.
+    pub fn finish(self) -> Result<Container> {
+        let entry = self
+            .entry
+            .as_ref()
+            .ok_or_else(|| Error::Eof("no entry".into()))?;
+
+        // collect the reachable code sections in order.
+        let mut keys = vec![Key::Entry];
+        let mut index = 0;
+        while let Some(key) = keys.get(index).cloned() {
+            index += 1;
+            let unit = match key {
+                Key::Entry => entry,
+                Key::Func(func) => self.funcs.get(&func).ok_or(Error::FuncNotFound(func))?,
+                Key::Ext(_) => continue,
+            };
+
+            for jump in unit.table.jump.values() {
+                let key = match jump {
+                    Jump::Func(func) => Key::Func(*func),
+                    Jump::ExtFunc(ext) => Key::Ext(ext.clone()),
+                    _ => continue,
+                };
+
+                if !keys.contains(&key) {
+                    keys.push(key);
+                }
+            }
+        }
+
+        if keys.len() > 1024 {
+            return Err(Error::Eof(format!(
+                "{} code sections exceed 1024",
+                keys.len()
+            )));
+        }
+
+        // the types of the code sections, returning functions take the
+        // placeholder of the return address.
+        let mut types = Vec::with_capacity(keys.len());
+        let mut funcs = BTreeMap::new();
+        let mut exts = Vec::new();
+        for (index, key) in keys.iter().enumerate() {
+            let io = match key {
+                Key::Entry => (0, NON_RETURNING),
+                Key::Func(func) => {
+                    funcs.insert(*func, index as u16);
+                    match self.funcs[func].results {
+                        Some(results) => (1, results),
+                        None => (0, NON_RETURNING),
+                    }
+                }
+                Key::Ext(ext) => {
+                    exts.push((ext.clone(), index as u16));
+                    if ext.returning {
+                        (ext.stack_in + 1, ext.stack_out)
+                    } else {
+                        (ext.stack_in, NON_RETURNING)
+                    }
+                }
+            };
+
+            types.push(io);
+        }
+
+        let lower = Lower {
+            funcs: &funcs,
+            exts: &exts,
+            types: &types,
+        };
+
+        let mut sections = Vec::with_capacity(keys.len());
+        for (key, (inputs, outputs)) in keys.iter().zip(types.iter()) {
+            let code = match key {
+                Key::Entry => lower.code(&entry.buffer, &entry.table, false)?,
+                Key::Func(func) => {
+                    let unit = &self.funcs[func];
+                    lower.code(&unit.buffer, &unit.table, unit.results.is_some())?
+                }
+                Key::Ext(ext) => lower.code(&ext.bytecode, &JumpTable::default(), ext.returning)?,
+            };
+
+            let mut section = Section {
+                inputs: *inputs,
+                outputs: *outputs,
+                max_stack_height: 0,
+                code,
+            };
+            section.max_stack_height = lower::max_stack_height(&section, &types)?;
+            sections.push(section);
+        }
+
+        Ok(Container {
+            sections,
+            containers: Default::default(),
+            data: Default::default(),
+        })
+    }
+}
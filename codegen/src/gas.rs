@@ -0,0 +1,92 @@
+//! Static gas estimation.
+
+use std::{collections::BTreeMap, ops::Range};
+
+/// Static gas estimation of a function.
+///
+/// The gas is the base gas of the opcodes, the dynamic costs, e.g.
+/// of the storage, are not included.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Gas {
+    /// Base gas of the code, only the more expensive branch of each
+    /// if-else is counted, loops are counted once.
+    pub fixed: u64,
+    /// Base gas of one iteration of each loop.
+    pub loops: Vec<u64>,
+    /// The highest memory offset, the statically allocated memory or
+    /// the whole linear memory and heap once they are accessed.
+    pub memory: usize,
+    /// Internal functions called, one for each call site.
+    pub calls: Vec<u32>,
+    /// Length of the code.
+    pub size: u16,
+    /// Program counters of each loop, in the order of `loops`.
+    pub loop_ranges: Vec<Range<u16>>,
+    /// Both branches of each if-else, the inner ones first.
+    pub branches: Vec<[GasBlock; 2]>,
+}
+
+/// Base gas of the code between two program counters.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct GasBlock {
+    /// Program counters of the code.
+    pub range: Range<u16>,
+    /// Base gas of the code.
+    pub gas: u64,
+}
+
+impl GasBlock {
+    /// Subtract the gas saved by the peephole optimizer, the code
+    /// starts at `pc`.
+    pub fn optimize(&mut self, pc: u16, saved: &BTreeMap<u16, u64>) {
+        self.gas -= saved
+            .range(self.range.start + pc..self.range.end + pc)
+            .map(|(_, gas)| gas)
+            .sum::<u64>();
+    }
+}
+
+impl Gas {
+    /// Subtract the gas saved by the peephole optimizer.
+    ///
+    /// `saved` is the gas saved at the program counters of the code
+    /// before the optimization, where the function starts at `pc`.
+    pub fn optimize(&mut self, pc: u16, saved: &BTreeMap<u16, u64>) {
+        let saved_in = |range: &Range<u16>| -> u64 {
+            saved
+                .range(range.start + pc..range.end + pc)
+                .map(|(_, gas)| gas)
+                .sum()
+        };
+
+        // the cheaper branch of each if-else is uncounted, which may
+        // change with the savings in the branches, `refunds` are the
+        // changes of the uncounted gas.
+        let mut refunds: Vec<(u16, u64)> = Vec::new();
+        let refund_in = |refunds: &[(u16, u64)], range: &Range<u16>| -> u64 {
+            refunds
+                .iter()
+                .filter(|(pc, _)| range.contains(pc))
+                .map(|(_, gas)| gas)
+                .sum()
+        };
+
+        for branches in self.branches.iter_mut() {
+            let uncounted = branches[0].gas.min(branches[1].gas);
+            for block in branches.iter_mut() {
+                block.gas -= saved_in(&block.range) - refund_in(&refunds, &block.range);
+            }
+
+            refunds.push((
+                branches[0].range.start,
+                uncounted - branches[0].gas.min(branches[1].gas),
+            ));
+        }
+
+        for (gas, range) in self.loops.iter_mut().zip(self.loop_ranges.iter()) {
+            *gas -= saved_in(range) - refund_in(&refunds, range);
+        }
+
+        self.fixed -= saved_in(&(0..self.size)) - refund_in(&refunds, &(0..self.size));
+    }
+}
@@ -18,18 +18,28 @@ mod target;
 pub enum Jump {
     /// Jump to a specific label, which corresponds to the original program counter.
     Label(u16),
+    /// Jump to a label from an entry of a computed jump table.
+    ///
+    /// Always relocated with `PUSH2`, so all entries of the table
+    /// share the same size.
+    Entry(u16),
     /// Jump to a function identified by its index.
     Func(u32),
     /// Jump to an external function.
     ExtFunc(ExtFunc),
+    /// Offset of a data segment in the code section, which is pushed
+    /// for `CODECOPY` instead of jumping to.
+    Data(u32),
 }
 
 impl Display for Jump {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         match self {
             Jump::Label(offset) => write!(f, "Label(0x{offset:x})"),
+            Jump::Entry(offset) => write!(f, "Entry(0x{offset:x})"),
             Jump::Func(index) => write!(f, "Func({index})"),
             Jump::ExtFunc(_) => write!(f, "ExtFunc"),
+            Jump::Data(index) => write!(f, "Data({index})"),
         }
     }
 }
@@ -37,7 +47,7 @@ impl Display for Jump {
 impl Jump {
     /// Checks if the target is a label.
     pub fn is_label(&self) -> bool {
-        matches!(self, Jump::Label { .. })
+        matches!(self, Jump::Label { .. } | Jump::Entry { .. })
     }
 
     /// Checks if the target is a function call.
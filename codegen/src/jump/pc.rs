@@ -3,7 +3,11 @@
 //! This module provides functionality to shift the program counter for various jump types
 //! and manage the relationships between labels and their corresponding program counters.
 
-use crate::{jump::JumpTable, Error, Result, BUFFER_LIMIT};
+use crate::{
+    jump::{Jump, JumpTable},
+    Error, Result, BUFFER_LIMIT,
+};
+use std::collections::BTreeSet;
 
 impl JumpTable {
     /// Shifts the program counter for all jump items.
@@ -43,4 +47,49 @@ impl JumpTable {
         self.jump = new_jump.into_iter().collect();
         Ok(())
     }
+
+    /// Maps all of the program counters with `f`.
+    ///
+    /// The mapping should keep the order of the program counters, the
+    /// empty ranges of sources are removed.
+    pub fn remap(&mut self, f: impl Fn(u16) -> u16) {
+        self.jump = std::mem::take(&mut self.jump)
+            .into_iter()
+            .map(|(pc, jump)| {
+                let jump = match jump {
+                    Jump::Label(target) => Jump::Label(f(target)),
+                    Jump::Entry(target) => Jump::Entry(f(target)),
+                    jump => jump,
+                };
+
+                (f(pc), jump)
+            })
+            .collect();
+
+        self.func.values_mut().for_each(|pc| *pc = f(*pc));
+        for source in self.sources.iter_mut() {
+            source.start = f(source.start);
+            source.end = f(source.end);
+        }
+        self.sources.retain(|source| source.start < source.end);
+    }
+
+    /// Get the program counters targeted by the jumps.
+    pub fn targets(&self) -> BTreeSet<u16> {
+        self.jump
+            .values()
+            .filter_map(|jump| match jump {
+                Jump::Label(target) | Jump::Entry(target) => Some(*target),
+                _ => None,
+            })
+            .chain(self.func.values().copied())
+            .collect()
+    }
+
+    /// Shifts the program counter ranges of sources.
+    pub fn shift_source_pc(&mut self, start: u16, offset: u16) {
+        for source in self.sources.iter_mut() {
+            source.shift(start, offset);
+        }
+    }
 }
@@ -6,7 +6,7 @@
 //! point to the correct addresses after any modifications to the code section.
 
 use crate::{
-    jump::{relocate, JumpTable},
+    jump::{relocate, Jump, JumpTable},
     wasm::ToLSBytes,
     Buffer, Error, Result, BUFFER_LIMIT,
 };
@@ -46,8 +46,10 @@ impl JumpTable {
             );
 
             // Update the buffer with the new target program counter.
-            let offset = relocate::pc(buffer, pc, target)?;
+            let wide = matches!(jump, Jump::Entry(_));
+            let offset = relocate::pc(buffer, pc, target, wide)?;
             self.shift_label_pc(pc, offset as u16)?;
+            self.shift_source_pc(pc, offset as u16);
         }
 
         // Extend the buffer with the finished code section.
@@ -62,14 +64,19 @@ impl JumpTable {
 /// counter, and updates the provided buffer with the necessary opcode
 /// instructions. It ensures that the buffer does not exceed the defined
 /// size limit and handles the conversion of the target program counter
-/// to the appropriate byte representation.
-fn pc(buffer: &mut Buffer, original_pc: u16, target_pc: u16) -> Result<usize> {
+/// to the appropriate byte representation, `wide` forces `PUSH2`.
+fn pc(buffer: &mut Buffer, original_pc: u16, target_pc: u16, wide: bool) -> Result<usize> {
     let original_pc = original_pc as usize;
     let mut new_buffer: Buffer = buffer[..original_pc].into();
     let rest_buffer: Buffer = buffer[original_pc..].into();
 
     // Convert the target program counter to its byte representation.
-    let target = target_pc.to_ls_bytes();
+    let target = if wide {
+        target_pc.to_be_bytes().into()
+    } else {
+        target_pc.to_ls_bytes()
+    };
+
     if target.len() == 1 {
         new_buffer.push(OpCode::PUSH1.into());
     } else {
@@ -4,7 +4,7 @@
 //! table, and code section. It provides methods to register jumps, functions, and
 //! labels, as well as to merge jump tables.
 
-use crate::{codegen::ExtFunc, jump::Jump, Code, Error, Result};
+use crate::{backtrace::Source, codegen::ExtFunc, jump::Jump, Code, Error, Result};
 use std::collections::BTreeMap;
 
 /// Jump table implementation.
@@ -16,6 +16,8 @@ pub struct JumpTable {
     pub(crate) func: BTreeMap<u32, u16>,
     /// Code section associated with the jump table.
     pub(crate) code: Code,
+    /// Sources of the program counter ranges.
+    pub(crate) sources: Vec<Source>,
 }
 
 impl JumpTable {
@@ -48,11 +50,34 @@ impl JumpTable {
         self.jump.insert(pc, Jump::ExtFunc(func));
     }
 
+    /// Registers the offset of a data segment in the jump table.
+    pub fn data(&mut self, pc: u16, index: u32, data: Vec<u8>) {
+        self.code.try_add_data(index, data);
+        self.jump.insert(pc, Jump::Data(index));
+    }
+
     /// Registers a label in the jump table.
     pub fn label(&mut self, pc: u16, label: u16) {
         self.jump.insert(pc, Jump::Label(label));
     }
 
+    /// Registers the source of a range of program counters.
+    pub fn source(&mut self, source: Source) {
+        if source.start < source.end {
+            self.sources.push(source);
+        }
+    }
+
+    /// Get the sources of the program counter ranges.
+    pub fn sources(&self) -> &[Source] {
+        &self.sources
+    }
+
+    /// Registers a label for an entry of a computed jump table.
+    pub fn entry(&mut self, pc: u16, label: u16) {
+        self.jump.insert(pc, Jump::Entry(label));
+    }
+
     /// Merges another jump table into this one.
     ///
     /// This function updates the program counters of the target jump table and
@@ -78,6 +103,16 @@ impl JumpTable {
             self.code.try_add_func(func);
         }
 
+        for (index, data) in table.code.data() {
+            self.code.try_add_data(index, data);
+        }
+
+        for mut source in table.sources.into_iter() {
+            source.start += pc;
+            source.end += pc;
+            self.sources.push(source);
+        }
+
         Ok(())
     }
 
@@ -89,8 +124,8 @@ impl JumpTable {
     /// Get the max target from the current jump table
     pub fn max_target(&self) -> u16 {
         self.jump
-            .iter()
-            .filter_map(|(_, jump)| self.target(jump).ok())
+            .values()
+            .filter_map(|jump| self.target(jump).ok())
             .max()
             .unwrap_or(0)
     }
@@ -15,9 +15,13 @@ impl JumpTable {
     /// (offset, label, function, or external function).
     pub fn target(&self, jump: &Jump) -> Result<u16> {
         match jump {
-            Jump::Label(label) => Ok(*label),
+            Jump::Label(label) | Jump::Entry(label) => Ok(*label),
             Jump::Func(func) => Ok(*self.func.get(func).ok_or(Error::FuncNotFound(*func))?),
             Jump::ExtFunc(ext) => Ok(self.code.offset_of(ext).ok_or(Error::ExtFuncNotFound)?),
+            Jump::Data(index) => Ok(self
+                .code
+                .offset_of_data(*index)
+                .ok_or(Error::SegmentNotFound(*index))?),
         }
     }
 
@@ -43,7 +47,7 @@ impl JumpTable {
             };
 
             // Calculate instruction size based on absolute target value
-            let instr_size = if target > 0xff {
+            let instr_size = if target > 0xff || matches!(jump, Jump::Entry(_)) {
                 3 // PUSH2 + 2 bytes
             } else {
                 2 // PUSH1 + 1 byte
@@ -54,11 +58,9 @@ impl JumpTable {
         }
 
         // Second pass: apply shifts with accumulated offsets
-        total_offset = 0;
         for (pc, size) in target_sizes {
             tracing::debug!("shift target at pc=0x{pc:x} with size={size}");
             self.shift_target(pc, size)?;
-            total_offset += size;
         }
 
         Ok(())
@@ -97,7 +99,7 @@ impl JumpTable {
     /// Shifts the program counter for labels.
     pub fn shift_label_target(&mut self, ptr: u16, offset: u16) -> Result<()> {
         for (_, jump) in self.jump.iter_mut() {
-            let Jump::Label(target) = jump else {
+            let (Jump::Label(target) | Jump::Entry(target)) = jump else {
                 continue;
             };
 
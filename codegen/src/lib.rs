@@ -4,22 +4,29 @@
 
 pub use crate::{
     asm::Assembler,
-    codegen::{Code, Constructor, Dispatcher, Function, InitStorage},
+    backtrace::Source,
+    codegen::{Code, Constructor, DispatchStrategy, Dispatcher, Function, InitStorage, Prologue},
     control::{ControlStack, ControlStackFrame, ControlStackFrameType},
+    gas::{Gas, GasBlock},
     jump::JumpTable,
     local::{LocalSlot, Locals},
     masm::MacroAssembler,
+    peephole::{Peephole, Rule, Stats},
     result::{Error, Result},
 };
+pub use opcodes::Upgrade;
 use smallvec::SmallVec;
 
 mod asm;
 mod backtrace;
 mod codegen;
 mod control;
+pub mod eof;
+mod gas;
 mod jump;
 mod local;
 mod masm;
+mod peephole;
 mod result;
 mod validator;
 mod visitor;
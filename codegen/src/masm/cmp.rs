@@ -6,50 +6,34 @@ use opcodes::Cancun as OpCode;
 impl MacroAssembler {
     /// Greater than or equal comparison.
     ///
-    /// a b ge -> a b-1 gt(lt)
-    ///
-    /// Using lt due to order of stack.
+    /// a b ge -> !(a < b), using gt due to order of stack.
     pub fn _ge(&mut self) -> Result<()> {
-        self.push(&[1])?;
-        // NOTE: this is the overridden sub but not `self.asm.sub`
-        self._sub()?;
-        self.asm._lt()
+        self.asm._gt()?;
+        self._iszero()
     }
 
-    /// Greater than or equal comparison.
+    /// Signed greater than or equal comparison.
     ///
-    /// a b sge -> a b-1 sgt(slt)
-    ///
-    /// Using lt due to order of stack.
+    /// a b sge -> !(a < b), using sgt due to order of stack.
     pub fn _sge(&mut self) -> Result<()> {
-        self.push(&[1])?;
-        // NOTE: this is the overridden sub but not `self.asm.sub`
-        self._sub()?;
-        self.asm._slt()
+        self.asm._sgt()?;
+        self._iszero()
     }
 
-    /// Greater than or equal comparison.
+    /// Signed less than or equal comparison.
     ///
-    /// a b sge -> a b-1 sgt(slt)
-    ///
-    /// Using lt due to order of stack.
+    /// a b sle -> !(a > b), using slt due to order of stack.
     pub fn _sle(&mut self) -> Result<()> {
-        self.push(&[1])?;
-        // NOTE: this is the overridden sub but not `self.asm.sub`
-        self._sub()?;
-        self.asm._slt()
+        self.asm._slt()?;
+        self._iszero()
     }
 
-    /// Greater than or equal comparison.
+    /// Less than or equal comparison.
     ///
-    /// a b le -> a b-1 lt(gt)
-    ///
-    /// Using gt due to order of stack.
+    /// a b le -> !(a > b), using lt due to order of stack.
     pub fn _le(&mut self) -> Result<()> {
-        self.push(&[1])?;
-        // NOTE: this is the overridden sub but not `self.asm.sub`
-        self._sub()?;
-        self.asm._lt()
+        self.asm._lt()?;
+        self._iszero()
     }
 
     /// Greater than and equal comparison.
@@ -1,76 +1,841 @@
 //! Float Instructions
+//!
+//! Floats are kept as their IEEE-754 bit patterns on the stack. The
+//! operators only touching the sign bit are emitted inline, the others
+//! are the bodies of the soft float functions in the code section, see
+//! `Soft` for the assembler of them.
+//!
+//! NaN results are always the canonical quiet NaN.
 
-use crate::{MacroAssembler, Result};
+use super::soft::Soft;
+use crate::{wasm::ToLSBytes, MacroAssembler, Result};
+use wasmparser::{Ieee32, Ieee64};
+
+/// IEEE-754 binary float format.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Float {
+    /// Bits of the exponent.
+    exp: u32,
+    /// Bits of the mantissa.
+    man: u32,
+}
+
+impl Float {
+    /// 32-bit float.
+    pub const F32: Self = Self { exp: 8, man: 23 };
+
+    /// 64-bit float.
+    pub const F64: Self = Self { exp: 11, man: 52 };
+
+    /// Bits of the exponent.
+    pub fn exp(&self) -> u32 {
+        self.exp
+    }
+
+    /// Bits of the mantissa.
+    pub fn man(&self) -> u32 {
+        self.man
+    }
+
+    /// Bits of the format.
+    pub fn width(&self) -> u32 {
+        1 + self.exp + self.man
+    }
+
+    /// Exponent bias.
+    pub fn bias(&self) -> u128 {
+        (1 << (self.exp - 1)) - 1
+    }
+
+    /// The sign bit.
+    pub fn sign(&self) -> u128 {
+        1 << (self.width() - 1)
+    }
+
+    /// Mask of the bits except the sign bit.
+    pub fn abs(&self) -> u128 {
+        self.sign() - 1
+    }
+
+    /// Positive infinity.
+    pub fn inf(&self) -> u128 {
+        ((1 << self.exp) - 1) << self.man
+    }
+
+    /// The canonical quiet NaN.
+    pub fn nan(&self) -> u128 {
+        self.inf() | 1 << (self.man - 1)
+    }
+}
+
+/// Rounding of `ceil`, `floor`, `trunc` and `nearest`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Round {
+    /// Towards positive infinity.
+    Ceil,
+    /// Towards negative infinity.
+    Floor,
+    /// Towards zero.
+    Trunc,
+    /// To the nearest, ties to even.
+    Nearest,
+}
 
 impl MacroAssembler {
-    /// Maximum of two values
-    pub fn _max(&mut self) -> Result<()> {
-        todo!()
+    /// Push a 32-bit float value on the stack.
+    pub fn _f32_const(&mut self, value: Ieee32) -> Result<()> {
+        if value.bits() == 0 {
+            self._push0()
+        } else {
+            self.push(value.bits().to_ls_bytes().as_ref())
+        }
     }
 
-    /// Minimum of two values
-    pub fn _min(&mut self) -> Result<()> {
-        todo!()
+    /// Push a 64-bit float value on the stack.
+    pub fn _f64_const(&mut self, value: Ieee64) -> Result<()> {
+        if value.bits() == 0 {
+            self._push0()
+        } else {
+            self.push(value.bits().to_ls_bytes().as_ref())
+        }
     }
 
-    /// Ceiling operator
-    pub fn _ceil(&mut self) -> Result<()> {
-        todo!()
+    /// Reinterpret a 32-bit float as a 32-bit integer.
+    pub fn _i32_reinterpret_f32(&mut self) -> Result<()> {
+        Ok(())
     }
 
-    /// Floor operator
-    pub fn _floor(&mut self) -> Result<()> {
-        todo!()
+    /// Reinterpret a 64-bit float as a 64-bit integer.
+    pub fn _i64_reinterpret_f64(&mut self) -> Result<()> {
+        Ok(())
     }
 
-    /// Round to nearest integer, ties to even.
-    pub fn _nearest(&mut self) -> Result<()> {
-        todo!()
+    /// Reinterpret a 32-bit integer as a 32-bit float.
+    pub fn _f32_reinterpret_i32(&mut self) -> Result<()> {
+        Ok(())
     }
 
-    /// Square root
-    pub fn _sqrt(&mut self) -> Result<()> {
-        todo!()
+    /// Reinterpret a 64-bit integer as a 64-bit float.
+    pub fn _f64_reinterpret_i64(&mut self) -> Result<()> {
+        Ok(())
     }
 
     /// Absolute value
-    pub fn _abs(&mut self) -> Result<()> {
-        todo!()
+    pub fn _fabs(&mut self, f: Float) -> Result<()> {
+        self.push(&(f.abs() as u64).to_ls_bytes())?;
+        self._and()
     }
 
     /// Negation
-    pub fn _neg(&mut self) -> Result<()> {
-        todo!()
+    pub fn _fneg(&mut self, f: Float) -> Result<()> {
+        self.push(&(f.sign() as u64).to_ls_bytes())?;
+        self._xor()
     }
 
     /// If z1 and z2 have the same sign, return z1, otherwise
     /// return z1 with negated sign.
-    pub fn _copysign(&mut self) -> Result<()> {
-        todo!()
+    ///
+    /// STACK: [z1, z2] -> [copysign(z1, z2)]
+    pub fn _fcopysign(&mut self, f: Float) -> Result<()> {
+        self.push(&(f.sign() as u64).to_ls_bytes())?;
+        self._and()?;
+        self._swap1()?;
+        self.push(&(f.abs() as u64).to_ls_bytes())?;
+        self._and()?;
+        self._or()
+    }
+
+    /// Addition
+    ///
+    /// STACK: [a, b] -> [a + b]
+    pub fn _fadd(&mut self, f: Float) -> Result<()> {
+        let mut s = Soft::new(self, &["a", "b"]);
+        add(&mut s, f)?;
+        s.finish()
+    }
+
+    /// Subtraction
+    ///
+    /// STACK: [a, b] -> [a - b]
+    pub fn _fsub(&mut self, f: Float) -> Result<()> {
+        let mut s = Soft::new(self, &["a", "b"]);
+        s.get("b")?;
+        s.num(f.sign())?;
+        s.xor()?;
+        s.set("b")?;
+        add(&mut s, f)?;
+        s.finish()
+    }
+
+    /// Multiplication
+    ///
+    /// STACK: [a, b] -> [a * b]
+    pub fn _fmul(&mut self, f: Float) -> Result<()> {
+        let mut s = Soft::new(self, &["a", "b"]);
+
+        // NaN, or 0 * inf
+        s.isnan(f, "a")?;
+        s.isnan(f, "b")?;
+        s.or()?;
+        s.isinf(f, "a")?;
+        s.iszero_f(f, "b")?;
+        s.and()?;
+        s.or()?;
+        s.iszero_f(f, "a")?;
+        s.isinf(f, "b")?;
+        s.and()?;
+        s.or()?;
+        s.set("nan")?;
+
+        s.sign(f, "a")?;
+        s.sign(f, "b")?;
+        s.xor()?;
+        s.set("sign")?;
+
+        // signed infinity
+        s.isinf(f, "a")?;
+        s.isinf(f, "b")?;
+        s.or()?;
+        s.set("inf")?;
+        special(&mut s, f, "inf", |s| {
+            s.get("sign")?;
+            s.num(f.width() as u128 - 1)?;
+            s.shl()?;
+            s.num(f.inf())?;
+            s.or()
+        })?;
+
+        s.unpack(f, "a", ["sa", "ka", "ma"])?;
+        s.unpack(f, "b", ["sb", "kb", "mb"])?;
+        s.drop_all(&["a", "b", "sa", "sb"])?;
+
+        s.get("ma")?;
+        s.get("mb")?;
+        s.mul()?;
+        s.set("m")?;
+        s.get("ka")?;
+        s.get("kb")?;
+        s.add()?;
+        s.num(f.bias() + f.man() as u128)?;
+        s.sub()?;
+        s.set("k")?;
+        s.drop_all(&["ka", "kb", "ma", "mb"])?;
+
+        s.pack(f, ["sign", "k", "m"])?;
+        resolve(&mut s)?;
+        s.finish()
+    }
+
+    /// Division
+    ///
+    /// STACK: [a, b] -> [a / b]
+    pub fn _fdiv(&mut self, f: Float) -> Result<()> {
+        let mut s = Soft::new(self, &["a", "b"]);
+
+        // NaN, 0 / 0 or inf / inf
+        s.isnan(f, "a")?;
+        s.isnan(f, "b")?;
+        s.or()?;
+        s.isinf(f, "a")?;
+        s.isinf(f, "b")?;
+        s.and()?;
+        s.or()?;
+        s.iszero_f(f, "a")?;
+        s.iszero_f(f, "b")?;
+        s.and()?;
+        s.or()?;
+        s.set("nan")?;
+
+        s.sign(f, "a")?;
+        s.sign(f, "b")?;
+        s.xor()?;
+        s.set("sign")?;
+
+        // signed infinity for inf / b and a / 0, signed zero for
+        // a / inf and 0 / b.
+        s.isinf(f, "a")?;
+        s.iszero_f(f, "b")?;
+        s.or()?;
+        s.set("inf")?;
+        s.get("inf")?;
+        s.isinf(f, "b")?;
+        s.or()?;
+        s.iszero_f(f, "a")?;
+        s.or()?;
+        s.set("zero")?;
+        special(&mut s, f, "zero", |s| {
+            s.get("sign")?;
+            s.num(f.width() as u128 - 1)?;
+            s.shl()?;
+            s.get("inf")?;
+            s.num(f.inf())?;
+            s.mul()?;
+            s.or()
+        })?;
+        s.drop("inf")?;
+
+        s.unpack(f, "a", ["sa", "ka", "ma"])?;
+        s.unpack(f, "b", ["sb", "kb", "mb"])?;
+        s.drop_all(&["a", "b", "sa", "sb"])?;
+
+        // 128 more bits of the quotient, the lowest bit is set if
+        // the division is inexact.
+        s.get("ma")?;
+        s.num(128)?;
+        s.shl()?;
+        s.set("ma")?;
+        s.get("ma")?;
+        s.get("mb")?;
+        s.div()?;
+        s.get("ma")?;
+        s.get("mb")?;
+        s.rem()?;
+        s.nonzero()?;
+        s.or()?;
+        s.set("m")?;
+
+        s.get("ka")?;
+        s.num(f.bias() + f.man() as u128)?;
+        s.add()?;
+        s.get("kb")?;
+        s.sub()?;
+        s.num(128)?;
+        s.sub()?;
+        s.set("k")?;
+        s.drop_all(&["ka", "kb", "ma", "mb"])?;
+
+        s.pack(f, ["sign", "k", "m"])?;
+        resolve(&mut s)?;
+        s.finish()
+    }
+
+    /// Square root
+    ///
+    /// STACK: [a] -> [sqrt(a)]
+    pub fn _fsqrt(&mut self, f: Float) -> Result<()> {
+        let man = f.man() as u128;
+        let mut s = Soft::new(self, &["a"]);
+
+        // NaN, or negative
+        s.isnan(f, "a")?;
+        s.sign(f, "a")?;
+        s.iszero_f(f, "a")?;
+        s.iszero()?;
+        s.and()?;
+        s.or()?;
+        s.set("nan")?;
+
+        // +inf and zeros are kept
+        s.isinf(f, "a")?;
+        s.iszero_f(f, "a")?;
+        s.or()?;
+        s.set("keep")?;
+        special(&mut s, f, "keep", |s| s.get("a"))?;
+
+        s.unpack(f, "a", ["sign", "k", "m"])?;
+        s.drop("a")?;
+
+        // normalize subnormals, m has man + 1 bits
+        s.num(man + 1)?;
+        s.get("m")?;
+        s.bitlen()?;
+        s.sub()?;
+        s.set("n")?;
+        s.get("m")?;
+        s.get("n")?;
+        s.shl()?;
+        s.set("m")?;
+
+        // the exponent t of m * 2 ^ t, made even
+        s.get("k")?;
+        s.get("n")?;
+        s.sub()?;
+        s.num(f.bias() + man)?;
+        s.sub()?;
+        s.set("t")?;
+        s.get("t")?;
+        s.num(1)?;
+        s.and()?;
+        s.set("odd")?;
+        s.get("m")?;
+        s.num(200)?;
+        s.get("odd")?;
+        s.add()?;
+        s.shl()?;
+        s.set("m")?;
+
+        // newton's method from an upper bound of the root
+        s.num(1)?;
+        s.num((man + 203) / 2)?;
+        s.shl()?;
+        s.set("x")?;
+        for _ in 0..8 {
+            s.get("m")?;
+            s.get("x")?;
+            s.div()?;
+            s.get("x")?;
+            s.add()?;
+            s.num(1)?;
+            s.shr()?;
+            s.set("x")?;
+        }
+
+        // x is floor(sqrt(m)) or floor(sqrt(m)) + 1
+        s.get("x")?;
+        s.get("m")?;
+        s.get("x")?;
+        s.div()?;
+        s.get("x")?;
+        s.lt()?;
+        s.sub()?;
+        s.set("x")?;
+
+        // the lowest bit is set if the root is inexact
+        s.get("x")?;
+        s.get("x")?;
+        s.get("x")?;
+        s.mul()?;
+        s.get("m")?;
+        s.eq()?;
+        s.iszero()?;
+        s.or()?;
+        s.set("m")?;
+
+        s.get("t")?;
+        s.get("odd")?;
+        s.sub()?;
+        s.num(200)?;
+        s.sub()?;
+        s.num(1)?;
+        s.sar()?;
+        s.num(f.bias() + man)?;
+        s.add()?;
+        s.set("k")?;
+        s.drop_all(&["x", "odd", "t", "n"])?;
+
+        s.pack(f, ["sign", "k", "m"])?;
+        resolve(&mut s)?;
+        s.finish()
+    }
+
+    /// Round to an integral value.
+    ///
+    /// STACK: [a] -> [round(a)]
+    pub fn _fround(&mut self, f: Float, round: Round) -> Result<()> {
+        let mut s = Soft::new(self, &["a"]);
+        s.isnan(f, "a")?;
+        s.set("nan")?;
+
+        s.unpack(f, "a", ["sign", "k", "m"])?;
+        s.get("k")?;
+        s.num(f.bias() + f.man() as u128)?;
+        s.sub()?;
+        s.set("t")?;
+
+        // infinities and the floats without fraction are kept
+        s.get("t")?;
+        s.num(0)?;
+        s.slt()?;
+        s.iszero()?;
+        s.set("keep")?;
+        special(&mut s, f, "keep", |s| s.get("a"))?;
+        s.drop("a")?;
+
+        // integral part and fraction of m * 2 ^ t
+        s.num(0)?;
+        s.get("t")?;
+        s.sub()?;
+        s.num(128)?;
+        s.min()?;
+        s.set("t")?;
+        s.get("m")?;
+        s.get("t")?;
+        s.shr()?;
+        s.set("int")?;
+        s.get("m")?;
+        s.get("int")?;
+        s.get("t")?;
+        s.shl()?;
+        s.sub()?;
+        s.set("frac")?;
+
+        match round {
+            Round::Trunc => s.num(0)?,
+            Round::Floor | Round::Ceil => {
+                s.get("frac")?;
+                s.nonzero()?;
+                s.get("sign")?;
+                if round == Round::Ceil {
+                    s.iszero()?;
+                }
+                s.and()?;
+            }
+            Round::Nearest => {
+                s.num(1)?;
+                s.get("t")?;
+                s.shl()?;
+                s.num(1)?;
+                s.shr()?;
+                s.set("half")?;
+                s.get("frac")?;
+                s.get("half")?;
+                s.gt()?;
+                s.get("frac")?;
+                s.get("half")?;
+                s.eq()?;
+                s.get("int")?;
+                s.num(1)?;
+                s.and()?;
+                s.and()?;
+                s.or()?;
+                s.drop("half")?;
+            }
+        }
+
+        s.get("int")?;
+        s.add()?;
+        s.set("m")?;
+        s.num(f.bias() + f.man() as u128)?;
+        s.set("k")?;
+        s.drop_all(&["int", "frac", "t"])?;
+
+        s.pack(f, ["sign", "k", "m"])?;
+        resolve(&mut s)?;
+        s.finish()
+    }
+
+    /// Minimum or maximum of two values, -0 is less than +0.
+    ///
+    /// STACK: [a, b] -> [min(a, b)] or [max(a, b)]
+    pub fn _fminmax(&mut self, f: Float, max: bool) -> Result<()> {
+        let mut s = Soft::new(self, &["a", "b"]);
+        s.key(f, "a")?;
+        s.set("ka")?;
+        s.key(f, "b")?;
+        s.set("kb")?;
+
+        s.isnan(f, "a")?;
+        s.isnan(f, "b")?;
+        s.or()?;
+        s.num(f.nan())?;
+
+        // a if a wins, b if b wins, otherwise they are equal or zeros
+        s.get("ka")?;
+        s.get("kb")?;
+        if max {
+            s.gt()?;
+        } else {
+            s.lt()?;
+        }
+        s.get("a")?;
+        s.get("kb")?;
+        s.get("ka")?;
+        if max {
+            s.gt()?;
+        } else {
+            s.lt()?;
+        }
+        s.get("b")?;
+        s.get("a")?;
+        s.get("b")?;
+        if max {
+            s.and()?;
+        } else {
+            s.or()?;
+        }
+        s.select()?;
+        s.select()?;
+        s.select()?;
+        s.drop_all(&["a", "b", "ka", "kb"])?;
+        s.finish()
     }
 
-    /// Convert a signed 32-bit integer to a (32-bit/64-bit) float
-    pub fn _convert_i32_s(&mut self) -> Result<()> {
-        todo!()
+    /// Comparison, NaN is unordered.
+    ///
+    /// STACK: [a, b] -> [a op b]
+    pub fn _fcmp(
+        &mut self,
+        f: Float,
+        ordered: impl FnOnce(&mut Soft) -> Result<()>,
+        unordered: bool,
+    ) -> Result<()> {
+        let mut s = Soft::new(self, &["a", "b"]);
+        s.isnan(f, "a")?;
+        s.isnan(f, "b")?;
+        s.or()?;
+        s.num(unordered as u128)?;
+        s.key(f, "a")?;
+        s.key(f, "b")?;
+        ordered(&mut s)?;
+        s.select()?;
+        s.drop_all(&["a", "b"])?;
+        s.finish()
     }
 
-    /// Convert an unsigned 32-bit integer to a (32-bit/64-bit) float
-    pub fn _convert_i32_u(&mut self) -> Result<()> {
-        todo!()
+    /// Convert an integer of `width` bytes to a float.
+    ///
+    /// STACK: [i] -> [float(i)]
+    pub fn _fconvert(&mut self, f: Float, width: usize, signed: bool) -> Result<()> {
+        let mut s = Soft::new(self, &["i"]);
+        if signed {
+            s.get("i")?;
+            s.num(width as u128 * 8 - 1)?;
+            s.shr()?;
+            s.set("sign")?;
+
+            // the magnitude of negative integers
+            s.get("sign")?;
+            s.num(0)?;
+            s.get("i")?;
+            s.sub()?;
+            s.num((1 << (width * 8)) - 1)?;
+            s.and()?;
+            s.get("i")?;
+            s.select()?;
+            s.set("i")?;
+        } else {
+            s.num(0)?;
+            s.set("sign")?;
+        }
+
+        s.num(f.bias() + f.man() as u128)?;
+        s.set("k")?;
+        s.pack(f, ["sign", "k", "i"])?;
+        s.finish()
     }
 
-    /// Convert a signed 32-bit integer to a (32-bit/64-bit) float
-    pub fn _convert_i64_s(&mut self) -> Result<()> {
-        todo!()
+    /// Truncate a float to an integer of `width` bytes, traps on NaN
+    /// or if the integer is out of range.
+    ///
+    /// STACK: [a] -> [trunc(a)]
+    pub fn _ftrunc_int(&mut self, f: Float, width: usize, signed: bool) -> Result<()> {
+        let bits = width as u128 * 8;
+        let mut s = Soft::new(self, &["a"]);
+        s.isnan(f, "a")?;
+        s.set("nan")?;
+        s.unpack(f, "a", ["sign", "k", "m"])?;
+        s.drop("a")?;
+
+        // m * 2 ^ t, the shift to the left is capped since it
+        // overflows anyway.
+        s.get("k")?;
+        s.num(f.bias() + f.man() as u128)?;
+        s.sub()?;
+        s.set("t")?;
+        s.get("t")?;
+        s.num(0)?;
+        s.slt()?;
+        s.set("neg")?;
+        s.get("m")?;
+        s.get("t")?;
+        s.num(128)?;
+        s.smin()?;
+        s.get("neg")?;
+        s.iszero()?;
+        s.mul()?;
+        s.shl()?;
+        s.num(0)?;
+        s.get("t")?;
+        s.sub()?;
+        s.get("neg")?;
+        s.mul()?;
+        s.shr()?;
+        s.set("int")?;
+
+        // the limit of the magnitude
+        if signed {
+            s.num((1 << (bits - 1)) - 1)?;
+            s.get("sign")?;
+            s.add()?;
+        } else {
+            s.get("sign")?;
+            s.num(0)?;
+            s.num((1 << bits) - 1)?;
+            s.select()?;
+        }
+        s.get("int")?;
+        s.lt()?;
+        s.get("nan")?;
+        s.or()?;
+        s.trap()?;
+
+        s.get("sign")?;
+        s.num(0)?;
+        s.get("int")?;
+        s.sub()?;
+        s.num((1 << bits) - 1)?;
+        s.and()?;
+        s.get("int")?;
+        s.select()?;
+        s.drop_all(&["nan", "sign", "k", "m", "t", "neg", "int"])?;
+        s.finish()
     }
 
-    /// Convert a unsigned 32-bit integer to a (32-bit/64-bit) float
-    pub fn _convert_i64_u(&mut self) -> Result<()> {
-        todo!()
+    /// Convert a float to another format.
+    ///
+    /// STACK: [a] -> [convert(a)]
+    pub fn _fconvert_float(&mut self, from: Float, to: Float) -> Result<()> {
+        let mut s = Soft::new(self, &["a"]);
+        s.isnan(from, "a")?;
+        s.set("nan")?;
+        s.isinf(from, "a")?;
+        s.set("inf")?;
+
+        s.unpack(from, "a", ["sign", "k", "m"])?;
+        s.drop("a")?;
+        special(&mut s, to, "inf", |s| {
+            s.get("sign")?;
+            s.num(to.width() as u128 - 1)?;
+            s.shl()?;
+            s.num(to.inf())?;
+            s.or()
+        })?;
+
+        s.get("k")?;
+        s.num(to.bias() + to.man() as u128)?;
+        s.add()?;
+        s.num(from.bias() + from.man() as u128)?;
+        s.sub()?;
+        s.set("k")?;
+
+        s.pack(to, ["sign", "k", "m"])?;
+        resolve(&mut s)?;
+        s.finish()
     }
+}
+
+/// Addition of the named floats `a` and `b`.
+fn add(s: &mut Soft, f: Float) -> Result<()> {
+    // NaN, or infinities of different signs
+    s.isnan(f, "a")?;
+    s.isnan(f, "b")?;
+    s.or()?;
+    s.isinf(f, "a")?;
+    s.isinf(f, "b")?;
+    s.and()?;
+    s.get("a")?;
+    s.get("b")?;
+    s.xor()?;
+    s.num(f.sign())?;
+    s.and()?;
+    s.nonzero()?;
+    s.and()?;
+    s.or()?;
+    s.set("nan")?;
+
+    s.isinf(f, "a")?;
+    s.isinf(f, "b")?;
+    s.or()?;
+    s.set("inf")?;
+    special(s, f, "inf", |s| {
+        s.isinf(f, "a")?;
+        s.get("a")?;
+        s.get("b")?;
+        s.select()
+    })?;
 
-    /// Round to nearest integer towards zero
-    pub fn _trunc(&mut self) -> Result<()> {
-        todo!()
+    s.unpack(f, "a", ["sa", "ka", "ma"])?;
+    s.unpack(f, "b", ["sb", "kb", "mb"])?;
+    s.drop_all(&["a", "b"])?;
+
+    // align the significands to the larger exponent with 128 more
+    // bits, the far smaller one only matters as a sticky bit.
+    s.get("ka")?;
+    s.get("kb")?;
+    s.max()?;
+    s.set("k")?;
+    for (k, m) in [("ka", "ma"), ("kb", "mb")] {
+        s.get(m)?;
+        s.num(128)?;
+        s.get("k")?;
+        s.get(k)?;
+        s.sub()?;
+        s.num(127)?;
+        s.min()?;
+        s.sub()?;
+        s.shl()?;
+        s.set(m)?;
+        s.drop(k)?;
     }
+
+    s.get("sa")?;
+    s.get("sb")?;
+    s.eq()?;
+    s.set("same")?;
+    s.get("ma")?;
+    s.get("mb")?;
+    s.gt()?;
+    s.set("gt")?;
+
+    // the sum of the same signs, or the difference
+    s.get("same")?;
+    s.get("ma")?;
+    s.get("mb")?;
+    s.add()?;
+    s.get("gt")?;
+    s.get("ma")?;
+    s.get("mb")?;
+    s.sub()?;
+    s.get("mb")?;
+    s.get("ma")?;
+    s.sub()?;
+    s.select()?;
+    s.select()?;
+    s.set("m")?;
+
+    // the sign of the larger one, the exact zero difference is +0
+    s.get("same")?;
+    s.get("gt")?;
+    s.or()?;
+    s.get("sa")?;
+    s.get("sb")?;
+    s.select()?;
+    s.get("m")?;
+    s.nonzero()?;
+    s.get("same")?;
+    s.or()?;
+    s.and()?;
+    s.set("sign")?;
+    s.drop_all(&["sa", "sb", "ma", "mb", "same", "gt"])?;
+
+    s.get("k")?;
+    s.num(128)?;
+    s.sub()?;
+    s.set("k")?;
+
+    s.pack(f, ["sign", "k", "m"])?;
+    resolve(s)
+}
+
+/// Prepare the special result `sp` of the routine for the named flags
+/// `nan` and `flag`, the value of `flag` is emitted by `value`.
+///
+/// The flags are consumed.
+fn special(
+    s: &mut Soft,
+    f: Float,
+    flag: &'static str,
+    value: impl FnOnce(&mut Soft) -> Result<()>,
+) -> Result<()> {
+    s.get("nan")?;
+    s.get(flag)?;
+    s.or()?;
+    s.set("special")?;
+
+    s.get("nan")?;
+    s.num(f.nan())?;
+    value(s)?;
+    s.select()?;
+    s.set("sp")?;
+    s.drop_all(&["nan", flag])
+}
+
+/// Select the special result over the result on the top of the stack.
+fn resolve(s: &mut Soft) -> Result<()> {
+    s.set("result")?;
+    s.get("special")?;
+    s.get("sp")?;
+    s.get("result")?;
+    s.select()?;
+    s.drop_all(&["special", "sp", "result"])
 }
@@ -1,7 +1,6 @@
 // Integer instructions
 
 use crate::{wasm::ToLSBytes, MacroAssembler, Result};
-use wasmparser::{Ieee32, Ieee64};
 
 impl MacroAssembler {
     /// Sub two numbers.
@@ -10,6 +9,35 @@ impl MacroAssembler {
         self.asm._sub()
     }
 
+    /// Divide two numbers.
+    pub fn _div(&mut self) -> Result<()> {
+        self._swap1()?;
+        self.asm._div()
+    }
+
+    /// Divide two signed numbers.
+    pub fn _sdiv(&mut self) -> Result<()> {
+        self._swap1()?;
+        self.asm._sdiv()
+    }
+
+    /// Remainder of two numbers.
+    pub fn _mod(&mut self) -> Result<()> {
+        self._swap1()?;
+        self.asm._mod()
+    }
+
+    /// Remainder of two signed numbers.
+    pub fn _smod(&mut self) -> Result<()> {
+        self._swap1()?;
+        self.asm._smod()
+    }
+
+    /// Arithmetic shift right.
+    pub fn _sshr(&mut self) -> Result<()> {
+        self.asm._sar()
+    }
+
     /// Push a 32-bit integer value on the stack.
     pub fn _i32_const(&mut self, value: i32) -> Result<()> {
         if value == 0 {
@@ -28,105 +56,186 @@ impl MacroAssembler {
         }
     }
 
-    /// Push a 32-bit float value on the stack.
-    pub fn _f32_const(&mut self, _value: Ieee32) -> Result<()> {
-        todo!()
-    }
-
-    /// Push a 64-bit float value on the stack.
-    pub fn _f64_const(&mut self, _value: Ieee64) -> Result<()> {
-        todo!()
-    }
-
     /// wrap a 64-bit integer to a 32-bit integer.
+    ///
+    /// The higher bits are dropped lazily, see `Function::dirty`.
     pub fn _i32_wrap_i64(&mut self) -> Result<()> {
-        todo!()
+        Ok(())
     }
 
     /// Extend a signed 32-bit integer to a 64-bit integer.
+    ///
+    /// The operand has been sign-extended to 256 bits already, the
+    /// result is wrapped to 64 bits lazily, see `Function::dirty`.
     pub fn _i64_extend_i32_s(&mut self) -> Result<()> {
-        todo!()
+        Ok(())
     }
 
     /// Extend an unsigned 32-bit integer to a 64-bit integer.
     pub fn _i64_extend_i32_u(&mut self) -> Result<()> {
-        todo!()
-    }
-
-    /// Truncate a 64-bit float to a signed 32-bit integer.
-    pub fn _f32_demote_f64(&mut self) -> Result<()> {
-        todo!()
+        Ok(())
     }
 
-    /// Truncate a 64-bit float to an unsigned 32-bit integer.
-    pub fn _f64_promote_f32(&mut self) -> Result<()> {
-        todo!()
+    /// Sign-extend the lowest byte of a 32-bit integer.
+    pub fn _i32_extend8_s(&mut self) -> Result<()> {
+        self._extend_s(1, 4)
     }
 
-    /// Convert a signed 32-bit integer to a 32-bit float.
-    pub fn _i32_reinterpret_f32(&mut self) -> Result<()> {
-        todo!()
+    /// Sign-extend the lowest 2 bytes of a 32-bit integer.
+    pub fn _i32_extend16_s(&mut self) -> Result<()> {
+        self._extend_s(2, 4)
     }
 
-    /// Convert a signed 64-bit integer to a 64-bit float.
-    pub fn _i64_reinterpret_f64(&mut self) -> Result<()> {
-        todo!()
+    /// Sign-extend the lowest byte of a 64-bit integer.
+    pub fn _i64_extend8_s(&mut self) -> Result<()> {
+        self._extend_s(1, 8)
     }
 
-    /// Convert a 32-bit float to a signed 32-bit integer.
-    pub fn _f32_reinterpret_i32(&mut self) -> Result<()> {
-        todo!()
+    /// Sign-extend the lowest 2 bytes of a 64-bit integer.
+    pub fn _i64_extend16_s(&mut self) -> Result<()> {
+        self._extend_s(2, 8)
     }
 
-    /// Convert a 64-bit float to a signed 64-bit integer.
-    pub fn _f64_reinterpret_i64(&mut self) -> Result<()> {
-        todo!()
+    /// Sign-extend the lowest 4 bytes of a 64-bit integer.
+    pub fn _i64_extend32_s(&mut self) -> Result<()> {
+        self._extend_s(4, 8)
     }
 
     /// sign-agnostic rotate left
     ///
-    /// Return the result of rotating i1 left by k bits.
+    /// Return the result of rotating i1 left by k bits, the bits
+    /// rotated out are left above the width.
+    ///
+    /// STACK: [i1, k, bits] -> [rotl(i1, k)]
     pub fn _rotl(&mut self) -> Result<()> {
-        todo!()
+        // [i1, k, bits - k]
+        self._dup2()?;
+        self._sub()?;
+
+        // [i1 >> (bits - k), k, i1]
+        self._dup3()?;
+        self._swap1()?;
+        self._shr()?;
+        self._swap2()?;
+
+        // [i1 >> (bits - k), i1 << k]
+        self._swap1()?;
+        self._shl()?;
+        self._or()
     }
 
     /// sign-agnostic rotate right
     ///
-    /// Return the result of rotating i1 right by k bits.
+    /// Return the result of rotating i1 right by k bits, the bits
+    /// rotated out are left above the width.
+    ///
+    /// STACK: [i1, k, bits] -> [rotr(i1, k)]
     pub fn _rotr(&mut self) -> Result<()> {
-        todo!()
+        // [i1, k, bits - k]
+        self._dup2()?;
+        self._sub()?;
+
+        // [i1 << (bits - k), k, i1]
+        self._dup3()?;
+        self._swap1()?;
+        self._shl()?;
+        self._swap2()?;
+
+        // [i1 << (bits - k), i1 >> k]
+        self._swap1()?;
+        self._shr()?;
+        self._or()
     }
 
     /// sign-agnostic count leading zero bits
     ///
     /// Return the number of leading zero bits in i, all zero bits
     /// are considered leading if the value is zero.
+    ///
+    /// STACK: [i, bits] -> [clz(i)]
     pub fn _clz(&mut self) -> Result<()> {
-        todo!()
+        self._swap1()?;
+
+        // fill the bits under the highest one bit, the
+        // one bits are counted as the length of i then.
+        for shift in [1u8, 2, 4, 8, 16, 32] {
+            self._dup1()?;
+            self.push(&[shift])?;
+            self._shr()?;
+            self._or()?;
+        }
+
+        self._popcnt()?;
+        self._sub()
     }
 
-    /// sign-agnostic count leading zero bits
+    /// sign-agnostic count trailing zero bits
     ///
-    /// Return the number of leading zero bits in i, all zero bits
+    /// Return the number of trailing zero bits in i, all zero bits
     /// are considered trailing if the value is zero.
+    ///
+    /// STACK: [i, bits] -> [ctz(i)]
     pub fn _ctz(&mut self) -> Result<()> {
-        todo!()
+        // [i, (1 << bits) - 1]
+        self.push(&[1])?;
+        self._swap1()?;
+        self._shl()?;
+        self.push(&[1])?;
+        self._sub()?;
+
+        // [mask, (i & -i) - 1], the trailing zero bits turn into one
+        // bits, and all bits do if i is zero.
+        self._swap1()?;
+        self._dup1()?;
+        self._push0()?;
+        self.asm._sub()?;
+        self._and()?;
+        self.push(&[1])?;
+        self._sub()?;
+
+        self._and()?;
+        self._popcnt()
     }
 
     /// sign-agnostic count number of one bits
     ///
-    /// Return the count of no zero bits in i.
+    /// Return the count of no zero bits in i, which is at most 64 bits.
+    ///
+    /// STACK: [i] -> [popcnt(i)]
     pub fn _popcnt(&mut self) -> Result<()> {
-        todo!()
-    }
-
-    /// Truncate a 32-bit float to an integer
-    pub fn _trunc_f32(&mut self) -> Result<()> {
-        todo!()
-    }
-
-    /// Truncate a 64-bit float to an integer
-    pub fn _trunc_f64(&mut self) -> Result<()> {
-        todo!()
+        // count bits in pairs, i - ((i >> 1) & 0x55..)
+        self._dup1()?;
+        self.push(&[1])?;
+        self._shr()?;
+        self.push(&[0x55; 8])?;
+        self._and()?;
+        self._sub()?;
+
+        // in nibbles, (i & 0x33..) + ((i >> 2) & 0x33..)
+        self._dup1()?;
+        self.push(&[0x33; 8])?;
+        self._and()?;
+        self._swap1()?;
+        self.push(&[2])?;
+        self._shr()?;
+        self.push(&[0x33; 8])?;
+        self._and()?;
+        self._add()?;
+
+        // in bytes, (i + (i >> 4)) & 0x0f..
+        self._dup1()?;
+        self.push(&[4])?;
+        self._shr()?;
+        self._add()?;
+        self.push(&[0x0f; 8])?;
+        self._and()?;
+
+        // sum the bytes up in the highest byte of 64 bits
+        self.push(&[0x01; 8])?;
+        self._mul()?;
+        self.push(&[56])?;
+        self._shr()?;
+        self.push(&[0xff])?;
+        self._and()
     }
 }
@@ -1,70 +1,389 @@
 //! Memory Instructions
 
-use crate::{MacroAssembler, Result};
+use crate::{
+    wasm::{Memory, ToLSBytes, FREE_MEMORY_POINTER, PAGE_SIZE},
+    MacroAssembler, Result,
+};
 
 impl MacroAssembler {
-    /// Load n bytes to extend self as another number type.
+    /// Load `size` bytes from the word at the EVM offset on the stack.
     ///
-    /// Just for adapting the WASM instructions, this method makes
-    /// no sense for EVM since all of the numbers as U256.
-    pub(crate) fn _load(&mut self) -> Result<()> {
-        Ok(())
+    /// STACK: [ptr] -> [value]
+    pub fn _load(&mut self, size: usize) -> Result<()> {
+        self._mload()?;
+        self._wrap(size)
     }
 
-    /// Load 1 byte to extend self as another number type.
+    /// Sign-extend the value in the lowest `size` bytes and wrap
+    /// it to `width` bytes.
+    pub fn _extend_s(&mut self, size: usize, width: usize) -> Result<()> {
+        self.push(&(size - 1).to_ls_bytes())?;
+        self._signextend()?;
+        self._wrap(width)
+    }
+
+    /// Keep the lowest `size` bytes of the value on the stack.
+    pub fn _wrap(&mut self, size: usize) -> Result<()> {
+        if size >= 32 {
+            return Ok(());
+        }
+
+        self.push(&vec![0xff; size])?;
+        self._and()
+    }
+
+    /// Store the lowest `size` bytes of a value in the word at the
+    /// EVM offset on the stack.
+    ///
+    /// The bytes around the stored value are loaded and written back
+    /// since `MSTORE` always writes a whole word.
+    ///
+    /// STACK: [ptr, value] -> []
+    pub fn _store(&mut self, size: usize) -> Result<()> {
+        // [ptr, value & mask, old >> bits << bits]
+        self._wrap(size)?;
+        self._dup2()?;
+        self._mload()?;
+        let bits = (size * 8).to_ls_bytes();
+        self.push(&bits)?;
+        self._shr()?;
+        self.push(&bits)?;
+        self._shl()?;
+
+        // [value | old, ptr]
+        self._or()?;
+        self._swap1()?;
+        self._mstore()
+    }
+
+    /// The memory size instruction returns the current
+    /// size of memory.
+    ///
+    /// STACK: [] -> [pages]
+    pub fn _memory_size(&mut self, memory: &Memory) -> Result<()> {
+        self.push(&memory.grown().to_ls_bytes())?;
+        self._mload()?;
+        self.push(&memory.pages.to_ls_bytes())?;
+        self._add()
+    }
+
+    /// The memory grow instruction grows memory by a given
+    /// delta and returns the previous size, or -1 if enough
+    /// memory cannot be allocated.
+    ///
+    /// The pages are only counted since the grown pages are reserved
+    /// in EVM memory, the result is selected without jumps to keep the
+    /// gas predictable.
+    ///
+    /// STACK: [delta] -> [pages]
+    pub fn _memory_grow(&mut self, memory: &Memory) -> Result<()> {
+        let grown = memory.grown().to_ls_bytes();
+
+        // [delta, grown, grown + delta > growable]
+        self.push(&grown)?;
+        self._mload()?;
+        self._dup1()?;
+        self._dup3()?;
+        self._add()?;
+        self.push(&memory.growable().to_ls_bytes())?;
+        self._gt()?;
+
+        // [delta, grown, failed], grown += delta * !failed
+        self._dup1()?;
+        self._iszero()?;
+        self._dup4()?;
+        self._mul()?;
+        self._dup3()?;
+        self._add()?;
+        self.push(&grown)?;
+        self._mstore()?;
+
+        // [(pages + grown) | (failed * 0xffffffff)]
+        self.push(&[0xff; 4])?;
+        self._mul()?;
+        self._swap1()?;
+        self.push(&memory.pages.to_ls_bytes())?;
+        self._add()?;
+        self._or()?;
+        self._swap1()?;
+        self._drop()
+    }
+
+    /// Translate a range of the linear memory to the EVM offset of
+    /// its lowest byte, see [`crate::wasm::Memory`].
+    ///
+    /// Traps if the range is out of the initial pages or the pages grown
+    /// at runtime, a range could not straddle the initial pages and the
+    /// grown pages since they are not contiguous in EVM memory.
+    ///
+    /// STACK: [address, size] -> [ptr]
+    pub fn _memory_range(&mut self, memory: &Memory) -> Result<()> {
+        let initial = memory.initial().to_ls_bytes();
+        let bound = memory.bound().to_ls_bytes();
+        if memory.growable() == 0 {
+            // [end], trap if end > initial
+            self._add()?;
+            self._dup1()?;
+            self.push(&initial)?;
+            self.asm._lt()?;
+            self._trap()?;
+
+            // [bound - end]
+            self.push(&bound)?;
+            return self.asm._sub();
+        }
+
+        // [size, address, grown], grown = address >= initial
+        self._swap1()?;
+        self.push(&initial)?;
+        self._dup2()?;
+        self.asm._lt()?;
+        self._iszero()?;
+
+        // [grown, end]
+        self._swap2()?;
+        self._add()?;
+
+        // [grown, end, limit], limit = initial + grown * pages * PAGE_SIZE
+        self.push(&memory.grown().to_ls_bytes())?;
+        self._mload()?;
+        self.push(&PAGE_SIZE.to_ls_bytes())?;
+        self._mul()?;
+        self._dup3()?;
+        self._mul()?;
+        self.push(&initial)?;
+        self._add()?;
+
+        // [grown, end], trap if end > limit
+        self._dup2()?;
+        self.asm._gt()?;
+        self._trap()?;
+
+        // [bound - end + grown * distance]
+        self.push(&bound)?;
+        self.asm._sub()?;
+        self._swap1()?;
+        self.push(&memory.distance().to_ls_bytes())?;
+        self._mul()?;
+        self._add()
+    }
+
+    /// Prepare the state of the loop which copies bytes one by one.
     ///
-    /// Just for adapting the WASM instructions, this method makes
-    /// no sense for EVM since all of the numbers as U256.
-    pub(crate) fn _load8(&mut self) -> Result<()> {
-        Ok(())
+    /// The bytes are copied from the lowest offset if the destination
+    /// is below the source, otherwise from the highest one, so the
+    /// overlapped bytes are read before they are overwritten. The
+    /// offset of the byte copied with the counter `i` is `base + step * i`.
+    ///
+    /// STACK: [size, src, dst] -> [step, dst + base, src + base, size]
+    pub fn _copy_bytes(&mut self) -> Result<()> {
+        // [size, src, dst, forward], forward = dst <= src
+        self._dup2()?;
+        self._dup2()?;
+        self.asm._gt()?;
+        self._iszero()?;
+
+        // [size, src, dst, forward, 1 - 2 * forward]
+        self._dup1()?;
+        self.push(&[1])?;
+        self._shl()?;
+        self.push(&[1])?;
+        self.asm._sub()?;
+
+        // [size, src, dst, step, forward * (size + 1) - 1]
+        self._swap1()?;
+        self._dup5()?;
+        self.push(&[1])?;
+        self._add()?;
+        self._mul()?;
+        self.push(&[1])?;
+        self._swap1()?;
+        self.asm._sub()?;
+
+        // [size, dst + base, src + base, step]
+        self._swap2()?;
+        self._dup3()?;
+        self._add()?;
+        self._swap3()?;
+        self._dup3()?;
+        self._add()?;
+        self._swap2()?;
+        self._drop()?;
+
+        // [step, dst + base, src + base, size]
+        self._swap3()
     }
 
-    /// Load 2 bytes to extend self as another number type.
+    /// Copy the byte with the counter on the stack.
     ///
-    /// Just for adapting the WASM instructions, this method makes
-    /// no sense for EVM since all of the numbers as U256.
-    pub(crate) fn _load16(&mut self) -> Result<()> {
-        Ok(())
+    /// STACK: [step, dst, src, i] -> [step, dst, src, i]
+    pub fn _copy_byte(&mut self) -> Result<()> {
+        // [step, dst, src, i, step * i]
+        self._dup4()?;
+        self._dup2()?;
+        self._mul()?;
+
+        // [step, dst, src, i, step * i, byte]
+        self._dup1()?;
+        self._dup4()?;
+        self._add()?;
+        self._mload()?;
+        self.push(&[0xf8])?;
+        self._shr()?;
+
+        self._swap1()?;
+        self._dup5()?;
+        self._add()?;
+        self._mstore8()
     }
 
-    /// Load 4 bytes to extend self as another number type.
+    /// Prepare the state of the loop which fills bytes one by one.
     ///
-    /// Just for adapting the WASM instructions, this method makes
-    /// no sense for EVM since all of the numbers as U256.
-    pub(crate) fn _load32(&mut self) -> Result<()> {
-        Ok(())
+    /// STACK: [value, size, ptr] -> [value, ptr - 1, size]
+    pub fn _fill_bytes(&mut self) -> Result<()> {
+        self.push(&[1])?;
+        self._swap1()?;
+        self.asm._sub()?;
+        self._swap1()
     }
 
-    /// Store n bytes in memory.
-    pub fn _store(&mut self) -> Result<()> {
-        todo!()
+    /// Fill the byte with the counter on the stack.
+    ///
+    /// STACK: [value, dst, i] -> [value, dst, i]
+    pub fn _fill_byte(&mut self) -> Result<()> {
+        self._dup3()?;
+        self._dup2()?;
+        self._dup4()?;
+        self._add()?;
+        self._mstore8()
     }
 
-    /// Wrap self to i8 and store 1 byte
-    pub fn _store8(&mut self) -> Result<()> {
-        todo!()
+    /// Push the free memory pointer, which falls back to the start
+    /// of the heap before the first allocation.
+    ///
+    /// STACK: [] -> [ptr]
+    pub fn _free_memory_pointer(&mut self, memory: &Memory) -> Result<()> {
+        // [ptr | (ptr == 0) * heap]
+        self.push(&FREE_MEMORY_POINTER.to_ls_bytes())?;
+        self._mload()?;
+        self._dup1()?;
+        self._iszero()?;
+        self.push(&memory.heap().to_ls_bytes())?;
+        self._mul()?;
+        self._or()
     }
 
-    /// Wrap self to i16 and store 2 bytes
-    pub fn _store16(&mut self) -> Result<()> {
-        todo!()
+    /// Allocate `size` bytes on the heap, the size is rounded up to
+    /// words so the allocations stay aligned.
+    ///
+    /// STACK: [size] -> [ptr]
+    pub fn _alloc(&mut self, memory: &Memory) -> Result<()> {
+        // [(size + 0x1f) >> 5 << 5]
+        self.push(&[0x1f])?;
+        self._add()?;
+        self.push(&[5])?;
+        self._shr()?;
+        self.push(&[5])?;
+        self._shl()?;
+
+        // [ptr], free memory pointer = ptr + size
+        self._free_memory_pointer(memory)?;
+        self._dup1()?;
+        self._swap2()?;
+        self._add()?;
+        self._heap_check(memory)?;
+        self.push(&FREE_MEMORY_POINTER.to_ls_bytes())?;
+        self._mstore()
     }
 
-    /// Wrap self to i32 and store 4 bytes
-    pub fn _store32(&mut self) -> Result<()> {
-        todo!()
+    /// Trap if the end of an allocation is beyond the heap.
+    ///
+    /// STACK: [end] -> [end]
+    pub fn _heap_check(&mut self, memory: &Memory) -> Result<()> {
+        self._dup1()?;
+        self.push(&memory.heap_end().to_ls_bytes())?;
+        self.asm._lt()?;
+        self._trap()
     }
 
-    /// The memory size instruction returns the current
-    /// size of memory.
-    pub fn _memory_size(&mut self, _: u32, _: u8) -> Result<()> {
-        todo!()
+    /// Save the locals in `slots` in a new frame on the heap.
+    ///
+    /// Each frame starts with the pointer of the previous frame.
+    ///
+    /// STACK: [] -> []
+    pub fn _push_frame(&mut self, memory: &Memory, slots: &[usize]) -> Result<()> {
+        let frames = memory.frames().to_ls_bytes();
+
+        // [fp], link the previous frame
+        self._free_memory_pointer(memory)?;
+        self.push(&frames)?;
+        self._mload()?;
+        self._dup2()?;
+        self._mstore()?;
+
+        for (index, slot) in slots.iter().enumerate() {
+            // [fp, local, fp + 0x20 * (index + 1)]
+            self.push(&slot.to_ls_bytes())?;
+            self._mload()?;
+            self._dup2()?;
+            self.push(&(0x20 * (index + 1)).to_ls_bytes())?;
+            self._add()?;
+            self._mstore()?;
+        }
+
+        // frames = fp, free memory pointer = fp + size
+        self._dup1()?;
+        self.push(&frames)?;
+        self._mstore()?;
+        self.push(&((slots.len() + 1) * 0x20).to_ls_bytes())?;
+        self._add()?;
+        self._heap_check(memory)?;
+        self.push(&FREE_MEMORY_POINTER.to_ls_bytes())?;
+        self._mstore()
     }
 
-    /// The memory grow instruction grows memory by a given
-    /// delta and returns the previous size, or -1 if enough
-    /// memory cannot be allocated.
-    pub fn _memory_grow(&mut self, _: u32, _: u8) -> Result<()> {
-        todo!()
+    /// Restore the locals in `slots` from the latest frame.
+    ///
+    /// The frame is released if nothing has been allocated above it,
+    /// otherwise it is left on the heap since the allocations may still
+    /// be in use.
+    ///
+    /// STACK: [] -> []
+    pub fn _pop_frame(&mut self, memory: &Memory, slots: &[usize]) -> Result<()> {
+        let frames = memory.frames().to_ls_bytes();
+        let size = ((slots.len() + 1) * 0x20).to_ls_bytes();
+
+        // [fp]
+        self.push(&frames)?;
+        self._mload()?;
+        for (index, slot) in slots.iter().enumerate() {
+            self._dup1()?;
+            self.push(&(0x20 * (index + 1)).to_ls_bytes())?;
+            self._add()?;
+            self._mload()?;
+            self.push(&slot.to_ls_bytes())?;
+            self._mstore()?;
+        }
+
+        // [fp], frames = previous frame
+        self._dup1()?;
+        self._mload()?;
+        self.push(&frames)?;
+        self._mstore()?;
+
+        // [ptr - (ptr == fp + size) * size]
+        self.push(&size)?;
+        self._add()?;
+        self.push(&FREE_MEMORY_POINTER.to_ls_bytes())?;
+        self._mload()?;
+        self._dup1()?;
+        self._swap2()?;
+        self._eq()?;
+        self.push(&size)?;
+        self._mul()?;
+        self._swap1()?;
+        self.asm._sub()?;
+        self.push(&FREE_MEMORY_POINTER.to_ls_bytes())?;
+        self._mstore()
     }
 }
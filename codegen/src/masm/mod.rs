@@ -5,6 +5,7 @@ use crate::{
     wasm::{ToLSBytes, Type},
     Error, Result,
 };
+use opcodes::Upgrade;
 use smallvec::SmallVec;
 use std::ops::{Deref, DerefMut};
 
@@ -14,8 +15,11 @@ mod float;
 mod integer;
 mod memory;
 mod ret;
+mod soft;
 mod stack;
 
+pub use float::{Float, Round};
+
 /// EVM MacroAssembler.
 #[derive(Default, Debug, Clone)]
 pub struct MacroAssembler {
@@ -47,6 +51,13 @@ pub struct MemoryInfo {
 }
 
 impl MacroAssembler {
+    /// New macro assembler targeting the provided fork.
+    pub fn new(target: Upgrade) -> Self {
+        Self {
+            asm: Assembler::new(target),
+        }
+    }
+
     /// Store data in memory with at current memory byte pointer.
     pub fn memory_write(&mut self, ty: impl Type) -> Result<MemoryInfo> {
         let offset = self.mp.to_ls_bytes();
@@ -60,13 +71,37 @@ impl MacroAssembler {
         Ok(MemoryInfo { offset, size })
     }
 
-    /// Write bytes to memory.
-    pub fn memory_write_bytes(&mut self, bytes: &[u8]) -> Result<MemoryInfo> {
-        let len = bytes.len();
+    /// Write bytes of any length to memory at the offset on the stack.
+    ///
+    /// The bytes are written word by word, the last word is padded with
+    /// zeros at the end.
+    ///
+    /// STACK: [offset] -> [offset]
+    pub fn memory_write_bytes(&mut self, bytes: &[u8]) -> Result<()> {
+        for (index, word) in bytes.chunks(32).enumerate() {
+            // push the significant bytes and shift them to the start
+            // of the word, which is cheaper than pushing the zeros.
+            let start = word.iter().position(|b| *b != 0).unwrap_or(word.len());
+            let end = word
+                .iter()
+                .rposition(|b| *b != 0)
+                .map_or(start, |end| end + 1);
+            let shift = (32 - end) * 8;
+            self.push(&word[start..end])?;
+            if shift > 0 && end > start {
+                self.push(&shift.to_ls_bytes())?;
+                self._shl()?;
+            }
 
-        // TODO: if len is out of 32.
-        self.push(bytes)?;
-        self.memory_write(len)
+            self._dup2()?;
+            if index > 0 {
+                self.push(&(index * 32).to_ls_bytes())?;
+                self._add()?;
+            }
+            self._mstore()?;
+        }
+
+        Ok(())
     }
 
     /// Store data in memory at offset.
@@ -250,6 +285,19 @@ impl MacroAssembler {
         self.emit_return_value(&[1])
     }
 
+    /// Trap if the condition on the stack is not zero.
+    ///
+    /// Jumps to the `PC` instruction itself, which is not a valid
+    /// jump destination.
+    ///
+    /// STACK: [condition] -> []
+    pub fn _trap(&mut self) -> Result<()> {
+        self._pc()?;
+        self._jumpi()?;
+        self.asm.decrement_relocation_gas();
+        Ok(())
+    }
+
     /// Emits EVM bytecode to return a value from the current execution context.
     /// It handles the complete sequence for returning a value from an EVM contract
     pub fn emit_return_value(&mut self, value: &[u8]) -> Result<()> {
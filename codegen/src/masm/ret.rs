@@ -1,8 +1,17 @@
 //! Return handlers
 
-use crate::{wasm::ToLSBytes, Error, MacroAssembler, Result};
+use crate::{
+    wasm::{ToLSBytes, Type},
+    Error, MacroAssembler, Result,
+};
 use wasmparser::ValType;
 
+/// Selector of `Error(string)`.
+const ERROR_SELECTOR: [u8; 4] = [0x08, 0xc3, 0x79, 0xa0];
+
+/// Selector of `Panic(uint256)`.
+const PANIC_SELECTOR: [u8; 4] = [0x4e, 0x48, 0x7b, 0x71];
+
 impl MacroAssembler {
     /// Return with nothing.
     pub(crate) fn handle_empty_return(&mut self) -> Result<()> {
@@ -14,14 +23,17 @@ impl MacroAssembler {
     }
 
     /// Handle the end of the main function.
+    ///
+    /// The results should have been stored in memory from the current
+    /// memory pointer, each of them takes a word.
     pub fn main_return(&mut self, results: &[ValType]) -> Result<()> {
         if results.is_empty() {
             return self.handle_empty_return();
         }
 
-        let size = self.memory_write(results)?.size;
-        let offset =
-            self.mp_offset(|mp| mp.checked_sub(size).ok_or_else(|| Error::InvalidMP(0)))?;
+        let offset = self.mp.to_ls_bytes();
+        let size = results.align();
+        self.increment_mp(size)?;
 
         self.push(&size.to_ls_bytes())?;
         self.push(&offset)?;
@@ -29,21 +41,72 @@ impl MacroAssembler {
         Ok(())
     }
 
+    /// Revert with `Error(string)` of the message.
+    ///
+    /// The error is encoded from the start of the memory, which is free
+    /// to use since the execution is aborted, the selector ends at `0x20`.
+    ///
+    /// STACK: [] -> []
+    pub fn revert_error(&mut self, message: &[u8]) -> Result<()> {
+        let word = |value: usize| {
+            let mut word = [0; 32];
+            word[24..].copy_from_slice(&(value as u64).to_be_bytes());
+            word
+        };
+
+        // [padding, selector, offset, length, message padded to words]
+        let mut data = vec![0; 0x1c];
+        data.extend_from_slice(&ERROR_SELECTOR);
+        data.extend_from_slice(&word(0x20));
+        data.extend_from_slice(&word(message.len()));
+        data.extend_from_slice(message);
+        data.resize(0x60 + message.len().align(), 0);
+
+        self._push0()?;
+        self.memory_write_bytes(&data)?;
+        self._drop()?;
+
+        self.push(&(data.len() - 0x1c).to_ls_bytes())?;
+        self.push(&[0x1c])?;
+        self.asm._revert()
+    }
+
+    /// Revert with `Panic(uint256)` of the code on the stack.
+    ///
+    /// STACK: [code] -> []
+    pub fn revert_panic(&mut self) -> Result<()> {
+        self.push(&[0x20])?;
+        self._mstore()?;
+        self.push(&PANIC_SELECTOR)?;
+        self._push0()?;
+        self._mstore()?;
+
+        self.push(&[0x24])?;
+        self.push(&[0x1c])?;
+        self.asm._revert()
+    }
+
     /// Handle the return of a call.
     pub fn call_return(&mut self, results: &[ValType]) -> Result<()> {
         let len = results.len() as u16;
 
         tracing::trace!("cleaning frame stack, target: {}", len + 1);
-        // TODO: clean stacks via the count of nested control stacks.
-        while self.sp() > len + 1 {
-            self._drop()?;
+        // drop the values under the results, which are left by the
+        // enclosing control frames of early returns.
+        let extra = self.sp().saturating_sub(len + 1);
+        self._drop_under(extra, len)?;
+
+        // Shift the return address over the results, at most 16
+        // results could be returned since `SWAP16` is the deepest.
+        if len > 16 {
+            return Err(Error::StackIndexOutOfRange(len));
         }
 
-        // Shift stack to prompt the jump instruction,
-        // what about just dup it?
-        //
-        // TODO: handle the length of results > u8::MAX.
         self.shift_stack(len, false)?;
-        self._jump()
+        self._jump()?;
+
+        // the return address is on the stack already.
+        self.asm.decrement_relocation_gas();
+        Ok(())
     }
 }
@@ -0,0 +1,500 @@
+//! Soft float assembler
+//!
+//! The float routines are too long to be written with raw stack
+//! operations, this assembler tracks the stack items by name and
+//! addresses them with `DUP` and `SWAP`. The routines are branchless,
+//! so the layout of the stack is known at every instruction.
+
+use super::float::Float;
+use crate::{MacroAssembler, Result};
+use opcodes::Cancun as OpCode;
+
+/// Assembler addressing the stack items by name.
+pub struct Soft<'m> {
+    masm: &'m mut MacroAssembler,
+    /// Names of the stack items from the bottom, temporary values
+    /// are unnamed.
+    stack: Vec<Option<&'static str>>,
+}
+
+impl<'m> Soft<'m> {
+    /// Create a soft assembler with the named inputs on the stack,
+    /// the last input is on the top.
+    pub fn new(masm: &'m mut MacroAssembler, inputs: &[&'static str]) -> Self {
+        Self {
+            masm,
+            stack: inputs.iter().map(|name| Some(*name)).collect(),
+        }
+    }
+
+    /// Check that only the result is left on the stack.
+    pub fn finish(self) -> Result<()> {
+        debug_assert_eq!(self.stack, [None], "unbalanced soft float routine");
+        Ok(())
+    }
+
+    /// Depth of the named item from the top of the stack.
+    fn depth(&self, name: &'static str) -> u16 {
+        let index = self
+            .stack
+            .iter()
+            .rposition(|item| *item == Some(name))
+            .unwrap_or_else(|| panic!("{name} is not on the stack"));
+
+        (self.stack.len() - 1 - index) as u16
+    }
+
+    /// Emit an operator consuming `stack_in` temporary values.
+    fn op(&mut self, opcode: OpCode, stack_in: usize) -> Result<()> {
+        for _ in 0..stack_in {
+            debug_assert_eq!(self.stack.pop(), Some(None), "consuming named item");
+        }
+
+        self.masm.emit_op(opcode)?;
+        self.stack.push(None);
+        Ok(())
+    }
+
+    /// Push a copy of the named item.
+    pub fn get(&mut self, name: &'static str) -> Result<()> {
+        self.masm.dup(self.depth(name) + 1)?;
+        self.stack.push(None);
+        Ok(())
+    }
+
+    /// Name the value on the top of the stack, the previous item with
+    /// the same name is replaced.
+    pub fn set(&mut self, name: &'static str) -> Result<()> {
+        debug_assert_eq!(self.stack.last(), Some(&None), "naming named item");
+        if self.stack.contains(&Some(name)) {
+            self.masm.swap(self.depth(name))?;
+            self.masm._pop()?;
+            self.stack.pop();
+        } else if let Some(top) = self.stack.last_mut() {
+            *top = Some(name);
+        }
+
+        Ok(())
+    }
+
+    /// Remove the named item from the stack.
+    ///
+    /// The item on the top of the stack is kept on the top, the one
+    /// under it takes the place of the removed item.
+    pub fn drop(&mut self, name: &'static str) -> Result<()> {
+        let depth = self.depth(name) as usize;
+        let top = self.stack.len() - 1;
+        self.masm.swap(depth as u16)?;
+        self.masm._pop()?;
+        self.stack.swap(top - depth, top);
+        self.stack.pop();
+
+        if depth > 1 {
+            self.masm.swap(depth as u16 - 1)?;
+            self.stack.swap(top - depth, top - 1);
+        }
+
+        Ok(())
+    }
+
+    /// Remove the named items from the stack.
+    pub fn drop_all(&mut self, names: &[&'static str]) -> Result<()> {
+        names.iter().try_for_each(|name| self.drop(name))
+    }
+
+    /// Push a number.
+    pub fn num(&mut self, value: u128) -> Result<()> {
+        let bytes = value.to_be_bytes();
+        let start = bytes.iter().position(|b| *b != 0).unwrap_or(15);
+        self.masm.push(&bytes[start..])?;
+        self.stack.push(None);
+        Ok(())
+    }
+
+    /// a b -> a + b
+    pub fn add(&mut self) -> Result<()> {
+        self.op(OpCode::ADD, 2)
+    }
+
+    /// a b -> a * b
+    pub fn mul(&mut self) -> Result<()> {
+        self.op(OpCode::MUL, 2)
+    }
+
+    /// a b -> a - b
+    pub fn sub(&mut self) -> Result<()> {
+        self.masm._swap1()?;
+        self.op(OpCode::SUB, 2)
+    }
+
+    /// a b -> a / b
+    pub fn div(&mut self) -> Result<()> {
+        self.masm._swap1()?;
+        self.op(OpCode::DIV, 2)
+    }
+
+    /// a b -> a % b
+    pub fn rem(&mut self) -> Result<()> {
+        self.masm._swap1()?;
+        self.op(OpCode::MOD, 2)
+    }
+
+    /// a b -> a & b
+    pub fn and(&mut self) -> Result<()> {
+        self.op(OpCode::AND, 2)
+    }
+
+    /// a b -> a | b
+    pub fn or(&mut self) -> Result<()> {
+        self.op(OpCode::OR, 2)
+    }
+
+    /// a b -> a ^ b
+    pub fn xor(&mut self) -> Result<()> {
+        self.op(OpCode::XOR, 2)
+    }
+
+    /// a n -> a << n
+    pub fn shl(&mut self) -> Result<()> {
+        self.op(OpCode::SHL, 2)
+    }
+
+    /// a n -> a >> n
+    pub fn shr(&mut self) -> Result<()> {
+        self.op(OpCode::SHR, 2)
+    }
+
+    /// a n -> a >> n, arithmetic
+    pub fn sar(&mut self) -> Result<()> {
+        self.op(OpCode::SAR, 2)
+    }
+
+    /// a b -> a == b
+    pub fn eq(&mut self) -> Result<()> {
+        self.op(OpCode::EQ, 2)
+    }
+
+    /// a b -> a < b
+    pub fn lt(&mut self) -> Result<()> {
+        self.op(OpCode::GT, 2)
+    }
+
+    /// a b -> a > b
+    pub fn gt(&mut self) -> Result<()> {
+        self.op(OpCode::LT, 2)
+    }
+
+    /// a b -> a < b, signed
+    pub fn slt(&mut self) -> Result<()> {
+        self.op(OpCode::SGT, 2)
+    }
+
+    /// a b -> a > b, signed
+    pub fn sgt(&mut self) -> Result<()> {
+        self.op(OpCode::SLT, 2)
+    }
+
+    /// a -> a == 0
+    pub fn iszero(&mut self) -> Result<()> {
+        self.op(OpCode::ISZERO, 1)
+    }
+
+    /// a -> a != 0
+    pub fn nonzero(&mut self) -> Result<()> {
+        self.iszero()?;
+        self.iszero()
+    }
+
+    /// c a b -> c ? a : b, where c is 0 or 1
+    pub fn select(&mut self) -> Result<()> {
+        // b ^ ((a ^ b) * c)
+        self.masm._swap1()?;
+        self.masm._dup2()?;
+        self.masm._xor()?;
+        self.masm._swap1()?;
+        self.masm._swap2()?;
+        self.masm._mul()?;
+        self.masm._xor()?;
+
+        self.stack.truncate(self.stack.len() - 3);
+        self.stack.push(None);
+        Ok(())
+    }
+
+    /// Select between `a b` with the comparison `a < b`.
+    fn pick(&mut self, cmp: OpCode, max: bool) -> Result<()> {
+        // [a, b, a < b]
+        self.masm._dup2()?;
+        self.masm._dup2()?;
+        self.masm.emit_op(cmp)?;
+
+        // [a < b, b, a]
+        self.masm._swap2()?;
+        if !max {
+            self.masm._swap1()?;
+        }
+
+        self.stack.push(None);
+        self.select()
+    }
+
+    /// a b -> min(a, b)
+    pub fn min(&mut self) -> Result<()> {
+        self.pick(OpCode::GT, false)
+    }
+
+    /// a b -> max(a, b)
+    pub fn max(&mut self) -> Result<()> {
+        self.pick(OpCode::GT, true)
+    }
+
+    /// a b -> min(a, b), signed
+    pub fn smin(&mut self) -> Result<()> {
+        self.pick(OpCode::SGT, false)
+    }
+
+    /// a b -> max(a, b), signed
+    pub fn smax(&mut self) -> Result<()> {
+        self.pick(OpCode::SGT, true)
+    }
+
+    /// Trap if the value on the top of the stack is not zero.
+    ///
+    /// Jumps to the `PC` instruction itself, which is not a valid
+    /// jump destination.
+    pub fn trap(&mut self) -> Result<()> {
+        self.masm._pc()?;
+        self.masm._jumpi()?;
+        debug_assert_eq!(self.stack.pop(), Some(None), "consuming named item");
+        Ok(())
+    }
+
+    /// x -> the bit length of x
+    pub fn bitlen(&mut self) -> Result<()> {
+        self.set("_x")?;
+        self.num(0)?;
+        self.set("_n")?;
+
+        // binary search of the highest bit
+        for shift in [128, 64, 32, 16, 8, 4, 2, 1] {
+            self.get("_x")?;
+            self.num(shift)?;
+            self.shr()?;
+            self.nonzero()?;
+            self.num(shift)?;
+            self.mul()?;
+            self.set("_c")?;
+
+            self.get("_x")?;
+            self.get("_c")?;
+            self.shr()?;
+            self.set("_x")?;
+            self.get("_n")?;
+            self.get("_c")?;
+            self.add()?;
+            self.set("_n")?;
+            self.drop("_c")?;
+        }
+
+        self.get("_n")?;
+        self.get("_x")?;
+        self.add()?;
+        self.drop_all(&["_x", "_n"])
+    }
+
+    /// x -> x is NaN
+    pub fn isnan(&mut self, f: Float, x: &'static str) -> Result<()> {
+        self.get(x)?;
+        self.num(f.abs())?;
+        self.and()?;
+        self.num(f.inf())?;
+        self.gt()
+    }
+
+    /// x -> x is infinite
+    pub fn isinf(&mut self, f: Float, x: &'static str) -> Result<()> {
+        self.get(x)?;
+        self.num(f.abs())?;
+        self.and()?;
+        self.num(f.inf())?;
+        self.eq()
+    }
+
+    /// x -> x is zero
+    pub fn iszero_f(&mut self, f: Float, x: &'static str) -> Result<()> {
+        self.get(x)?;
+        self.num(f.abs())?;
+        self.and()?;
+        self.iszero()
+    }
+
+    /// x -> the sign bit of x
+    pub fn sign(&mut self, f: Float, x: &'static str) -> Result<()> {
+        self.get(x)?;
+        self.num(f.width() as u128 - 1)?;
+        self.shr()
+    }
+
+    /// Key of x which orders the floats as integers, both zeros
+    /// have the same key.
+    pub fn key(&mut self, f: Float, x: &'static str) -> Result<()> {
+        self.get(x)?;
+        self.num(f.abs())?;
+        self.and()?;
+        self.set("_m")?;
+
+        // sign + m - 2 * s * m
+        self.num(f.sign())?;
+        self.get("_m")?;
+        self.add()?;
+        self.sign(f, x)?;
+        self.get("_m")?;
+        self.mul()?;
+        self.num(1)?;
+        self.shl()?;
+        self.sub()?;
+        self.drop("_m")
+    }
+
+    /// Unpack x into its sign `s`, exponent `k` and significand `m`,
+    /// the value of finite x is `m * 2 ^ (k - bias - man)`.
+    pub fn unpack(
+        &mut self,
+        f: Float,
+        x: &'static str,
+        [s, k, m]: [&'static str; 3],
+    ) -> Result<()> {
+        self.sign(f, x)?;
+        self.set(s)?;
+
+        self.get(x)?;
+        self.num(f.man() as u128)?;
+        self.shr()?;
+        self.num((1 << f.exp()) - 1)?;
+        self.and()?;
+        self.set("_e")?;
+
+        // subnormals have the exponent of the smallest normals
+        self.get("_e")?;
+        self.iszero()?;
+        self.get("_e")?;
+        self.add()?;
+        self.set(k)?;
+
+        // the implicit leading bit of normals
+        self.get(x)?;
+        self.num((1 << f.man()) - 1)?;
+        self.and()?;
+        self.get("_e")?;
+        self.nonzero()?;
+        self.num(f.man() as u128)?;
+        self.shl()?;
+        self.or()?;
+        self.set(m)?;
+        self.drop("_e")
+    }
+
+    /// Pack the sign `s`, the signed exponent `k` and the significand
+    /// `m` of `m * 2 ^ (k - bias - man)` into the float format, rounding
+    /// to the nearest, ties to even.
+    ///
+    /// The significand should be less than 2 ^ 249, the named items
+    /// are consumed.
+    pub fn pack(&mut self, f: Float, [s, k, m]: [&'static str; 3]) -> Result<()> {
+        let man = f.man() as u128;
+
+        // the bits to shift out, normals keep man + 1 bits, subnormals
+        // keep the exponent of the smallest normals.
+        self.get(m)?;
+        self.bitlen()?;
+        self.num(man + 1)?;
+        self.sub()?;
+        self.num(1)?;
+        self.get(k)?;
+        self.sub()?;
+        self.smax()?;
+        self.num(250)?;
+        self.smin()?;
+        self.set("_sh")?;
+
+        self.get("_sh")?;
+        self.num(0)?;
+        self.sgt()?;
+        self.set("_pos")?;
+
+        // q = (m >> right) << left
+        self.get("_sh")?;
+        self.get("_pos")?;
+        self.mul()?;
+        self.set("_right")?;
+        self.get(m)?;
+        self.get("_right")?;
+        self.shr()?;
+        self.num(0)?;
+        self.get("_sh")?;
+        self.sub()?;
+        self.get("_pos")?;
+        self.iszero()?;
+        self.mul()?;
+        self.shl()?;
+        self.set("_q")?;
+
+        // the bits shifted out and the half of the last bit kept
+        self.get(m)?;
+        self.num(1)?;
+        self.get("_right")?;
+        self.shl()?;
+        self.num(1)?;
+        self.sub()?;
+        self.and()?;
+        self.set("_rem")?;
+        self.num(1)?;
+        self.get("_right")?;
+        self.shl()?;
+        self.num(1)?;
+        self.shr()?;
+        self.set("_half")?;
+
+        // round up if rem > half, or rem == half and q is odd
+        self.get("_rem")?;
+        self.get("_half")?;
+        self.gt()?;
+        self.get("_rem")?;
+        self.get("_half")?;
+        self.eq()?;
+        self.get("_q")?;
+        self.num(1)?;
+        self.and()?;
+        self.and()?;
+        self.or()?;
+        self.get("_pos")?;
+        self.and()?;
+        self.get("_q")?;
+        self.add()?;
+        self.set("_q")?;
+        self.drop_all(&["_rem", "_half", "_right", "_pos"])?;
+
+        // the carry of the significand increments the exponent, and
+        // subnormals have the exponent 0.
+        self.get(k)?;
+        self.get("_sh")?;
+        self.add()?;
+        self.num(1)?;
+        self.sub()?;
+        self.num(man)?;
+        self.shl()?;
+        self.get("_q")?;
+        self.add()?;
+        self.get("_q")?;
+        self.nonzero()?;
+        self.mul()?;
+        self.num(f.inf())?;
+        self.min()?;
+
+        self.get(s)?;
+        self.num(f.width() as u128 - 1)?;
+        self.shl()?;
+        self.or()?;
+        self.drop_all(&["_q", "_sh", s, k, m])
+    }
+}
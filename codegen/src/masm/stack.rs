@@ -7,4 +7,20 @@ impl MacroAssembler {
     pub fn _drop(&mut self) -> Result<()> {
         self._pop()
     }
+
+    /// Drop `count` items under the top `keep` items of the stack.
+    pub fn _drop_under(&mut self, count: u16, keep: u16) -> Result<()> {
+        for _ in 0..count {
+            // [a, v1, .., vk] -> [vk, v1, .., vk-1]
+            self.swap(keep)?;
+            self._drop()?;
+
+            // rotate vk back to the top
+            for index in 1..keep {
+                self.swap(index)?;
+            }
+        }
+
+        Ok(())
+    }
 }
@@ -0,0 +1,258 @@
+//! Peephole optimizer
+//!
+//! Rewrites short windows of instructions in the generated bytecode
+//! before the relocation, the program counters registered in the jump
+//! table are remapped along with the rewrites, so jumps stay consistent.
+
+use crate::{Buffer, JumpTable};
+use opcodes::{Cancun as OpCode, OpCode as _};
+pub use rules::RULES;
+use std::collections::BTreeMap;
+
+mod rules;
+
+/// A peephole rule.
+#[derive(Clone, Copy, Debug)]
+pub struct Rule {
+    /// Name of the rule.
+    pub name: &'static str,
+    /// Number of instructions in the window.
+    pub window: usize,
+    /// Rewrite the instructions in the window, returns `None` if the
+    /// window doesn't match.
+    ///
+    /// The instruction following the window, if any, is passed after
+    /// the window as context, it is kept as it is. The rewritten
+    /// bytecode must be shorter than the window.
+    pub rewrite: fn(&[&[u8]]) -> Option<Vec<u8>>,
+}
+
+/// Statistics of a rule.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Stats {
+    /// Times the rule applied.
+    pub hits: usize,
+    /// Bytes saved by the rule.
+    pub saved: usize,
+}
+
+/// A rewrite of the bytecode.
+struct Edit {
+    /// Start of the window.
+    start: u16,
+    /// End of the window.
+    end: u16,
+    /// The rewritten bytecode.
+    bytecode: Vec<u8>,
+}
+
+/// Peephole optimizer.
+#[derive(Clone, Debug)]
+pub struct Peephole {
+    /// Rules to apply, in the order of priority.
+    rules: Vec<Rule>,
+    /// Statistics of the rules.
+    stats: BTreeMap<&'static str, Stats>,
+    /// Gas saved at the program counters of the original bytecode.
+    saved: BTreeMap<u16, u64>,
+}
+
+impl Default for Peephole {
+    fn default() -> Self {
+        Self::new(RULES.to_vec())
+    }
+}
+
+impl Peephole {
+    /// Create a new optimizer with rules.
+    pub fn new(rules: Vec<Rule>) -> Self {
+        Self {
+            rules,
+            stats: Default::default(),
+            saved: Default::default(),
+        }
+    }
+
+    /// With an extra rule.
+    pub fn rule(mut self, rule: Rule) -> Self {
+        self.rules.push(rule);
+        self
+    }
+
+    /// Get the statistics of the applied rules.
+    pub fn stats(&self) -> &BTreeMap<&'static str, Stats> {
+        &self.stats
+    }
+
+    /// Get the gas saved by the rewrites at the program counters of
+    /// the bytecode before the optimization.
+    pub fn saved(&self) -> &BTreeMap<u16, u64> {
+        &self.saved
+    }
+
+    /// Optimize the bytecode until no rule applies.
+    ///
+    /// *WARNING*: This function should be called before relocating
+    /// the jump table.
+    pub fn run(&mut self, buffer: &mut Buffer, table: &mut JumpTable) {
+        let mut passes: Vec<Vec<Edit>> = Vec::new();
+        loop {
+            let edits = self.edits(buffer, table);
+            if edits.is_empty() {
+                return;
+            }
+
+            for edit in edits.iter() {
+                let gas = gas(&buffer[edit.start as usize..edit.end as usize])
+                    .saturating_sub(gas(&edit.bytecode));
+                let pc = passes
+                    .iter()
+                    .rev()
+                    .fold(edit.start, |pc, edits| unmap(edits, pc));
+                *self.saved.entry(pc).or_default() += gas;
+            }
+
+            let mut optimized = Buffer::new();
+            let mut pc = 0;
+            for edit in edits.iter() {
+                optimized.extend_from_slice(&buffer[pc..edit.start as usize]);
+                optimized.extend_from_slice(&edit.bytecode);
+                pc = edit.end as usize;
+            }
+
+            optimized.extend_from_slice(&buffer[pc..]);
+            *buffer = optimized;
+            table.remap(|pc| remap(&edits, pc));
+            passes.push(edits);
+        }
+    }
+
+    /// Find the rewrites of a pass over the bytecode.
+    fn edits(&mut self, buffer: &[u8], table: &JumpTable) -> Vec<Edit> {
+        let instrs = instrs(buffer);
+        let targets = table.targets();
+        let mut edits = Vec::new();
+
+        let mut index = 0;
+        'instrs: while index < instrs.len() {
+            for rule in self.rules.iter() {
+                if index + rule.window > instrs.len() {
+                    continue;
+                }
+
+                let start = instrs[index].0;
+                let end = instrs
+                    .get(index + rule.window)
+                    .map(|(pc, _)| *pc)
+                    .unwrap_or(buffer.len() as u16);
+
+                // Relocated jumps insert bytes right before their program
+                // counters, and only duplicated jump destinations could
+                // collapse into one.
+                let window = instrs[index..(index + rule.window + 1).min(instrs.len())]
+                    .iter()
+                    .map(|(_, instr)| *instr)
+                    .collect::<Vec<_>>();
+                if table.jump.range(start..end).next().is_some()
+                    || (targets.range(start + 1..end).next().is_some()
+                        && !window[..rule.window]
+                            .iter()
+                            .all(|instr| *instr == [rules::JUMPDEST]))
+                {
+                    continue;
+                }
+
+                let Some(bytecode) = (rule.rewrite)(&window) else {
+                    continue;
+                };
+
+                let len = (end - start) as usize;
+                if bytecode.len() >= len {
+                    continue;
+                }
+
+                let stats = self.stats.entry(rule.name).or_default();
+                stats.hits += 1;
+                stats.saved += len - bytecode.len();
+                tracing::trace!("peephole {}: 0x{start:x}..0x{end:x}", rule.name);
+
+                edits.push(Edit {
+                    start,
+                    end,
+                    bytecode,
+                });
+                index += rule.window;
+                continue 'instrs;
+            }
+
+            index += 1;
+        }
+
+        edits
+    }
+}
+
+/// Split the bytecode into instructions with their program counters.
+fn instrs(buffer: &[u8]) -> Vec<(u16, &[u8])> {
+    let mut instrs = Vec::new();
+    let mut pc = 0;
+    while pc < buffer.len() {
+        let end = (pc + 1 + rules::immediates(buffer[pc])).min(buffer.len());
+        instrs.push((pc as u16, &buffer[pc..end]));
+        pc = end;
+    }
+
+    instrs
+}
+
+/// Base gas of the instructions.
+fn gas(bytecode: &[u8]) -> u64 {
+    instrs(bytecode)
+        .into_iter()
+        .map(|(_, instr)| OpCode::from(instr[0]).gas() as u64)
+        .sum()
+}
+
+/// Map a program counter of the bytecode to the rewritten one.
+///
+/// Program counters inside a window are mapped to the start of it.
+fn remap(edits: &[Edit], pc: u16) -> u16 {
+    let mut removed = 0;
+    for edit in edits {
+        if pc < edit.start {
+            break;
+        }
+
+        if pc < edit.end {
+            return edit.start - removed;
+        }
+
+        removed += edit.end - edit.start - edit.bytecode.len() as u16;
+    }
+
+    pc - removed
+}
+
+/// Map a program counter of the rewritten bytecode back to the
+/// original one.
+///
+/// Program counters inside a rewritten window are mapped to the start
+/// of the window.
+fn unmap(edits: &[Edit], pc: u16) -> u16 {
+    let mut removed = 0;
+    for edit in edits {
+        let start = edit.start - removed;
+        if pc < start {
+            break;
+        }
+
+        if pc < start + edit.bytecode.len() as u16 {
+            return edit.start;
+        }
+
+        removed += edit.end - edit.start - edit.bytecode.len() as u16;
+    }
+
+    pc + removed
+}
@@ -0,0 +1,148 @@
+//! Peephole rules
+//!
+//! Every rule rewrites its window to an equivalent sequence for any
+//! stack, so it is safe wherever the window matches.
+
+use super::Rule;
+use opcodes::ShangHai as OpCode;
+
+/// Opcode of `JUMPDEST`.
+pub const JUMPDEST: u8 = 0x5b;
+
+/// Default rules of the optimizer.
+pub const RULES: [Rule; 8] = [
+    Rule {
+        name: "push-pop",
+        window: 2,
+        rewrite: push_pop,
+    },
+    Rule {
+        name: "dup-pop",
+        window: 2,
+        rewrite: dup_pop,
+    },
+    Rule {
+        name: "swap-swap",
+        window: 2,
+        rewrite: swap_swap,
+    },
+    Rule {
+        name: "swap-commutative",
+        window: 2,
+        rewrite: swap_commutative,
+    },
+    Rule {
+        name: "iszero-iszero-jumpi",
+        window: 2,
+        rewrite: iszero_iszero_jumpi,
+    },
+    Rule {
+        name: "jumpdest-jumpdest",
+        window: 2,
+        rewrite: jumpdest_jumpdest,
+    },
+    Rule {
+        name: "store-load",
+        window: 4,
+        rewrite: store_load,
+    },
+    Rule {
+        name: "load-load",
+        window: 4,
+        rewrite: load_load,
+    },
+];
+
+/// Number of the immediate bytes of an opcode.
+pub fn immediates(opcode: u8) -> usize {
+    match opcode {
+        0x60..=0x7f => (opcode - 0x5f) as usize,
+        _ => 0,
+    }
+}
+
+/// If the instruction pushes a value.
+fn is_push(instr: &[u8]) -> bool {
+    (0x5f..=0x7f).contains(&instr[0])
+}
+
+/// If the instruction is the opcode.
+fn is(instr: &[u8], opcode: OpCode) -> bool {
+    instr == [u8::from(opcode)]
+}
+
+/// `PUSHn x POP` -> ``
+fn push_pop(window: &[&[u8]]) -> Option<Vec<u8>> {
+    (is_push(window[0]) && is(window[1], OpCode::POP)).then(Vec::new)
+}
+
+/// `DUPn POP` -> ``
+fn dup_pop(window: &[&[u8]]) -> Option<Vec<u8>> {
+    ((0x80..=0x8f).contains(&window[0][0]) && is(window[1], OpCode::POP)).then(Vec::new)
+}
+
+/// `SWAPn SWAPn` -> ``
+fn swap_swap(window: &[&[u8]]) -> Option<Vec<u8>> {
+    ((0x90..=0x9f).contains(&window[0][0]) && window[0] == window[1]).then(Vec::new)
+}
+
+/// `SWAP1 op` -> `op` for the commutative operators.
+fn swap_commutative(window: &[&[u8]]) -> Option<Vec<u8>> {
+    let commutative = [
+        OpCode::ADD,
+        OpCode::MUL,
+        OpCode::AND,
+        OpCode::OR,
+        OpCode::XOR,
+        OpCode::EQ,
+    ];
+
+    (is(window[0], OpCode::SWAP1) && commutative.iter().any(|op| is(window[1], *op)))
+        .then(|| window[1].to_vec())
+}
+
+/// `ISZERO ISZERO` -> `` before `JUMPI`, which only checks if the
+/// condition is non-zero.
+fn iszero_iszero_jumpi(window: &[&[u8]]) -> Option<Vec<u8>> {
+    (is(window[0], OpCode::ISZERO)
+        && is(window[1], OpCode::ISZERO)
+        && window.get(2).is_some_and(|next| is(next, OpCode::JUMPI)))
+    .then(Vec::new)
+}
+
+/// `JUMPDEST JUMPDEST` -> `JUMPDEST`
+fn jumpdest_jumpdest(window: &[&[u8]]) -> Option<Vec<u8>> {
+    (window[0] == [JUMPDEST] && window[1] == [JUMPDEST]).then(|| vec![JUMPDEST])
+}
+
+/// `PUSHn x MSTORE PUSHn x MLOAD` -> `DUP1 PUSHn x MSTORE`
+fn store_load(window: &[&[u8]]) -> Option<Vec<u8>> {
+    if !(is_push(window[0])
+        && is(window[1], OpCode::MSTORE)
+        && window[0] == window[2]
+        && is(window[3], OpCode::MLOAD))
+    {
+        return None;
+    }
+
+    let mut bytecode = vec![OpCode::DUP1.into()];
+    bytecode.extend_from_slice(window[0]);
+    bytecode.push(OpCode::MSTORE.into());
+    Some(bytecode)
+}
+
+/// `PUSHn x MLOAD PUSHn x MLOAD` -> `PUSHn x MLOAD DUP1`
+fn load_load(window: &[&[u8]]) -> Option<Vec<u8>> {
+    if !(is_push(window[0])
+        && is(window[1], OpCode::MLOAD)
+        && window[0] == window[2]
+        && is(window[3], OpCode::MLOAD))
+    {
+        return None;
+    }
+
+    let mut bytecode = window[0].to_vec();
+    bytecode.push(OpCode::MLOAD.into());
+    bytecode.push(OpCode::DUP1.into());
+    Some(bytecode)
+}
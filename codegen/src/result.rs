@@ -27,6 +27,9 @@ pub enum Error {
     /// Failed to merge jump table.
     #[error("Program counter {0} already exists in jump table")]
     DuplicateJump(u16),
+    /// Failed to lower the bytecode to EOF.
+    #[error("Failed to emit EOF: {0}")]
+    Eof(String),
     /// Failed to find ext function index in jump table.
     #[error("External function not found in jump table")]
     ExtFuncNotFound,
@@ -48,6 +51,12 @@ pub enum Error {
     /// Failed parse function signature.
     #[error("Invalid function signature")]
     InvalidFunctionSignature,
+    /// Failed to get global with given index.
+    #[error("Invalid global index {0}")]
+    InvalidGlobalIndex(u32),
+    /// Failed to set immutable global.
+    #[error("Global {0} is immutable")]
+    ImmutableGlobal(u32),
     /// Failed to get local with given index.
     #[error("Invalid local index {0}")]
     InvalidLocalIndex(usize),
@@ -69,6 +78,9 @@ pub enum Error {
     /// Failed to parse function selector.
     #[error("Invalid function selector")]
     InvalidSelector,
+    /// Failed to get function type with given index.
+    #[error("Invalid type index {0}")]
+    InvalidTypeIndex(u32),
     /// Failed to patch jump destination.
     #[error("Invalid frame label")]
     LabelMismatch,
@@ -81,12 +93,18 @@ pub enum Error {
     /// Failed to index data on memory.
     #[error("Memory index is out of range")]
     MemoryOutOfBounds,
+    /// Failed to find a data segment by its index.
+    #[error("Data segment {0} not found")]
+    SegmentNotFound(u32),
     /// Failed to find function selectors.0
     #[error("Function selector is not found.")]
     SelectorNotFound,
     /// Failed to index data on stack.
     #[error("Stack index is out of range {0}, max is 255 (0x400)")]
     StackIndexOutOfRange(u16),
+    /// The modeled stack height diverges from the stack pointer.
+    #[error("Stack mismatch, current stack items {0}, expect {1}")]
+    StackMismatch(u16, u16),
     /// Failed to increment stack pointer.
     #[error("Stack overflow, max is 1024 stack items, but add {1} to {0}")]
     StackOverflow(u16, u16),
@@ -96,9 +114,22 @@ pub enum Error {
     /// Failed to pop stack.
     #[error("Stack not balanced, current stack items {0}")]
     StackNotBalanced(u16),
+    /// Failed to compile float operator without soft float.
+    #[error("Float operator {0} requires soft float, which is disabled")]
+    SoftFloatDisabled(String),
+    /// Failed to emit an opcode which is not available on the target fork.
+    #[error("Opcode {0:?} is not available on the target fork {1}")]
+    UnavailableOpcode(opcodes::Cancun, opcodes::Upgrade),
     /// Failed to queue host functions.
     #[error("Unsupported host function {0:?}")]
     UnsupportedHostFunc(crate::wasm::HostFunc),
+    /// Failed to compile operator, with the function and the byte
+    /// offset of the operator in the module.
+    #[error("Unsupported operator {0} in function {1} at offset {2:#x}")]
+    UnsupportedOperator(String, String, usize),
+    /// Failed to compile a value type.
+    #[error("Unsupported value type {0}")]
+    UnsupportedType(String),
 }
 
 /// Codegen result
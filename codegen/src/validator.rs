@@ -16,33 +16,36 @@ macro_rules! validate_then_visit {
                 // ignored except for If, Block, Loop, Else and End. These operators need
                 // to be observed in order to keep the control stack frames balanced and to
                 // determine if reachability should be restored.
-                let visit_when_unreachable = visit_op_when_unreachable(Operator::$op $({ $($arg: $arg.clone()),* })?);
-                if true || visit_when_unreachable  {
-                    Ok(self.1.$visit($($($arg),*)?))
-                } else {
-                    Ok(Ok(()))
+                let op = Operator::$op $({ $($arg: $arg.clone()),* })?;
+                if !self.1.reachable {
+                    return Ok(if visit_op_when_unreachable(&op) {
+                        self.1.observe_unreachable(&op)
+                    } else {
+                        Ok(())
+                    });
                 }
+
+                let result = self.1.$visit($($($arg),*)?);
+                if ends_reachability(&op) {
+                    self.1.reachable = false;
+                }
+
+                Ok(result)
             }
         )*
     };
 }
 
-fn visit_op_when_unreachable(op: Operator) -> bool {
+fn visit_op_when_unreachable(op: &Operator) -> bool {
     use Operator::*;
     matches!(op, If { .. } | Block { .. } | Loop { .. } | Else | End)
 }
 
-// /// Trait to handle reachability state.
-// trait ReachableState {
-//     /// Returns true if the current state of the program is reachable.
-//     fn is_reachable(&self) -> bool;
-// }
-//
-// impl ReachableState for Function {
-//     fn is_reachable(&self) -> bool {
-//         true
-//     }
-// }
+/// If the code following the operator is unreachable.
+fn ends_reachability(op: &Operator) -> bool {
+    use Operator::*;
+    matches!(op, Unreachable | Return | Br { .. } | BrTable { .. })
+}
 
 impl<'a, T> VisitOperator<'a> for ValidateThenVisit<'_, T>
 where
@@ -0,0 +1,60 @@
+//! Bit counting and rotation instructions
+//!
+//! These instructions are compiled to shared functions in the code
+//! section, the bit width of the operands is passed on the stack, so
+//! the 32-bit and the 64-bit variants share the same function.
+
+use crate::{codegen::ExtFunc, masm::MacroAssembler, Function, Result};
+
+impl Function {
+    /// Count leading zero bits.
+    pub fn _clz(&mut self, bits: u8) -> Result<()> {
+        self.masm.push(&[bits])?;
+        self.call_ext(ExtFunc::new(self.env.target, 2, MacroAssembler::_clz)?)
+    }
+
+    /// Count trailing zero bits.
+    pub fn _ctz(&mut self, bits: u8) -> Result<()> {
+        self.masm.push(&[bits])?;
+        self.call_ext(ExtFunc::new(self.env.target, 2, MacroAssembler::_ctz)?)
+    }
+
+    /// Count one bits.
+    pub fn _popcnt(&mut self, _bits: u8) -> Result<()> {
+        self.call_ext(ExtFunc::new(self.env.target, 1, MacroAssembler::_popcnt)?)
+    }
+
+    /// Rotate left.
+    pub fn _rotl(&mut self, bits: u8) -> Result<()> {
+        self.masm.push(&[bits])?;
+        self.call_ext(ExtFunc::new(self.env.target, 3, MacroAssembler::_rotl)?)
+    }
+
+    /// Rotate right.
+    pub fn _rotr(&mut self, bits: u8) -> Result<()> {
+        self.masm.push(&[bits])?;
+        self.call_ext(ExtFunc::new(self.env.target, 3, MacroAssembler::_rotr)?)
+    }
+
+    /// Call a function in the code section.
+    ///
+    /// STACK: [inputs..] -> [output]
+    pub(crate) fn call_ext(&mut self, func: ExtFunc) -> Result<()> {
+        let (stack_in, stack_out) = (func.stack_in as u16, func.stack_out as u16);
+
+        // Register the label to jump back.
+        let return_pc = self.masm.pc() + 2;
+        self.table.label(self.masm.pc(), return_pc);
+        self.masm.asm.increment_relocation_gas();
+        self.masm._jumpdest()?;
+
+        // Register the function in the code section.
+        self.table.ext(self.masm.pc(), func);
+        self.masm.increment_sp(1)?;
+        self.masm._jump()?;
+
+        self.masm._jumpdest()?;
+        self.masm.decrement_sp(stack_in)?;
+        self.masm.increment_sp(stack_out)
+    }
+}
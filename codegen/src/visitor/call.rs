@@ -6,22 +6,212 @@
 //! calls.
 
 use crate::{
+    visitor::ENTRY_SIZE,
     wasm::{HostFunc, ToLSBytes},
     Error, Function, Result,
 };
-use anyhow::anyhow;
 use opcodes::Cancun as OpCode;
 
 impl Function {
-    /// The call indirect instruction calls a function indirectly                                                                                                                                          
-    /// through an operand indexing into a table.                                                                                                                                                          
+    /// The call indirect instruction calls a function indirectly
+    /// through an operand indexing into a table.
+    ///
+    /// Since the table is static, the index jumps to an entry of a
+    /// computed jump table, which leads to the landing pad storing the
+    /// parameters of the function in the slot. Null slots, type
+    /// mismatches and out-of-bounds indexes revert.
+    ///
+    /// STACK: [params, index] -> [results]
     pub fn _call_indirect(
         &mut self,
-        _type_index: u32,
+        type_index: u32,
         _table_index: u32,
         _table_byte: u8,
     ) -> Result<()> {
-        todo!()
+        let ty = self
+            .env
+            .types
+            .get(&type_index)
+            .ok_or(Error::InvalidTypeIndex(type_index))?
+            .clone();
+
+        let slots = self
+            .env
+            .table
+            .keys()
+            .max()
+            .map(|slot| slot + 1)
+            .unwrap_or_default();
+        let funcs = (0..slots)
+            .map(|slot| {
+                self.env
+                    .table
+                    .get(&slot)
+                    .filter(|func| self.env.func_type(**func) == Some(&ty))
+                    .copied()
+            })
+            .collect::<Vec<_>>();
+
+        // computed jump tables are not available in EOF.
+        if self.env.eof {
+            return self.call_indirect_linear(&funcs);
+        }
+
+        // Locals live in fixed memory slots, save them before they get
+        // overwritten by the recursive calls.
+        let recursive = funcs.iter().flatten().any(|f| self.env.is_recursive(*f));
+        let frame = if recursive {
+            self.frame()
+        } else {
+            Default::default()
+        };
+
+        if !frame.is_empty() {
+            self.touch_heap();
+            self.masm._push_frame(&self.env.memory, &frame)?;
+        }
+
+        // revert if index >= slots
+        self.masm._dup1()?;
+        self.masm.push(&slots.to_ls_bytes())?;
+        self.masm.asm._gt()?;
+        self.masm._iszero()?;
+        let out_of_bounds = self.masm.pc();
+        self.masm.increment_sp(1)?;
+        self.masm._jumpi()?;
+
+        // jump to base + index * ENTRY_SIZE
+        self.masm.push(&[ENTRY_SIZE])?;
+        self.masm._mul()?;
+        let jump_base = self.masm.pc();
+        self.masm.increment_sp(1)?;
+        self.masm._add()?;
+        self.masm._jump()?;
+        self.table.label(jump_base, self.masm.pc());
+
+        let height = self.masm.sp();
+        let mut entries = Vec::with_capacity(funcs.len());
+        for func in &funcs {
+            self.masm._jumpdest()?;
+            entries.push((self.masm.pc(), *func));
+            self.masm.increment_sp(1)?;
+            self.masm._jump()?;
+        }
+
+        // the landing pads storing the parameters and calling the function.
+        let params = ty.params().len() as u16;
+        let mut pads: Vec<(u32, u16)> = Vec::new();
+        let mut returns = Vec::new();
+        let mut nulls = Vec::new();
+        for (entry, func) in entries {
+            let Some(func) = func else {
+                nulls.push(entry);
+                continue;
+            };
+
+            let pad = match pads.iter().find(|(landed, _)| *landed == func) {
+                Some((_, pad)) => *pad,
+                None => {
+                    let pad = self.masm.pc();
+                    self.masm.asm.sp = height;
+                    self.masm._jumpdest()?;
+                    self.masm.increment_sp(1)?;
+
+                    let reserved = self.env.slots.get(&func).unwrap_or(&0);
+                    for i in (0..params as u32).rev() {
+                        self.masm.push(&((i + reserved) * 0x20).to_ls_bytes())?;
+                        self.masm._mstore()?;
+                    }
+
+                    // the return address is shared by all pads.
+                    returns.push(self.masm.pc());
+                    self.masm.asm.increment_relocation_gas();
+                    self.masm._jumpdest()?;
+                    self.table.call(self.masm.pc(), func);
+                    self.masm._jump()?;
+
+                    pads.push((func, pad));
+                    pad
+                }
+            };
+
+            self.table.entry(entry, pad);
+        }
+
+        // revert on null slots, type mismatches and out-of-bounds indexes
+        let revert = self.masm.pc();
+        self.masm._jumpdest()?;
+        self.masm._push0()?;
+        self.masm._push0()?;
+        self.masm._revert()?;
+        self.table.label(out_of_bounds, revert);
+        for entry in nulls {
+            self.table.entry(entry, revert);
+        }
+
+        // the shared return of the calls
+        let landing = self.masm.pc();
+        for pc in returns {
+            self.table.label(pc, landing);
+        }
+
+        self.masm._jumpdest()?;
+        self.masm.asm.sp = height - params;
+        self.masm.increment_sp(ty.results().len() as u16)?;
+        if !frame.is_empty() {
+            self.masm._pop_frame(&self.env.memory, &frame)?;
+        }
+
+        Ok(())
+    }
+
+    /// Compare the index on the stack against every slot holding a
+    /// function of the expected type, and jump to the landing pad
+    /// performing a regular internal call.
+    ///
+    /// STACK: [params, index] -> [results]
+    fn call_indirect_linear(&mut self, funcs: &[Option<u32>]) -> Result<()> {
+        // dispatch the index to the landing pads
+        let height = self.masm.sp();
+        let mut pads = Vec::with_capacity(funcs.len());
+        for (slot, func) in funcs.iter().enumerate() {
+            let Some(func) = func else {
+                continue;
+            };
+
+            self.masm._dup1()?;
+            self.masm.push(&slot.to_ls_bytes())?;
+            self.masm._eq()?;
+            pads.push((self.masm.pc(), *func));
+            self.masm.increment_sp(1)?;
+            self.masm._jumpi()?;
+        }
+
+        // revert on null slots, type mismatches and out-of-bounds indexes
+        self.masm._push0()?;
+        self.masm._push0()?;
+        self.masm._revert()?;
+
+        // call the function and jump to the join
+        let mut joins = Vec::with_capacity(pads.len());
+        let mut results = height;
+        for (jump, func) in pads {
+            self.masm.asm.sp = height;
+            self.table.label(jump, self.masm.pc());
+            self.masm._jumpdest()?;
+            self.masm._drop()?;
+            self.call_internal(func)?;
+            results = self.masm.sp();
+            joins.push(self.masm.pc());
+            self.masm.increment_sp(1)?;
+            self.masm._jump()?;
+        }
+
+        self.masm.asm.sp = results;
+        for join in joins {
+            self.table.label(join, self.masm.pc());
+        }
+        self.masm._jumpdest()
     }
 
     /// Calls a function specified by its index.
@@ -30,12 +220,12 @@ impl Function {
     /// function. If it is an external function, it will call the `call_imported` method.
     /// Otherwise, it will call the `call_internal` method to handle the internal function call.
     ///
-    /// # Panics
+    /// # Errors
     ///
-    /// If an attempt is made to call an external function internally, this function will panic.
+    /// Calling an external function internally is not supported yet.
     pub fn _call(&mut self, index: u32) -> Result<()> {
         if self.env.is_external(index) {
-            panic!("External functions could not be called internally");
+            return Err(self.unsupported("Call"));
         }
 
         if self.env.imports.len() as u32 > index {
@@ -48,31 +238,38 @@ impl Function {
     /// Calls an internal function specified by its index.
     ///
     /// This function handles the mechanics of calling an internal function, including:
-    /// - Checking for recursion and returning an error if detected.
+    /// - Saving the locals of the current function if the callee could call it again.
     /// - Recording the current program counter (PC) to manage the return address.
     /// - Adjusting the stack to accommodate parameters and the return address.
     /// - Storing parameters in memory and registering the call index in the jump table.
     ///
     /// # Errors
     ///
-    /// Returns an error if recursion is detected or if the function index is invalid.
+    /// Returns an error if the function index is invalid.
     fn call_internal(&mut self, index: u32) -> Result<()> {
-        if self.env.index == Some(index) {
-            return Err(anyhow!(
-                "Recursion is no longer supported in this version. See https://github.com/zink-lang/zink/issues/248"
-            ).into());
-        }
-
         tracing::debug!("Calling internal function: index={index}");
-        let reserved = self.env.slots.get(&index).unwrap_or(&0);
-        let (params, results) = self.env.funcs.get(&index).unwrap_or(&(0, 0));
+        let reserved = *self.env.slots.get(&index).unwrap_or(&0);
+        let (params, results) = *self.env.funcs.get(&index).unwrap_or(&(0, 0));
+
+        // Locals live in fixed memory slots, save them before they get
+        // overwritten by the recursive calls.
+        let frame = if self.env.is_recursive(index) {
+            self.frame()
+        } else {
+            Default::default()
+        };
+
+        if !frame.is_empty() {
+            self.touch_heap();
+            self.masm._push_frame(&self.env.memory, &frame)?;
+        }
 
         // TODO This is a temporary fix to avoid stack underflow.
         // We need to find a more elegant solution for this.
         self.masm.increment_sp(1)?;
 
         // Store parameters in memory and register the call index in the jump table.
-        for i in (0..*params).rev() {
+        for i in (0..params).rev() {
             tracing::trace!("Storing local at {} for function {index}", i + reserved);
             self.masm.push(&((i + reserved) * 0x20).to_ls_bytes())?;
             self.masm._mstore()?;
@@ -81,6 +278,7 @@ impl Function {
         // Register the label to jump back.
         let return_pc = self.masm.pc() + 2;
         self.table.label(self.masm.pc(), return_pc);
+        self.masm.asm.increment_relocation_gas();
         self.masm._jumpdest()?; // TODO: support same pc different label
 
         // Register the call index in the jump table.
@@ -89,10 +287,32 @@ impl Function {
 
         // Adjust the stack pointer for the results.
         self.masm._jumpdest()?;
-        self.masm.increment_sp(*results as u16)?;
+        self.masm.increment_sp(results as u16)?;
+
+        if !frame.is_empty() {
+            self.masm._pop_frame(&self.env.memory, &frame)?;
+        }
+
         Ok(())
     }
 
+    /// The memory slots of the locals of the current function.
+    ///
+    /// Parameters from calldata are not included since they are
+    /// never stored in memory.
+    fn frame(&self) -> Vec<usize> {
+        let reserved = self.env.reserved() as usize;
+        let params = if self.is_main || self.abi.is_some() {
+            self.ty.params().len()
+        } else {
+            0
+        };
+
+        (params..self.locals.len())
+            .map(|index| (index + reserved) * 0x20)
+            .collect()
+    }
+
     /// Calls an imported function specified by its index.
     ///
     /// This function retrieves the imported function from the environment and executes it.
@@ -112,6 +332,15 @@ impl Function {
             .ok_or(Error::ImportedFuncNotFound(index))?;
 
         tracing::trace!("Calling imported function, index={index}, func={func:?}");
+        if func.uses_heap() {
+            self.touch_heap();
+        }
+
+        // the logs read their data from the bytecode, the arguments they
+        // leave on the stack are dropped.
+        let (params, _) = *self.env.funcs.get(&index).unwrap_or(&(0, 0));
+        let height = self.masm.sp().saturating_sub(params as u16);
+
         match func {
             HostFunc::Evm(OpCode::LOG0) => self.log(0),
             HostFunc::Evm(OpCode::LOG1) => self.log(1),
@@ -120,12 +349,25 @@ impl Function {
             HostFunc::Evm(OpCode::LOG4) => self.log(4),
             HostFunc::Evm(op) => self.masm.emit_op(op),
             HostFunc::U256MAX => self.masm.push(&[255; 32]),
-            HostFunc::Revert(count) => self.revert(count),
+            HostFunc::Revert => self.revert(),
+            HostFunc::Panic => self.masm.revert_panic(),
+            HostFunc::Alloc => self.masm._alloc(&self.env.memory),
+            HostFunc::FreeMemoryPointer => self.masm._free_memory_pointer(&self.env.memory),
             HostFunc::NoOp | HostFunc::Label(_) => Ok(()),
             _ => {
                 tracing::error!("Unsupported host function {func:?}");
                 Err(Error::UnsupportedHostFunc(func))
             }
+        }?;
+
+        if matches!(
+            func,
+            HostFunc::Evm(OpCode::LOG0 | OpCode::LOG1 | OpCode::LOG2 | OpCode::LOG3 | OpCode::LOG4)
+        ) {
+            let extra = self.masm.sp().saturating_sub(height);
+            self.masm._drop_under(extra, 0)?;
         }
+
+        Ok(())
     }
 }
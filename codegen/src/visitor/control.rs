@@ -1,10 +1,13 @@
 //! Control flow visitors
 
 use crate::{
+    codegen::ExtFunc,
     control::{ControlStackFrame, ControlStackFrameType},
-    Function, Result,
+    visitor::ENTRY_SIZE,
+    wasm::ToLSBytes,
+    Error, Function, GasBlock, Result,
 };
-use wasmparser::{BlockType, BrTable};
+use wasmparser::{BlockType, BrTable, Operator};
 
 impl Function {
     /// The beginning of an if construct with an implicit block.
@@ -12,14 +15,9 @@ impl Function {
         // Emit iszero to check the condition.
         self.masm._iszero()?;
 
-        // push an `If` frame to the control stack
-        let frame = ControlStackFrame::new(
-            ControlStackFrameType::If(false),
-            self.masm.pc(),
-            self.masm.sp(),
-            blockty,
-        );
-        self.control.push(frame);
+        // push an `If` frame to the control stack, the condition
+        // will be consumed by the jump.
+        let mut frame = self.control_frame(ControlStackFrameType::If(false), blockty, 1)?;
 
         // mock the stack output of the counter
         //
@@ -27,19 +25,16 @@ impl Function {
         self.masm.asm.increment_sp(1)?;
         self.masm._jumpi()?;
 
+        // the jump is shared by both of the branches.
+        frame.gas = self.masm.asm.gas();
+        self.control.push(frame);
         Ok(())
     }
 
     /// The begeinning of a block construct. A sequence of
     /// instructions with a label at the end.
     pub fn _block(&mut self, blockty: BlockType) -> Result<()> {
-        let frame = ControlStackFrame::new(
-            ControlStackFrameType::Block,
-            self.masm.pc(),
-            self.masm.sp(),
-            blockty,
-        );
-        self.masm._jumpdest()?;
+        let frame = self.control_frame(ControlStackFrameType::Block, blockty, 0)?;
         self.control.push(frame);
 
         Ok(())
@@ -48,12 +43,7 @@ impl Function {
     /// A block with a label which may be used to
     /// form loops.
     pub fn _loop(&mut self, blockty: BlockType) -> Result<()> {
-        let frame = ControlStackFrame::new(
-            ControlStackFrameType::Loop,
-            self.masm.pc(),
-            self.masm.sp(),
-            blockty,
-        );
+        let frame = self.control_frame(ControlStackFrameType::Loop, blockty, 0)?;
 
         self.masm._jumpdest()?;
         self.control.push(frame);
@@ -64,23 +54,28 @@ impl Function {
     /// Marks an else block of an if.
     pub fn _else(&mut self) -> Result<()> {
         let last_frame = self.control.mark_else()?;
+        self.check_height(last_frame.end())?;
 
         // push an `Else` frame to the control stack.
-        let frame = ControlStackFrame::new(
+        let mut frame = ControlStackFrame::new(
             ControlStackFrameType::Else,
             self.masm.pc(),
-            self.masm.sp(),
+            last_frame.original_sp,
             last_frame.result(),
+            last_frame.io(),
         );
-        self.control.push(frame);
         self.masm.asm.increment_sp(1)?;
         self.masm._jump()?;
+        frame.gas = self.masm.asm.gas();
+        self.control.push(frame);
 
         // mark else as the jump destination of the if block.
         self.table
             .label(last_frame.original_pc_offset, self.masm.pc());
         self.masm._jumpdest()?;
 
+        // the else branch starts with the stack of the if block.
+        self.masm.asm.sp = last_frame.original_sp + last_frame.params();
         Ok(())
     }
 
@@ -88,14 +83,16 @@ impl Function {
     /// on whether its third oprand is zero or not.
     ///
     /// STACK: [cond, val2, val1] -> \[val1\] if cond is non-zero, \[val2\] otherwise.
+    ///
+    /// Both of the branches leave the same stack height at the join.
     pub fn _select(&mut self) -> Result<()> {
         tracing::trace!("select");
-        self.masm._iszero()?;
         self.masm.increment_sp(1)?;
         self.table.label(self.masm.pc(), self.masm.pc() + 2);
         self.masm._jumpi()?;
-        self.masm._drop()?;
+        self.masm._swap1()?;
         self.masm._jumpdest()?;
+        self.masm._drop()?;
 
         Ok(())
     }
@@ -104,42 +101,42 @@ impl Function {
     ///
     /// Performs an unconditional branch.
     pub fn _br(&mut self, depth: u32) -> Result<()> {
-        // Get the target label
-        let label = self.control.label_from_depth(depth)?;
-
-        // Check if this is a branch that would exit the function
-        let _is_exit_branch = self.control.is_exit_branch(depth);
-
-        // Mark affected frames as having potential early returns
-        self.control.mark_frames_with_early_return(depth);
-
-        // Set up jump target in the jump table
-        self.table.label(self.masm.pc(), label);
-
-        // Emit unconditional jump instruction
-        self.masm._jump()?;
-
-        Ok(())
+        self.shrink(depth)?;
+        self.branch(depth, false)?;
+        self.masm.increment_sp(1)?;
+        self.masm._jump()
     }
 
     /// Performs a conditional branch if i32 is non-zero.
     ///
     /// Conditional branch to a given label in an enclosing construct.
     pub fn _br_if(&mut self, depth: u32) -> Result<()> {
-        let label = self.control.label_from_depth(depth)?;
-
-        // Register the jump target in the jump table
-        self.table.label(self.masm.pc(), label);
+        // the condition is consumed by the jump
+        let extra = self.extra(depth, 1)?;
+        if extra == 0 {
+            // Register the jump target in the jump table
+            self.branch(depth, false)?;
+
+            // for a conditional branch, we need to:
+            //
+            // increment the stack pointer (for JUMPI's arguments) and
+            self.masm.increment_sp(1)?;
+
+            // emit the conditional jump instruction
+            return self.masm._jumpi();
+        }
 
-        // for a conditional branch, we need to:
-        //
-        // increment the stack pointer (for JUMPI's arguments) and
+        // skip the branch if the condition is zero, otherwise drop
+        // the values that the target doesn't expect and branch.
+        self.masm._iszero()?;
+        let skip = self.masm.pc();
         self.masm.increment_sp(1)?;
-
-        // emit the conditional jump instruction
         self.masm._jumpi()?;
+        self._br(depth)?;
 
-        Ok(())
+        self.table.label(skip, self.masm.pc());
+        self.masm._jumpdest()?;
+        self.masm.increment_sp(extra)
     }
 
     /// A jump table which jumps to a label in an enclosing construct.
@@ -147,8 +144,32 @@ impl Function {
     /// Performs an indirect branch through an operand indexing into the
     /// label vector that is an immediate to the instruction, or to the
     /// default target if the operand is out of bounds.
-    pub fn _br_table(&mut self, _table: BrTable<'_>) -> Result<()> {
-        todo!()
+    ///
+    /// Consecutive indexes sharing the same target are merged into ranges,
+    /// tables with many distinct targets are lowered to a computed jump
+    /// into a table of fixed-size entries, the others to a binary search
+    /// over the ranges.
+    pub fn _br_table(&mut self, table: BrTable<'_>) -> Result<()> {
+        let targets = table
+            .targets()
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        let default = table.default();
+
+        // [(start, depth)], the last range covers the default target.
+        let mut ranges: Vec<(u32, u32)> = Vec::new();
+        for (index, depth) in targets.iter().chain([&default]).enumerate() {
+            if ranges.last().map(|(_, last)| last) != Some(depth) {
+                ranges.push((index as u32, *depth));
+            }
+        }
+
+        let height = self.masm.sp() - 1;
+        // EOF has no computed jumps.
+        if !self.env.eof && ranges.len() >= 4 && ranges.len() * 2 > targets.len() {
+            self.br_table_computed(&targets, default, height)
+        } else {
+            self.br_table_search(&ranges, height)
+        }
     }
 
     /// Handle the end of instructions for different situations.
@@ -161,23 +182,51 @@ impl Function {
             return self.handle_frame_popping(frame);
         }
 
-        let results = self.ty.results();
+        let results = self.ty.results().len() as u16;
         if self.is_main || self.abi.is_some() {
             tracing::trace!("end of main function");
-            self.masm.main_return(results)
+            self.check_height(results)?;
         } else {
             tracing::trace!("end of call");
-            self.masm.call_return(results)
+            self.check_height(results + 1)?;
         }
+
+        self.return_results()
+    }
+
+    /// Return the results on the top of the stack from the function,
+    /// the results of the main and the external functions are encoded
+    /// with their ABI, multiple results as a tuple.
+    pub(crate) fn return_results(&mut self) -> Result<()> {
+        if !self.is_main && self.abi.is_none() {
+            return self.masm.call_return(self.ty.results());
+        }
+
+        // the results are stored from the last one on the top.
+        let results = self.ty.results();
+        let offset = self.masm.mp;
+        for index in (0..results.len()).rev() {
+            self.signextend_abi_result(index)?;
+            self.masm
+                .memory_write_at(&(offset + index * 0x20).to_ls_bytes())?;
+        }
+
+        self.masm.main_return(self.ty.results())
     }
 
-    /// Mark as invalid for now.
+    /// Revert with `Panic(0x01)`, the code of failed assertions in solc,
+    /// the code following it is unreachable.
     ///
-    /// TODO: recheck this implementation, if it is okay,
-    /// provide more docs.
+    /// The revert is shared by all `unreachable` in the code section.
     pub fn _unreachable(&mut self) -> Result<()> {
-        self.masm._invalid()?;
-        Ok(())
+        let stub = ExtFunc::stub(self.env.target, |masm| {
+            masm.push(&[0x01])?;
+            masm.revert_panic()
+        })?;
+
+        self.table.ext(self.masm.pc(), stub);
+        self.masm.increment_sp(1)?;
+        self.masm._jump()
     }
 
     /// Perform nothing in EVM bytecode.
@@ -185,30 +234,293 @@ impl Function {
         Ok(())
     }
 
+    /// Observe a control operator in unreachable code.
+    ///
+    /// Frames opened in unreachable code are only counted, an else or
+    /// an end of the frame that turned unreachable restores the
+    /// reachability and resynchronizes the stack pointer from the frame.
+    pub fn observe_unreachable(&mut self, op: &Operator) -> Result<()> {
+        match op {
+            Operator::If { .. } | Operator::Block { .. } | Operator::Loop { .. } => {
+                self.unreachable_depth += 1;
+            }
+            Operator::Else if self.unreachable_depth > 0 => {}
+            Operator::End if self.unreachable_depth > 0 => {
+                self.unreachable_depth -= 1;
+            }
+            Operator::Else => {
+                // the then branch never reaches the end, so the else
+                // branch continues as a block from the false path.
+                let last_frame = self.control.mark_else()?;
+                self.control.pop()?;
+                self.table
+                    .label(last_frame.original_pc_offset, self.masm.pc());
+
+                let mut frame = ControlStackFrame::new(
+                    ControlStackFrameType::Block,
+                    self.masm.pc(),
+                    last_frame.original_sp,
+                    last_frame.result(),
+                    last_frame.io(),
+                );
+                self.masm.asm.sp = last_frame.original_sp + last_frame.params();
+                frame.branches = last_frame.branches;
+                self.control.push(frame);
+                self.masm._jumpdest()?;
+                self.reachable = true;
+            }
+            Operator::End => {
+                let Some(frame) = self.control.stack.last() else {
+                    // the end of the function is never reached.
+                    self.masm.asm.sp = self.ty.results().len() as u16;
+                    return Ok(());
+                };
+
+                let reachable = match frame.ty {
+                    ControlStackFrameType::Loop => false,
+                    ControlStackFrameType::Block | ControlStackFrameType::If(true) => {
+                        !frame.branches.is_empty()
+                    }
+                    ControlStackFrameType::If(false) | ControlStackFrameType::Else => true,
+                };
+                self._end()?;
+                self.reachable = reachable;
+            }
+            _ => {}
+        }
+
+        Ok(())
+    }
+
     /// Handle the popping of a frame.
     ///
-    /// TODO: validate stack IO for all frames (#59)
+    /// Resolves the forward jumps to the end of the frame, and leaves
+    /// the results of the frame on top of its entry stack.
     pub(crate) fn handle_frame_popping(&mut self, frame: ControlStackFrame) -> Result<()> {
+        if self.reachable {
+            self.check_height(frame.end())?;
+        }
+
+        self.masm.asm.sp = frame.end();
+
+        let gas = self.masm.asm.gas();
+        let mut branches = frame.branches;
         match frame.ty {
-            ControlStackFrameType::If(true) => Ok(()),
-            ControlStackFrameType::Block => {
-                // For blocks that might have early returns, ensure proper jump destination
-                if frame.might_return_early {
-                    // Make sure the jump table has this position as a target for the block
-                    self.table.label(frame.original_pc_offset, self.masm.pc());
-                }
-                self.masm._jumpdest()
+            // branches to loops jump back to their beginnings.
+            ControlStackFrameType::Loop => {
+                self.loops.push(GasBlock {
+                    range: frame.original_pc_offset..self.masm.pc(),
+                    gas: (gas - frame.gas) as u64,
+                });
+                return Ok(());
+            }
+            // the if frame shares the end with its else frame, only
+            // the more expensive branch is counted.
+            ControlStackFrameType::Else => {
+                self.table.label(frame.original_pc_offset, self.masm.pc());
+                let mut last_frame = self.control.pop()?;
+                branches.append(&mut last_frame.branches);
+
+                let then = GasBlock {
+                    range: last_frame.original_pc_offset..frame.original_pc_offset,
+                    gas: (frame.gas - last_frame.gas) as u64,
+                };
+                let otherwise = GasBlock {
+                    range: frame.original_pc_offset..self.masm.pc(),
+                    gas: (gas - frame.gas) as u64,
+                };
+                self.masm
+                    .asm
+                    .decrement_gas(then.gas.min(otherwise.gas).into());
+                self.branches.push([then, otherwise]);
             }
-            ControlStackFrameType::Loop => Ok(()),
-            _ => {
+            ControlStackFrameType::If(false) => {
                 self.table.label(frame.original_pc_offset, self.masm.pc());
+            }
+            ControlStackFrameType::Block | ControlStackFrameType::If(true) => {
+                if branches.is_empty() {
+                    return Ok(());
+                }
+            }
+        }
+
+        let end = self.masm.pc();
+        for (pc, entry) in branches {
+            if entry {
+                self.table.entry(pc, end);
+            } else {
+                self.table.label(pc, end);
+            }
+        }
+
+        // Emit JUMPDEST at the end of the control flow.
+        self.masm._jumpdest()
+    }
+
+    /// Create a frame of the block type at the current program counter,
+    /// the params of the block are under the top `consumed` operands.
+    fn control_frame(
+        &self,
+        ty: ControlStackFrameType,
+        blockty: BlockType,
+        consumed: u16,
+    ) -> Result<ControlStackFrame> {
+        let io = self.env.block_type(blockty)?;
+        let sp = self.masm.sp() - consumed;
+        let original_sp = sp
+            .checked_sub(io.0)
+            .ok_or(Error::StackUnderflow(sp, io.0))?;
+
+        let mut frame = ControlStackFrame::new(ty, self.masm.pc(), original_sp, blockty, io);
+        frame.gas = self.masm.asm.gas();
+        Ok(frame)
+    }
+
+    /// Check if the stack pointer matches the modeled stack height.
+    fn check_height(&self, height: u16) -> Result<()> {
+        let sp = self.masm.sp();
+        if sp != height {
+            return Err(Error::StackMismatch(sp, height));
+        }
+
+        Ok(())
+    }
+
+    /// Register the jump at the current program counter to the frame
+    /// at the given depth.
+    ///
+    /// `entry` marks the jumps from the entries of computed jump tables.
+    fn branch(&mut self, depth: u32, entry: bool) -> Result<()> {
+        let pc = self.masm.pc();
+        let frame = self.control.frame_from_depth_mut(depth)?;
+        if frame.ty != ControlStackFrameType::Loop {
+            // the end of the frame is not emitted yet.
+            frame.branches.push((pc, entry));
+        } else if entry {
+            self.table.entry(pc, frame.original_pc_offset);
+        } else {
+            self.table.label(pc, frame.original_pc_offset);
+        }
+
+        Ok(())
+    }
+
+    /// The count of the values to drop before branching to the frame
+    /// at the given depth, excluding the top `consumed` items that will
+    /// be consumed by the jump.
+    fn extra(&self, depth: u32, consumed: u16) -> Result<u16> {
+        let height = self.control.frame_from_depth(depth)?.height();
+        let sp = self.masm.sp() - consumed;
+        sp.checked_sub(height)
+            .ok_or(Error::StackUnderflow(sp, height))
+    }
+
+    /// Drop the values that the frame at the given depth doesn't expect,
+    /// the values carried by the branch are kept on the top.
+    fn shrink(&mut self, depth: u32) -> Result<()> {
+        let extra = self.extra(depth, 0)?;
+        let arity = self.control.frame_from_depth(depth)?.arity();
+        self.masm._drop_under(extra, arity)
+    }
+
+    /// Drop the index on the stack then branch to the frame at the
+    /// given depth.
+    fn br_table_leaf(&mut self, depth: u32, height: u16) -> Result<()> {
+        self.masm.asm.sp = height + 1;
+        self.masm._drop()?;
+        self._br(depth)
+    }
+
+    /// Binary search the range of the index on the stack.
+    ///
+    /// STACK: [index] -> []
+    fn br_table_search(&mut self, ranges: &[(u32, u32)], height: u16) -> Result<()> {
+        if let [(_, depth)] = ranges {
+            return self.br_table_leaf(*depth, height);
+        }
+
+        let (lower, upper) = ranges.split_at(ranges.len() / 2);
+        let (pivot, _) = upper[0];
+
+        // jump to the lower half if index < pivot
+        self.masm.asm.sp = height + 1;
+        self.masm._dup1()?;
+        self.masm.push(&pivot.to_ls_bytes())?;
+        self.masm.asm._gt()?;
+        let jump = self.masm.pc();
+        self.masm.increment_sp(1)?;
+        self.masm._jumpi()?;
+
+        self.br_table_search(upper, height)?;
+        self.table.label(jump, self.masm.pc());
+        self.masm._jumpdest()?;
+        self.br_table_search(lower, height)
+    }
+
+    /// Jump into the entry of the index on the stack.
+    ///
+    /// Each entry jumps to its target directly, or to a landing pad
+    /// dropping the values that the target doesn't expect first.
+    ///
+    /// STACK: [index] -> []
+    fn br_table_computed(&mut self, targets: &[u32], default: u32, height: u16) -> Result<()> {
+        // jump to the default target if index >= len
+        self.masm._dup1()?;
+        self.masm.push(&targets.len().to_ls_bytes())?;
+        self.masm.asm._gt()?;
+        self.masm._iszero()?;
+        let jump_default = self.masm.pc();
+        self.masm.increment_sp(1)?;
+        self.masm._jumpi()?;
 
-                // TODO: Check the stack output and make decisions
-                // how to handle the results.
+        // jump to base + index * ENTRY_SIZE
+        self.masm.push(&[ENTRY_SIZE])?;
+        self.masm._mul()?;
+        let jump_base = self.masm.pc();
+        self.masm.increment_sp(1)?;
+        self.masm._add()?;
+        self.masm._jump()?;
 
-                // Emit JUMPDEST at the end of the control flow.
-                self.masm._jumpdest()
+        let base = self.masm.pc();
+        self.table.label(jump_base, base);
+
+        let mut pads: Vec<(u16, u32)> = Vec::new();
+        for depth in targets {
+            self.masm.asm.sp = height;
+            self.masm._jumpdest()?;
+            if self.extra(*depth, 0)? == 0 {
+                self.branch(*depth, true)?;
+            } else {
+                pads.push((self.masm.pc(), *depth));
             }
+
+            self.masm.increment_sp(1)?;
+            self.masm._jump()?;
         }
+
+        // the default target
+        self.table.label(jump_default, self.masm.pc());
+        self.masm._jumpdest()?;
+        self.br_table_leaf(default, height)?;
+
+        // the landing pads
+        let mut landed: Vec<(u32, u16)> = Vec::new();
+        for (pc, depth) in pads {
+            let pad = match landed.iter().find(|(landed, _)| *landed == depth) {
+                Some((_, pad)) => *pad,
+                None => {
+                    let pad = self.masm.pc();
+                    self.masm.asm.sp = height;
+                    self.masm._jumpdest()?;
+                    self._br(depth)?;
+                    landed.push((depth, pad));
+                    pad
+                }
+            };
+
+            self.table.entry(pc, pad);
+        }
+
+        Ok(())
     }
 }
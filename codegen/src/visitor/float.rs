@@ -0,0 +1,288 @@
+//! Float instructions
+//!
+//! The float operators which need software emulation are compiled to
+//! shared functions in the code section, one per float format, they
+//! are rejected unless `Env::soft_float` is enabled.
+
+use crate::{
+    codegen::ExtFunc,
+    masm::{Float, Round},
+    Error, Function, Result,
+};
+use wasmparser::Operator;
+
+impl Function {
+    /// Reject the operator if it needs soft float but it is disabled.
+    pub(crate) fn check_soft_float(&self, op: &Operator) -> Result<()> {
+        if self.env.soft_float || !is_soft_float(op) {
+            return Ok(());
+        }
+
+        Err(Error::SoftFloatDisabled(format!("{op:?}")))
+    }
+
+    /// Addition
+    pub fn _fadd(&mut self, f: Float) -> Result<()> {
+        self.call_ext(ExtFunc::new(self.env.target, 2, |masm| masm._fadd(f))?)
+    }
+
+    /// Subtraction
+    pub fn _fsub(&mut self, f: Float) -> Result<()> {
+        self.call_ext(ExtFunc::new(self.env.target, 2, |masm| masm._fsub(f))?)
+    }
+
+    /// Multiplication
+    pub fn _fmul(&mut self, f: Float) -> Result<()> {
+        self.call_ext(ExtFunc::new(self.env.target, 2, |masm| masm._fmul(f))?)
+    }
+
+    /// Division
+    pub fn _fdiv(&mut self, f: Float) -> Result<()> {
+        self.call_ext(ExtFunc::new(self.env.target, 2, |masm| masm._fdiv(f))?)
+    }
+
+    /// Square root
+    pub fn _fsqrt(&mut self, f: Float) -> Result<()> {
+        self.call_ext(ExtFunc::new(self.env.target, 1, |masm| masm._fsqrt(f))?)
+    }
+
+    /// Minimum of two values
+    pub fn _fmin(&mut self, f: Float) -> Result<()> {
+        self.call_ext(ExtFunc::new(self.env.target, 2, |masm| {
+            masm._fminmax(f, false)
+        })?)
+    }
+
+    /// Maximum of two values
+    pub fn _fmax(&mut self, f: Float) -> Result<()> {
+        self.call_ext(ExtFunc::new(self.env.target, 2, |masm| {
+            masm._fminmax(f, true)
+        })?)
+    }
+
+    /// Ceiling operator
+    pub fn _fceil(&mut self, f: Float) -> Result<()> {
+        self.call_ext(ExtFunc::new(self.env.target, 1, |masm| {
+            masm._fround(f, Round::Ceil)
+        })?)
+    }
+
+    /// Floor operator
+    pub fn _ffloor(&mut self, f: Float) -> Result<()> {
+        self.call_ext(ExtFunc::new(self.env.target, 1, |masm| {
+            masm._fround(f, Round::Floor)
+        })?)
+    }
+
+    /// Round to nearest integer towards zero
+    pub fn _ftrunc(&mut self, f: Float) -> Result<()> {
+        self.call_ext(ExtFunc::new(self.env.target, 1, |masm| {
+            masm._fround(f, Round::Trunc)
+        })?)
+    }
+
+    /// Round to nearest integer, ties to even.
+    pub fn _fnearest(&mut self, f: Float) -> Result<()> {
+        self.call_ext(ExtFunc::new(self.env.target, 1, |masm| {
+            masm._fround(f, Round::Nearest)
+        })?)
+    }
+
+    /// Absolute value
+    pub fn _fabs(&mut self, f: Float) -> Result<()> {
+        self.masm._fabs(f)
+    }
+
+    /// Negation
+    pub fn _fneg(&mut self, f: Float) -> Result<()> {
+        self.masm._fneg(f)
+    }
+
+    /// Copy the sign of the second operand to the first one.
+    pub fn _fcopysign(&mut self, f: Float) -> Result<()> {
+        self.masm._fcopysign(f)
+    }
+
+    /// Equal comparison.
+    pub fn _feq(&mut self, f: Float) -> Result<()> {
+        self.call_ext(ExtFunc::new(self.env.target, 2, |masm| {
+            masm._fcmp(f, |s| s.eq(), false)
+        })?)
+    }
+
+    /// Not equal comparison, true if any operand is NaN.
+    pub fn _fne(&mut self, f: Float) -> Result<()> {
+        self.call_ext(ExtFunc::new(self.env.target, 2, |masm| {
+            masm._fcmp(
+                f,
+                |s| {
+                    s.eq()?;
+                    s.iszero()
+                },
+                true,
+            )
+        })?)
+    }
+
+    /// Less than comparison.
+    pub fn _flt(&mut self, f: Float) -> Result<()> {
+        self.call_ext(ExtFunc::new(self.env.target, 2, |masm| {
+            masm._fcmp(f, |s| s.lt(), false)
+        })?)
+    }
+
+    /// Greater than comparison.
+    pub fn _fgt(&mut self, f: Float) -> Result<()> {
+        self.call_ext(ExtFunc::new(self.env.target, 2, |masm| {
+            masm._fcmp(f, |s| s.gt(), false)
+        })?)
+    }
+
+    /// Less than or equal comparison.
+    pub fn _fle(&mut self, f: Float) -> Result<()> {
+        self.call_ext(ExtFunc::new(self.env.target, 2, |masm| {
+            masm._fcmp(
+                f,
+                |s| {
+                    s.gt()?;
+                    s.iszero()
+                },
+                false,
+            )
+        })?)
+    }
+
+    /// Greater than or equal comparison.
+    pub fn _fge(&mut self, f: Float) -> Result<()> {
+        self.call_ext(ExtFunc::new(self.env.target, 2, |masm| {
+            masm._fcmp(
+                f,
+                |s| {
+                    s.lt()?;
+                    s.iszero()
+                },
+                false,
+            )
+        })?)
+    }
+
+    /// Convert a signed 32-bit integer to a float.
+    pub fn _fconvert_i32_s(&mut self, f: Float) -> Result<()> {
+        self.call_ext(ExtFunc::new(self.env.target, 1, |masm| {
+            masm._fconvert(f, 4, true)
+        })?)
+    }
+
+    /// Convert an unsigned 32-bit integer to a float.
+    pub fn _fconvert_i32_u(&mut self, f: Float) -> Result<()> {
+        self.call_ext(ExtFunc::new(self.env.target, 1, |masm| {
+            masm._fconvert(f, 4, false)
+        })?)
+    }
+
+    /// Convert a signed 64-bit integer to a float.
+    pub fn _fconvert_i64_s(&mut self, f: Float) -> Result<()> {
+        self.call_ext(ExtFunc::new(self.env.target, 1, |masm| {
+            masm._fconvert(f, 8, true)
+        })?)
+    }
+
+    /// Convert an unsigned 64-bit integer to a float.
+    pub fn _fconvert_i64_u(&mut self, f: Float) -> Result<()> {
+        self.call_ext(ExtFunc::new(self.env.target, 1, |masm| {
+            masm._fconvert(f, 8, false)
+        })?)
+    }
+
+    /// Truncate a 32-bit float to an integer of `width` bytes.
+    pub fn _trunc_f32(&mut self, width: usize, signed: bool) -> Result<()> {
+        self.call_ext(ExtFunc::new(self.env.target, 1, |masm| {
+            masm._ftrunc_int(Float::F32, width, signed)
+        })?)
+    }
+
+    /// Truncate a 64-bit float to an integer of `width` bytes.
+    pub fn _trunc_f64(&mut self, width: usize, signed: bool) -> Result<()> {
+        self.call_ext(ExtFunc::new(self.env.target, 1, |masm| {
+            masm._ftrunc_int(Float::F64, width, signed)
+        })?)
+    }
+
+    /// Round a 64-bit float to a 32-bit float.
+    pub fn _f32_demote_f64(&mut self) -> Result<()> {
+        self.call_ext(ExtFunc::new(self.env.target, 1, |masm| {
+            masm._fconvert_float(Float::F64, Float::F32)
+        })?)
+    }
+
+    /// Convert a 32-bit float to a 64-bit float.
+    pub fn _f64_promote_f32(&mut self) -> Result<()> {
+        self.call_ext(ExtFunc::new(self.env.target, 1, |masm| {
+            masm._fconvert_float(Float::F32, Float::F64)
+        })?)
+    }
+}
+
+/// If the operator needs soft float.
+///
+/// Operators only moving the bits of floats, and the ones only
+/// touching the sign bit are always supported.
+fn is_soft_float(op: &Operator) -> bool {
+    use Operator::*;
+
+    matches!(
+        op,
+        F32Add
+            | F32Sub
+            | F32Mul
+            | F32Div
+            | F32Min
+            | F32Max
+            | F32Sqrt
+            | F32Ceil
+            | F32Floor
+            | F32Trunc
+            | F32Nearest
+            | F32Eq
+            | F32Ne
+            | F32Lt
+            | F32Gt
+            | F32Le
+            | F32Ge
+            | F64Add
+            | F64Sub
+            | F64Mul
+            | F64Div
+            | F64Min
+            | F64Max
+            | F64Sqrt
+            | F64Ceil
+            | F64Floor
+            | F64Trunc
+            | F64Nearest
+            | F64Eq
+            | F64Ne
+            | F64Lt
+            | F64Gt
+            | F64Le
+            | F64Ge
+            | F32ConvertI32S
+            | F32ConvertI32U
+            | F32ConvertI64S
+            | F32ConvertI64U
+            | F64ConvertI32S
+            | F64ConvertI32U
+            | F64ConvertI64S
+            | F64ConvertI64U
+            | F32DemoteF64
+            | F64PromoteF32
+            | I32TruncF32S
+            | I32TruncF32U
+            | I32TruncF64S
+            | I32TruncF64U
+            | I64TruncF32S
+            | I64TruncF32U
+            | I64TruncF64S
+            | I64TruncF64U
+    )
+}
@@ -1,6 +1,9 @@
 //! Local instructions
 
-use crate::{wasm::ToLSBytes, Error, Function, Result};
+use crate::{
+    wasm::{Global, ToLSBytes},
+    Error, Function, Result,
+};
 
 impl Function {
     /// This instruction gets the value of a variable.
@@ -31,14 +34,50 @@ impl Function {
         Ok(())
     }
 
-    /// This instruction gets the value of a variable.
-    pub fn _global_get(&mut self, _: u32) -> Result<()> {
-        todo!()
+    /// This instruction gets the value of a global.
+    ///
+    /// Immutable globals are folded to their initial values.
+    pub fn _global_get(&mut self, global_index: u32) -> Result<()> {
+        let global = self.global(global_index)?;
+        if !global.mutable {
+            return self.masm.push(&global.init);
+        }
+
+        self.masm.push(&global.slot.to_ls_bytes())?;
+        self.masm._mload()?;
+        self.xor_init(&global.init)
     }
 
-    /// This instruction sets the value of a variable.
-    pub fn _global_set(&mut self, _: u32) -> Result<()> {
-        todo!()
+    /// This instruction sets the value of a global.
+    pub fn _global_set(&mut self, global_index: u32) -> Result<()> {
+        let global = self.global(global_index)?;
+        if !global.mutable {
+            return Err(Error::ImmutableGlobal(global_index));
+        }
+
+        self.xor_init(&global.init)?;
+        self.masm.push(&global.slot.to_ls_bytes())?;
+        self.masm._mstore()
+    }
+
+    /// Get global from the environment.
+    fn global(&self, global_index: u32) -> Result<Global> {
+        self.env
+            .globals
+            .get(&global_index)
+            .cloned()
+            .ok_or(Error::InvalidGlobalIndex(global_index))
+    }
+
+    /// Toggle the value on the stack between the value of a global
+    /// and the one stored in its slot, see [`Global`].
+    fn xor_init(&mut self, init: &[u8]) -> Result<()> {
+        if init.iter().all(|byte| *byte == 0) {
+            return Ok(());
+        }
+
+        self.masm.push(init)?;
+        self.masm._xor()
     }
 
     /// Local get from calldata.
@@ -51,6 +90,10 @@ impl Function {
         self.masm.push(&offset)?;
         self.masm._calldataload()?;
 
+        if let Some(width) = self.calldata_width(local_index) {
+            self.masm._wrap(width)?;
+        }
+
         Ok(())
     }
 
@@ -1,12 +1,15 @@
 //! System instructions
 
-use crate::{masm::MemoryInfo, wasm::ToLSBytes, Error, Function, Result};
+use crate::{
+    wasm::{ToLSBytes, Type},
+    Error, Function, Result,
+};
 
 impl Function {
     /// Parse log data from the bytecode.
     ///
     /// WASM example:
-    /// ```
+    /// ```wasm
     /// i32.const 1048576   ;; offset
     /// i32.const 4         ;; 4 bytes
     /// ```
@@ -59,18 +62,27 @@ impl Function {
     }
 
     /// Log a message with topics.
+    ///
+    /// The message is written to the free memory on the heap, which is
+    /// not allocated since it is only used by the log.
     pub fn log(&mut self, count: usize) -> Result<()> {
         let (offset, size) = self.data()?;
         let data = self.env.data.load(offset, size as usize)?;
 
-        // 1. write data to memory
-        let MemoryInfo { offset, size } = self.masm.memory_write_bytes(&data)?;
+        // 1. write data to memory, the words of data should fit in the heap.
+        self.masm._free_memory_pointer(&self.env.memory)?;
+        self.masm._dup1()?;
+        self.masm.push(&data.len().align().to_ls_bytes())?;
+        self.masm._add()?;
+        self.masm._heap_check(&self.env.memory)?;
+        self.masm._drop()?;
+        self.masm.memory_write_bytes(&data)?;
 
-        // 3. prepare the offset and size of the data.
-        self.masm.push(&size.to_ls_bytes())?;
-        self.masm.push(&offset)?;
+        // 2. prepare the offset and size of the data.
+        self.masm.push(&data.len().to_ls_bytes())?;
+        self.masm._swap1()?;
 
-        // 4. run log for the data
+        // 3. run log for the data
         match count {
             0 => self.masm._log0(),
             1 => self.masm._log1(),
@@ -83,34 +95,12 @@ impl Function {
         Ok(())
     }
 
-    /// Revert with message.
-    pub fn revert(&mut self, count: usize) -> Result<()> {
-        let mut message = Vec::<Vec<u8>>::default();
-        for slot in 0..count {
-            let (offset, size) = self.data()?;
-            let size = size as usize;
-            let data = self.env.data.load(offset, size)?;
-
-            self.masm.push(&data)?;
-            if slot == 0 {
-                self.masm._push0()?;
-            } else {
-                self.masm.push(&slot.to_ls_bytes())?;
-            }
-            self.masm._mstore()?;
-            message.push(data);
-        }
-
-        tracing::debug!(
-            "revert message: {}",
-            String::from_utf8_lossy(&message.into_iter().flatten().collect::<Vec<u8>>())
-        );
-
-        self.masm.push(&(count * 32).to_ls_bytes())?;
-        self.masm._push0()?;
+    /// Revert with message, encoded as `Error(string)`.
+    pub fn revert(&mut self) -> Result<()> {
+        let (offset, size) = self.data()?;
+        let message = self.env.data.load(offset, size as usize)?;
+        tracing::debug!("revert message: {}", String::from_utf8_lossy(&message));
 
-        // 3. run log for the data
-        self.masm._revert()?;
-        Ok(())
+        self.masm.revert_error(&message)
     }
 }
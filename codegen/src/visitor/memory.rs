@@ -0,0 +1,290 @@
+//! Memory instructions
+
+use crate::{
+    codegen::ExtFunc,
+    wasm::{ToLSBytes, Type},
+    Error, Function, MacroAssembler, Result,
+};
+use opcodes::Upgrade;
+use wasmparser::{MemArg, ValType};
+
+impl Function {
+    /// Load a value of the given type from the linear memory.
+    pub fn _load(&mut self, memarg: MemArg, ty: ValType) -> Result<()> {
+        self.load(memarg, ty, ty.size(), false)
+    }
+
+    /// Load 1 byte and extend it to the given type.
+    pub fn _load8(&mut self, memarg: MemArg, ty: ValType, signed: bool) -> Result<()> {
+        self.load(memarg, ty, 1, signed)
+    }
+
+    /// Load 2 bytes and extend them to the given type.
+    pub fn _load16(&mut self, memarg: MemArg, ty: ValType, signed: bool) -> Result<()> {
+        self.load(memarg, ty, 2, signed)
+    }
+
+    /// Load 4 bytes and extend them to the given type.
+    pub fn _load32(&mut self, memarg: MemArg, ty: ValType, signed: bool) -> Result<()> {
+        self.load(memarg, ty, 4, signed)
+    }
+
+    /// Store a value of the given type in the linear memory.
+    pub fn _store(&mut self, memarg: MemArg, ty: ValType) -> Result<()> {
+        self.store(memarg, ty.size())
+    }
+
+    /// Wrap the value to 1 byte and store it.
+    pub fn _store8(&mut self, memarg: MemArg, _ty: ValType) -> Result<()> {
+        self.masm._swap1()?;
+        self.ptr(memarg, 1, false)?;
+        self.masm._mstore8()
+    }
+
+    /// Wrap the value to 2 bytes and store it.
+    pub fn _store16(&mut self, memarg: MemArg, _ty: ValType) -> Result<()> {
+        self.store(memarg, 2)
+    }
+
+    /// Wrap the value to 4 bytes and store it.
+    pub fn _store32(&mut self, memarg: MemArg, _ty: ValType) -> Result<()> {
+        self.store(memarg, 4)
+    }
+
+    /// Get the current size of the linear memory in pages.
+    pub fn _memory_size(&mut self, _mem: u32, _mem_byte: u8) -> Result<()> {
+        self.masm._memory_size(&self.env.memory)
+    }
+
+    /// Grow the linear memory by the pages on the stack.
+    pub fn _memory_grow(&mut self, _mem: u32, _mem_byte: u8) -> Result<()> {
+        self.masm._memory_grow(&self.env.memory)
+    }
+
+    /// Copy a data segment into the linear memory.
+    ///
+    /// The segment is appended to the code section in reverse order,
+    /// the same as the linear memory, and copied with `CODECOPY`. Traps
+    /// if the range is out of the segment or the linear memory.
+    ///
+    /// STACK: [dst, src, size] -> []
+    pub fn _memory_init(&mut self, data_index: u32, _mem: u32) -> Result<()> {
+        let mut data = self.env.data.segment(data_index)?.to_vec();
+        data.reverse();
+
+        // [dst, size, src + size], trap if src + size > len
+        let len = data.len().to_ls_bytes();
+        self.masm._swap1()?;
+        self.masm._dup2()?;
+        self.masm._add()?;
+        self.masm._dup1()?;
+        self.masm.push(&len)?;
+        self.masm.asm._lt()?;
+        self.masm._trap()?;
+
+        // [dst, size, len - src - size]
+        self.masm.push(&len)?;
+        self.masm.asm._sub()?;
+
+        // [dst, size, code + len - src - size]
+        self.table.data(self.masm.pc(), data_index, data);
+        self.masm.asm.increment_relocation_gas();
+        self.masm.increment_sp(1)?;
+        self.masm._add()?;
+
+        // [size, code + len - src - size, dst_ptr]
+        self.masm._swap2()?;
+        self.masm._dup2()?;
+        self.range()?;
+        self.masm._swap2()?;
+        self.masm._swap1()?;
+        self.masm._swap2()?;
+        self.masm._codecopy()
+    }
+
+    /// Drop a data segment.
+    ///
+    /// The segments stay in the code section, so this is a no-op.
+    pub fn _data_drop(&mut self, _data_index: u32) -> Result<()> {
+        Ok(())
+    }
+
+    /// Copy bytes in the linear memory, the regions could overlap.
+    ///
+    /// Lowers to `MCOPY` since Cancun, otherwise the bytes are copied
+    /// one by one.
+    ///
+    /// STACK: [dst, src, size] -> []
+    pub fn _memory_copy(&mut self, _dst_mem: u32, _src_mem: u32) -> Result<()> {
+        // [dst, size, src_ptr]
+        self.masm._swap1()?;
+        self.masm._dup2()?;
+        self.range()?;
+
+        // [size, src_ptr, dst_ptr]
+        self.masm._swap2()?;
+        self.masm._dup2()?;
+        self.range()?;
+        self.masm._swap1()?;
+        self.masm._swap2()?;
+        self.masm._swap1()?;
+        if self.env.target >= Upgrade::Cancun {
+            return self.masm._mcopy();
+        }
+
+        self.masm._copy_bytes()?;
+        self.repeat(4, MacroAssembler::_copy_byte)
+    }
+
+    /// Fill bytes in the linear memory with the lowest byte of a value.
+    ///
+    /// STACK: [dst, value, size] -> []
+    pub fn _memory_fill(&mut self, _mem: u32) -> Result<()> {
+        // [value, size, dst_ptr]
+        self.masm._swap1()?;
+        self.masm._swap2()?;
+        self.masm._dup2()?;
+        self.range()?;
+
+        self.masm._fill_bytes()?;
+        self.repeat(3, MacroAssembler::_fill_byte)
+    }
+
+    /// Run `body` with the counter on the top of the stack, from the
+    /// initial counter down to one, then drop the `items` of the loop.
+    fn repeat(&mut self, items: u16, body: fn(&mut MacroAssembler) -> Result<()>) -> Result<()> {
+        // [.., counter], exit if the counter is zero
+        let start = self.masm.pc();
+        self.masm._jumpdest()?;
+        self.masm._dup1()?;
+        self.masm._iszero()?;
+        let exit = self.masm.pc();
+        self.masm.increment_sp(1)?;
+        self.masm._jumpi()?;
+
+        // [.., counter - 1]
+        body(&mut self.masm)?;
+        self.masm.push(&[1])?;
+        self.masm._swap1()?;
+        self.masm.asm._sub()?;
+        self.table.label(self.masm.pc(), start);
+        self.masm.increment_sp(1)?;
+        self.masm._jump()?;
+
+        self.table.label(exit, self.masm.pc());
+        self.masm._jumpdest()?;
+        for _ in 0..items {
+            self.masm._drop()?;
+        }
+
+        Ok(())
+    }
+
+    /// Load `size` bytes from the address on the stack plus the
+    /// static offset of the instruction.
+    fn load(&mut self, memarg: MemArg, ty: ValType, size: usize, signed: bool) -> Result<()> {
+        self.ptr(memarg, size, true)?;
+        self.masm._load(size)?;
+
+        if signed {
+            self.masm._extend_s(size, ty.size())?;
+        }
+
+        Ok(())
+    }
+
+    /// Store `size` bytes at the address on the stack plus the
+    /// static offset of the instruction.
+    fn store(&mut self, memarg: MemArg, size: usize) -> Result<()> {
+        self.masm._swap1()?;
+        self.ptr(memarg, size, true)?;
+        self.masm._swap1()?;
+        self.masm._store(size)
+    }
+
+    /// Translate the address on the stack plus the static offset of
+    /// the instruction to the EVM offset of the word ending with the
+    /// `size` bytes at the address, or of the byte at the address.
+    ///
+    /// Traps if the bytes are out of the linear memory. The offset is
+    /// computed from the static offset if the memory could not grow,
+    /// otherwise the address is checked against the grown pages at
+    /// runtime.
+    ///
+    /// STACK: [address] -> [ptr]
+    fn ptr(&mut self, memarg: MemArg, size: usize, word: bool) -> Result<()> {
+        self.touch_memory();
+        let memory = self.env.memory;
+        if memory.growable() == 0 {
+            let ptr = if word {
+                memory.word(memarg.offset)?
+            } else {
+                memory.byte(memarg.offset)?
+            };
+
+            // trap if address + offset + size > initial
+            let limit = usize::try_from(memarg.offset)
+                .ok()
+                .and_then(|offset| memory.initial().checked_sub(offset + size))
+                .ok_or(Error::MemoryOutOfBounds)?;
+            self.masm._dup1()?;
+            self.masm.push(&limit.to_ls_bytes())?;
+            self.masm.asm._lt()?;
+            self.masm._trap()?;
+
+            self.masm.push(&ptr.to_ls_bytes())?;
+            return self.masm.asm._sub();
+        }
+
+        if memarg.offset != 0 {
+            self.masm.push(&memarg.offset.to_ls_bytes())?;
+            self.masm._add()?;
+        }
+
+        self.call_ext(ExtFunc::new(self.env.target, 1, |masm| {
+            masm.push(&size.to_ls_bytes())?;
+            masm._memory_range(&memory)?;
+            if word && size < 32 {
+                masm.push(&(32 - size).to_ls_bytes())?;
+                masm._swap1()?;
+                masm.asm._sub()?;
+            }
+
+            Ok(())
+        })?)
+    }
+
+    /// Translate a range of the linear memory to the EVM offset of
+    /// its lowest byte.
+    ///
+    /// STACK: [address, size] -> [ptr]
+    fn range(&mut self) -> Result<()> {
+        self.touch_memory();
+        let memory = self.env.memory;
+        if memory.growable() == 0 {
+            return self.masm._memory_range(&memory);
+        }
+
+        self.call_ext(ExtFunc::new(self.env.target, 2, |masm| {
+            masm._memory_range(&memory)
+        })?)
+    }
+
+    /// Count the expansion of the linear memory in the gas estimation,
+    /// the addresses are unknown, so the whole memory is counted.
+    fn touch_memory(&mut self) {
+        let memory = self.env.memory;
+        let end = if memory.growable() == 0 {
+            memory.bound()
+        } else {
+            memory.grown_bound()
+        };
+
+        self.memory = self.memory.max(end);
+    }
+
+    /// Count the expansion of the heap in the gas estimation.
+    pub(crate) fn touch_heap(&mut self) {
+        self.memory = self.memory.max(self.env.memory.heap_end());
+    }
+}
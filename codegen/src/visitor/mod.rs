@@ -4,30 +4,56 @@
 //! `Function`; which defines a visitor per op-code, which validates
 //! and dispatches to the corresponding machine code emitter.
 
-use crate::{Function, Result};
+use crate::{masm::Float, Function, Result};
 use paste::paste;
 use tracing::trace;
-use wasmparser::{for_each_operator, BlockType, BrTable, Ieee32, Ieee64, MemArg, VisitOperator};
+use wasmparser::{
+    for_each_operator, BlockType, BrTable, Ieee32, Ieee64, MemArg, ValType, VisitOperator,
+};
 
+mod bits;
 mod call;
 mod control;
+mod float;
 mod local;
 mod log;
+mod memory;
+mod width;
+
+/// The size of an entry of the computed jump tables of `br_table` and
+/// `call_indirect`, `JUMPDEST PUSH2 <target> JUMP`.
+const ENTRY_SIZE: u8 = 5;
 
 /// A macro to define unsupported WebAssembly operators.
 ///
 /// This macro calls itself recursively;
 /// 1. It no-ops when matching a supported operator.
-/// 2. Defines the visitor function and panics when
-///    matching an unsupported operator.
+/// 2. Defines the visitor function and returns
+///    `Error::UnsupportedOperator` when matching an unsupported operator.
 macro_rules! impl_visit_operator {
     ( @mvp $op:ident $({ $($arg:ident: $argty:ty),* })? => $visit:ident $($rest:tt)* ) => {
         impl_visit_operator!($($rest)*);
     };
+    ( @sign_extension $op:ident => $visit:ident $($rest:tt)* ) => {
+        impl_visit_operator!($($rest)*);
+    };
+    ( @bulk_memory $op:ident $({ $($arg:ident: $argty:ty),* })? => visit_memory_init $($rest:tt)* ) => {
+        impl_visit_operator!($($rest)*);
+    };
+    ( @bulk_memory $op:ident $({ $($arg:ident: $argty:ty),* })? => visit_data_drop $($rest:tt)* ) => {
+        impl_visit_operator!($($rest)*);
+    };
+    ( @bulk_memory $op:ident $({ $($arg:ident: $argty:ty),* })? => visit_memory_copy $($rest:tt)* ) => {
+        impl_visit_operator!($($rest)*);
+    };
+    ( @bulk_memory $op:ident $({ $($arg:ident: $argty:ty),* })? => visit_memory_fill $($rest:tt)* ) => {
+        impl_visit_operator!($($rest)*);
+    };
     ( @$proposal:ident $op:ident $({ $($arg:ident: $argty:ty),* })? => $visit:ident $($rest:tt)* ) => {
+        #[allow(unused_variables)]
         fn $visit(&mut self $($(, $arg: $argty)*)?) -> Self::Output {
             trace!("{}", stringify!($op));
-            Ok(())
+            Err(self.unsupported(stringify!($op)))
         }
 
         impl_visit_operator!($($rest)*);
@@ -52,6 +78,25 @@ macro_rules! map_wasm_operators {
             }
         }
     };
+    (@bits $ty:tt, $wasm:tt, $bits:literal) => {
+        paste! {
+            fn [< visit_ $ty _ $wasm >](&mut self) -> Self::Output {
+                trace!("{}.{}", stringify!($ty), stringify!($wasm));
+
+                let before = self.masm.buffer().len();
+                self.[< _ $wasm >]($bits)?;
+
+                let instr = self.masm.buffer()[before..].to_vec();
+                self.backtrace.push(instr);
+
+                Ok(())
+            }
+        }
+    };
+    (@bits $wasm:tt) => {
+        map_wasm_operators!(@bits i32, $wasm, 32);
+        map_wasm_operators!(@bits i64, $wasm, 64);
+    };
     (@integer32 $wasm:tt, $evm:tt $($arg:ident: $argty:ty),*) => {
         map_wasm_operators!(@basic i32, $wasm, $evm $($arg: $argty),*);
     };
@@ -62,21 +107,70 @@ macro_rules! map_wasm_operators {
         map_wasm_operators!(@integer32 $wasm, $evm $($arg: $argty),*);
         map_wasm_operators!(@integer64 $wasm, $evm $($arg: $argty),*);
     };
-    (@xdr $wasm:tt, $evm:tt $($arg:ident: $argty:ty),*) => {
-        paste!{
-            map_wasm_operators!(@integer [< $wasm _s >], $evm $($arg: $argty),*);
-            map_wasm_operators!(@integer [< $wasm _u >], $evm $($arg: $argty),*);
+    (@xdr $ty:tt, $op:tt, $wasm:tt, $width:literal, $signed:literal) => {
+        paste! {
+            fn [< visit_ $ty _ $op >](&mut self) -> Self::Output {
+                trace!("{}.{}", stringify!($ty), stringify!($op));
+
+                let before = self.masm.buffer().len();
+                self.[< _ $wasm >]($width, $signed)?;
+
+                let instr = self.masm.buffer()[before..].to_vec();
+                self.backtrace.push(instr);
+
+                Ok(())
+            }
+        }
+    };
+    (@xdr $wasm:tt) => {
+        paste! {
+            map_wasm_operators!(@xdr i32, [< $wasm _s >], $wasm, 4, true);
+            map_wasm_operators!(@xdr i32, [< $wasm _u >], $wasm, 4, false);
+            map_wasm_operators!(@xdr i64, [< $wasm _s >], $wasm, 8, true);
+            map_wasm_operators!(@xdr i64, [< $wasm _u >], $wasm, 8, false);
+        }
+    };
+    (@float $ty:tt, $wasm:tt, $evm:tt, $format:ident) => {
+        paste! {
+            fn [< visit_ $ty _ $wasm >](&mut self) -> Self::Output {
+                trace!("{}.{}", stringify!($ty), stringify!($wasm));
+
+                let before = self.masm.buffer().len();
+                self.[< _f $evm >](Float::$format)?;
+
+                let instr = self.masm.buffer()[before..].to_vec();
+                self.backtrace.push(instr);
+
+                Ok(())
+            }
         }
     };
-    (@float32 $wasm:tt, $evm:tt $($arg:ident: $argty:ty),*) => {
-        map_wasm_operators!(@basic f32, $wasm, $evm $($arg: $argty),*);
+    (@float $wasm:tt, $evm:tt) => {
+        map_wasm_operators!(@float f32, $wasm, $evm, F32);
+        map_wasm_operators!(@float f64, $wasm, $evm, F64);
+    };
+    (@memory $ty:tt, $wasm:tt, $evm:tt $(, $signed:literal)?) => {
+        paste! {
+            fn [< visit_ $ty _ $wasm >](&mut self, memarg: MemArg) -> Self::Output {
+                trace!("{}.{} {:?}", stringify!($ty), stringify!($wasm), memarg);
+
+                let before = self.masm.buffer().len();
+                self.[< _ $evm >](memarg, ValType::[< $ty:upper >] $(, $signed)?)?;
+
+                let instr = self.masm.buffer()[before..].to_vec();
+                self.backtrace.push(instr);
+
+                Ok(())
+            }
+        }
     };
-    (@float64 $wasm:tt, $evm:tt $($arg:ident: $argty:ty),*) => {
-        map_wasm_operators!(@basic f64, $wasm, $evm $($arg: $argty),*);
+    (@memory_integer $wasm:tt, $evm:tt $(, $signed:literal)?) => {
+        map_wasm_operators!(@memory i32, $wasm, $evm $(, $signed)?);
+        map_wasm_operators!(@memory i64, $wasm, $evm $(, $signed)?);
     };
-    (@float $wasm:tt, $evm:tt $($arg:ident: $argty:ty),*) => {
-        map_wasm_operators!(@float32 $wasm, $evm $($arg: $argty),*);
-        map_wasm_operators!(@float64 $wasm, $evm $($arg: $argty),*);
+    (@memory_float $wasm:tt, $evm:tt) => {
+        map_wasm_operators!(@memory f32, $wasm, $evm);
+        map_wasm_operators!(@memory f64, $wasm, $evm);
     };
     (@integer_and_float $op:tt $($arg:ident: $argty:ty),*) => {
         map_wasm_operators!(@integer $op, $op);
@@ -110,6 +204,7 @@ macro_rules! map_wasm_operators {
         all: [$($all:tt),+],
         xdr: [$($xdr:tt),+],
         integer: [$($integer:tt),+],
+        bits: [$($bits:tt),+],
         integer_and_float: [$($op:tt),+],
         float: [$($float:tt),+],
         map: {
@@ -140,7 +235,8 @@ macro_rules! map_wasm_operators {
             )+
 
             $(map_wasm_operators!(@integer $integer, $integer);)+
-            $(map_wasm_operators!(@xdr $xdr, $xdr);)+
+            $(map_wasm_operators!(@bits $bits);)+
+            $(map_wasm_operators!(@xdr $xdr);)+
             $(map_wasm_operators!(@float $float, $float);)+
 
             $(
@@ -149,32 +245,31 @@ macro_rules! map_wasm_operators {
             )+
 
             $(
-                map_wasm_operators!(@integer $mem, $mem _arg: MemArg);
-                map_wasm_operators!(@float $mem, $mem _arg: MemArg);
+                map_wasm_operators!(@memory_integer $mem, $mem);
+                map_wasm_operators!(@memory_float $mem, $mem);
             )+
 
-
             $(
-                map_wasm_operators!(@xdr $mem_integer, $mem_integer _arg: MemArg);
+                map_wasm_operators!(@memory_integer [< $mem_integer _s >], $mem_integer, true);
+                map_wasm_operators!(@memory_integer [< $mem_integer _u >], $mem_integer, false);
             )+
 
             $(
-                map_wasm_operators!(@integer64 [< $mem_integer64 _s >], $mem_integer64 _arg: MemArg );
-                map_wasm_operators!(@integer64 [< $mem_integer64 _u >], $mem_integer64 _arg: MemArg );
+                map_wasm_operators!(@memory i64, [< $mem_integer64 _s >], $mem_integer64, true);
+                map_wasm_operators!(@memory i64, [< $mem_integer64 _u >], $mem_integer64, false);
             )+
 
-
             $(
-                map_wasm_operators!(@integer $mem_signed, $mem_signed _arg: MemArg);
+                map_wasm_operators!(@memory_integer $mem_signed, $mem_signed);
             )+
 
             $(
-                map_wasm_operators!(@integer $mem_signed_and_float, $mem_signed_and_float _arg: MemArg);
-                map_wasm_operators!(@float $mem_signed_and_float, $mem_signed_and_float _arg: MemArg);
+                map_wasm_operators!(@memory_integer $mem_signed_and_float, $mem_signed_and_float);
+                map_wasm_operators!(@memory_float $mem_signed_and_float, $mem_signed_and_float);
             )+
 
             $(
-                map_wasm_operators!(@integer64 $mem_signed64, $mem_signed64 _arg: MemArg);
+                map_wasm_operators!(@memory i64, $mem_signed64, $mem_signed64);
             )+
 
             $(
@@ -195,8 +290,9 @@ impl VisitOperator<'_> for Function {
 
     map_wasm_operators! {
         all: [div, lt, gt, ge, le],
-        xdr: [shr, trunc_f32, trunc_f64],
-        integer: [and, clz, ctz, eqz, or, popcnt, rotl, rotr, shl, xor],
+        xdr: [trunc_f32, trunc_f64],
+        integer: [and, eqz, or, shl, xor],
+        bits: [clz, ctz, popcnt, rotl, rotr],
         integer_and_float: [add, sub, mul, eq, ne],
         float: [
             abs, ceil, copysign, floor, max, min, nearest, neg, sqrt,
@@ -204,7 +300,7 @@ impl VisitOperator<'_> for Function {
             trunc
         ],
         map: {
-            integer: [rem => mod],
+            integer: [rem => mod, shr => shr],
         },
         mem: {
             all: [load],
@@ -216,14 +312,6 @@ impl VisitOperator<'_> for Function {
         },
         masm: {
             drop,
-            memory_grow: {
-                mem: u32,
-                mem_byte: u8
-            },
-            memory_size: {
-                mem: u32,
-                mem_byte: u8
-            },
             i32_const: {
                 value: i32
             },
@@ -239,8 +327,11 @@ impl VisitOperator<'_> for Function {
             i32_wrap_i64,
             i64_extend_i32_s,
             i64_extend_i32_u,
-            f32_demote_f64,
-            f64_promote_f32,
+            i32_extend8_s,
+            i32_extend16_s,
+            i64_extend8_s,
+            i64_extend16_s,
+            i64_extend32_s,
             i32_reinterpret_f32,
             i64_reinterpret_f64,
             f32_reinterpret_i32,
@@ -248,6 +339,7 @@ impl VisitOperator<'_> for Function {
         },
         global: {
             else, select, end, nop, unreachable,
+            f32_demote_f64, f64_promote_f32,
             if: {
                 blockty: BlockType
             },
@@ -266,6 +358,28 @@ impl VisitOperator<'_> for Function {
             br_table: {
                 table: BrTable<'_>
             },
+            memory_grow: {
+                mem: u32,
+                mem_byte: u8
+            },
+            memory_size: {
+                mem: u32,
+                mem_byte: u8
+            },
+            memory_init: {
+                data_index: u32,
+                mem: u32
+            },
+            data_drop: {
+                data_index: u32
+            },
+            memory_copy: {
+                dst_mem: u32,
+                src_mem: u32
+            },
+            memory_fill: {
+                mem: u32
+            },
             local_get: {
                 local_index: u32
             },
@@ -298,15 +412,14 @@ impl VisitOperator<'_> for Function {
 
         let before = self.masm.buffer().len();
 
-        // for early returns in a function, emit return code with value 1 (true)
         if self.is_main || self.abi.is_some() {
             tracing::trace!("early return from main function");
-            self.masm.emit_return_value(&[1])?;
         } else {
             tracing::trace!("early return from call");
-            self.masm.call_return(self.ty.results())?;
         }
 
+        self.return_results()?;
+
         let instr = self.masm.buffer()[before..].to_vec();
         self.backtrace.push(instr);
 
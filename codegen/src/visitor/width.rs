@@ -0,0 +1,195 @@
+//! Integer widths
+//!
+//! Integers are kept zero-extended to their WASM widths on the EVM
+//! stack, except the value on the top of the stack, which could carry
+//! the overflowed bits of an arithmetic operation until the next
+//! operator that observes them.
+
+use crate::{wasm::ToLSBytes, Function, Result};
+use wasmparser::{Operator, ValType};
+
+/// How an operator treats the integer widths of its operands.
+#[derive(Clone, Copy, Debug, Default)]
+struct Width {
+    /// If the operator ignores the overflowed bits of the top operand.
+    tolerant: bool,
+    /// The width of the operands in bytes.
+    width: usize,
+    /// Sign-extend the operands before the operator.
+    signed: Option<Operands>,
+    /// Wrap the shift count to the bits of the width.
+    shift: bool,
+    /// The width of the result if it could carry overflowed bits.
+    overflow: Option<usize>,
+}
+
+/// The operands to sign-extend.
+#[derive(Clone, Copy, Debug)]
+enum Operands {
+    /// Both of the operands.
+    Both,
+    /// The only operand, or the one under the shift count.
+    Value,
+}
+
+impl Width {
+    /// Operator which ignores the overflowed bits of the top operand.
+    fn tolerant(width: usize, overflow: Option<usize>) -> Self {
+        Self {
+            tolerant: true,
+            width,
+            overflow,
+            ..Default::default()
+        }
+    }
+
+    /// Signed operator.
+    fn signed(width: usize, operands: Operands, overflow: Option<usize>) -> Self {
+        Self {
+            signed: Some(operands),
+            ..Self::tolerant(width, overflow)
+        }
+    }
+
+    /// Shift operator.
+    fn shift(width: usize, signed: bool) -> Self {
+        Self {
+            shift: true,
+            signed: signed.then_some(Operands::Value),
+            ..Self::tolerant(width, None)
+        }
+    }
+
+    /// Get the width info of an operator.
+    fn of(op: &Operator) -> Self {
+        use Operator::*;
+
+        match op {
+            I32Add | I32Sub | I32Mul | I32Or | I32Xor | I32WrapI64 => Self::tolerant(4, Some(4)),
+            I64Add | I64Sub | I64Mul | I64Or | I64Xor => Self::tolerant(8, Some(8)),
+            I32And | I64And | Drop => Self::tolerant(0, None),
+            I64ExtendI32S => Self::signed(4, Operands::Value, Some(8)),
+            I32Shl => Self {
+                overflow: Some(4),
+                ..Self::shift(4, false)
+            },
+            I64Shl => Self {
+                overflow: Some(8),
+                ..Self::shift(8, false)
+            },
+            I32Rotl | I32Rotr => Self {
+                overflow: Some(4),
+                ..Self::shift(4, false)
+            },
+            I64Rotl | I64Rotr => Self {
+                overflow: Some(8),
+                ..Self::shift(8, false)
+            },
+            I32ShrU => Self::shift(4, false),
+            I64ShrU => Self::shift(8, false),
+            I32ShrS => Self {
+                overflow: Some(4),
+                ..Self::shift(4, true)
+            },
+            I64ShrS => Self {
+                overflow: Some(8),
+                ..Self::shift(8, true)
+            },
+            I32LtS | I32GtS | I32LeS | I32GeS => Self::signed(4, Operands::Both, None),
+            I64LtS | I64GtS | I64LeS | I64GeS => Self::signed(8, Operands::Both, None),
+            I32DivS | I32RemS => Self::signed(4, Operands::Both, Some(4)),
+            I64DivS | I64RemS => Self::signed(8, Operands::Both, Some(8)),
+            _ => Self::default(),
+        }
+    }
+}
+
+impl Function {
+    /// Prepare the operands of an operator for its width.
+    ///
+    /// Returns the width of the result if it could overflow.
+    pub(crate) fn wrap_operands(&mut self, op: &Operator) -> Result<Option<usize>> {
+        let info = Width::of(op);
+        if !info.tolerant {
+            self.wrap_dirty()?;
+        }
+
+        if info.shift {
+            self.masm.push(&(info.width * 8 - 1).to_ls_bytes())?;
+            self.masm._and()?;
+        }
+
+        match info.signed {
+            Some(Operands::Both) => {
+                self.signextend(info.width)?;
+                self.masm._swap1()?;
+                self.signextend(info.width)?;
+                self.masm._swap1()?;
+            }
+            Some(Operands::Value) if info.shift => {
+                self.masm._swap1()?;
+                self.signextend(info.width)?;
+                self.masm._swap1()?;
+            }
+            Some(Operands::Value) => self.signextend(info.width)?,
+            None => {}
+        }
+
+        Ok(info.overflow)
+    }
+
+    /// Wrap the value on the top of the stack if it carries overflowed bits.
+    pub(crate) fn wrap_dirty(&mut self) -> Result<()> {
+        if let Some(width) = self.dirty.take() {
+            self.masm._wrap(width)?;
+        }
+
+        Ok(())
+    }
+
+    /// Sign-extend the value on the top of the stack from `width` bytes.
+    fn signextend(&mut self, width: usize) -> Result<()> {
+        self.masm.push(&(width - 1).to_ls_bytes())?;
+        self.masm._signextend()
+    }
+
+    /// Width of the parameter from calldata.
+    ///
+    /// Parameters of other types from calldata, i.e. addresses, are
+    /// kept in 256 bits.
+    pub(crate) fn calldata_width(&self, index: usize) -> Option<usize> {
+        if let Some(abi) = &self.abi {
+            let input = abi.inputs.get(index)?;
+            if !matches!(
+                input.ty.as_ref(),
+                "int8" | "int16" | "int32" | "int64" | "uint8" | "uint16" | "uint32" | "uint64"
+            ) {
+                return None;
+            }
+        }
+
+        match self.ty.params().get(index)? {
+            ValType::I32 => Some(4),
+            ValType::I64 => Some(8),
+            _ => None,
+        }
+    }
+
+    /// Sign-extend the result at `index` on the top of the stack to
+    /// 256 bits if it is a signed integer in the ABI.
+    pub(crate) fn signextend_abi_result(&mut self, index: usize) -> Result<()> {
+        let Some(abi) = &self.abi else {
+            return Ok(());
+        };
+
+        let width = match abi.outputs.get(index).map(|output| output.ty.as_ref()) {
+            Some("int8") => 1,
+            Some("int16") => 2,
+            Some("int32") => 4,
+            Some("int64") => 8,
+            _ => return Ok(()),
+        };
+
+        self.signextend(width)
+    }
+}
@@ -1,5 +1,6 @@
 //! WASM ABI
 
+use crate::{Error, Result};
 use smallvec::{smallvec, SmallVec};
 use wasmparser::{BlockType, ValType};
 
@@ -25,12 +26,23 @@ impl Type for &ValType {
         match self {
             ValType::I32 | ValType::F32 => 4,
             ValType::I64 | ValType::F64 => 8,
+            // rejected by [`check_type`] before reaching the code generation,
+            // a word is the best guess for them anyway.
+            //
             // TODO: align number implementations to 256 bits (issue #20)
-            _ => unimplemented!("unknown unsupported type {self}"),
+            _ => 32,
         }
     }
 }
 
+/// Check if the value type is supported by the code generation.
+pub fn check_type(ty: &ValType) -> Result<()> {
+    match ty {
+        ValType::I32 | ValType::I64 | ValType::F32 | ValType::F64 => Ok(()),
+        _ => Err(Error::UnsupportedType(ty.to_string())),
+    }
+}
+
 impl Type for ValType {
     fn size(&self) -> usize {
         (&self).size()
@@ -8,14 +8,26 @@ use std::{
 
 /// Data section conversion
 ///
-/// NOTE: current only support constant expression.
+/// The active segments are indexed by their constant offsets, which
+/// are resolved at compile time, i.e. for the payloads of logs, and
+/// copied into the linear memory by the entry prologue. All of the
+/// segments are kept by their indexes as well, `memory.init` copies
+/// them from the code section.
 #[derive(Default, Clone, Debug)]
-pub struct Data(BTreeMap<i32, Vec<u8>>);
+pub struct Data {
+    /// Active segments by their offsets.
+    active: BTreeMap<i32, Vec<u8>>,
+    /// All segments by their indexes.
+    segments: Vec<Vec<u8>>,
+    /// Offsets of the segments by their indexes, `None` for the
+    /// passive segments.
+    offsets: Vec<Option<i32>>,
+}
 
 impl Data {
     /// Load data from offset and size
     pub fn load(&self, offset: i32, size: usize) -> Result<Vec<u8>> {
-        for ptr in self.0.keys().cloned().rev() {
+        for ptr in self.active.keys().cloned().rev() {
             if offset >= ptr {
                 let start = (offset - ptr) as usize;
                 let data = self.get(&ptr).ok_or(Error::DataNotFound(offset, size))?;
@@ -26,18 +38,47 @@ impl Data {
 
         Err(Error::DataNotFound(offset, size))
     }
+
+    /// Add a segment, the active segments should provide their offsets.
+    pub fn push(&mut self, offset: Option<i32>, data: Vec<u8>) {
+        if let Some(offset) = offset {
+            self.active.insert(offset, data.clone());
+        }
+
+        self.segments.push(data);
+        self.offsets.push(offset);
+    }
+
+    /// Get the active segments with their indexes and offsets.
+    pub fn active_segments(&self) -> impl Iterator<Item = (u32, i32, &[u8])> {
+        self.segments
+            .iter()
+            .zip(self.offsets.iter())
+            .enumerate()
+            .filter_map(|(index, (data, offset))| {
+                offset.map(|offset| (index as u32, offset, data.as_slice()))
+            })
+    }
+
+    /// Get a segment by its index.
+    pub fn segment(&self, index: u32) -> Result<&[u8]> {
+        self.segments
+            .get(index as usize)
+            .map(Vec::as_slice)
+            .ok_or(Error::SegmentNotFound(index))
+    }
 }
 
 impl Deref for Data {
     type Target = BTreeMap<i32, Vec<u8>>;
 
     fn deref(&self) -> &Self::Target {
-        &self.0
+        &self.active
     }
 }
 
 impl DerefMut for Data {
     fn deref_mut(&mut self) -> &mut Self::Target {
-        &mut self.0
+        &mut self.active
     }
 }
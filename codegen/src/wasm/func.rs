@@ -56,9 +56,19 @@ impl<'f> Functions<'f> {
 
     /// Remove all selector functions
     pub fn drain_selectors(&mut self, exports: &Exports) -> Self {
+        self.drain(exports.selectors())
+    }
+
+    /// Remove all custom errors
+    pub fn drain_errors(&mut self, exports: &Exports) -> Self {
+        self.drain(exports.errors())
+    }
+
+    /// Remove functions by their indexes
+    fn drain(&mut self, indexes: Vec<u32>) -> Self {
         let mut functions = Self::default();
 
-        for index in exports.selectors() {
+        for index in indexes {
             if let Some(function) = self.0.remove(&index) {
                 functions.0.insert(index, function);
             }
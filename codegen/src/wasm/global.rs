@@ -0,0 +1,55 @@
+//! WASM globals
+
+use smallvec::SmallVec;
+use std::{
+    collections::BTreeMap,
+    ops::{Deref, DerefMut},
+};
+
+/// WASM global variable.
+///
+/// Mutable globals live in fixed memory slots right above the slots of
+/// locals, the slot keeps the value XORed with the initial value, so the
+/// zero-initialized EVM memory already holds the initial value of every
+/// global when a call starts.
+#[derive(Clone, Debug, Default)]
+pub struct Global {
+    /// The initial value in lowest significant bytes.
+    pub init: SmallVec<[u8; 8]>,
+    /// If the global is mutable.
+    pub mutable: bool,
+    /// The EVM offset of the memory slot of mutable globals.
+    pub slot: usize,
+}
+
+/// Global section of the WASM module.
+#[derive(Clone, Debug, Default)]
+pub struct Globals(BTreeMap<u32, Global>);
+
+impl Globals {
+    /// Allocate memory slots for the mutable globals from `offset`.
+    ///
+    /// Returns the end of the allocated slots.
+    pub fn alloc(&mut self, mut offset: usize) -> usize {
+        for global in self.0.values_mut().filter(|global| global.mutable) {
+            global.slot = offset;
+            offset += 0x20;
+        }
+
+        offset
+    }
+}
+
+impl Deref for Globals {
+    type Target = BTreeMap<u32, Global>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl DerefMut for Globals {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
@@ -1,7 +1,6 @@
 //! Host functions
 
 use crate::{Error, Result};
-use anyhow::anyhow;
 use core::str::FromStr;
 use opcodes::{Cancun as OpCode, OpCode as _};
 
@@ -18,8 +17,14 @@ pub enum HostFunc {
     EmitABI,
     /// Push u256 max to stack
     U256MAX,
-    /// Revert messages with length of slots
-    Revert(usize),
+    /// Revert with a message of any length
+    Revert,
+    /// Revert with the panic code on the stack
+    Panic,
+    /// Allocate memory on the heap
+    Alloc,
+    /// Push the free memory pointer to stack
+    FreeMemoryPointer,
     /// Compiler labels
     Label(CompilerLabel),
 }
@@ -29,6 +34,7 @@ impl HostFunc {
     pub fn stack_in(&self) -> u8 {
         match self {
             Self::Evm(op) => op.stack_in() as u8,
+            Self::Alloc | Self::Panic => 1,
             _ => 0,
         }
     }
@@ -37,9 +43,23 @@ impl HostFunc {
     pub fn stack_out(&self) -> u8 {
         match self {
             Self::Evm(op) => op.stack_out() as u8,
+            Self::Alloc | Self::FreeMemoryPointer => 1,
             _ => 0,
         }
     }
+
+    /// If the function uses the heap, the payloads of logs are written
+    /// to the heap as well.
+    pub fn uses_heap(&self) -> bool {
+        matches!(
+            self,
+            Self::Alloc
+                | Self::FreeMemoryPointer
+                | Self::Evm(
+                    OpCode::LOG0 | OpCode::LOG1 | OpCode::LOG2 | OpCode::LOG3 | OpCode::LOG4
+                )
+        )
+    }
 }
 
 impl TryFrom<(&str, &str)> for HostFunc {
@@ -56,6 +76,9 @@ impl TryFrom<(&str, &str)> for HostFunc {
                 "u256_max" => Ok(Self::U256MAX),
                 "u256_addmod" => Ok(Self::Evm(OpCode::ADDMOD)),
                 "u256_mulmod" => Ok(Self::Evm(OpCode::MULMOD)),
+                "alloc" => Ok(Self::Alloc),
+                "free_memory_pointer" => Ok(Self::FreeMemoryPointer),
+                "panic" => Ok(Self::Panic),
                 "label_reserve_mem_32" => Ok(Self::Label(CompilerLabel::ReserveMemory32)),
                 "label_reserve_mem_64" => Ok(Self::Label(CompilerLabel::ReserveMemory64)),
                 _ => Err(Error::HostFuncNotFound(module.into(), name.into())),
@@ -67,10 +90,8 @@ impl TryFrom<(&str, &str)> for HostFunc {
             ("asm", name) => match name {
                 n if n.starts_with("sload") => Ok(Self::Evm(OpCode::SLOAD)),
                 n if n.starts_with("tload") => Ok(Self::Evm(OpCode::TLOAD)),
-                n if n.starts_with("revert") => {
-                    let count = n.trim_start_matches("revert");
-                    Ok(Self::Revert(count.parse().map_err(|e| anyhow!("{e}"))?))
-                }
+                // `revert1` to `revert4` are kept for the compatibility.
+                "revert" | "revert1" | "revert2" | "revert3" | "revert4" => Ok(Self::Revert),
                 n if n.starts_with("mulmod") => Ok(Self::Evm(OpCode::MULMOD)),
                 n if n.starts_with("addmod") => Ok(Self::Evm(OpCode::ADDMOD)),
                 _ => Ok(Self::NoOp),
@@ -93,6 +114,9 @@ impl TryFrom<(&str, &str)> for HostFunc {
 }
 
 /// Labels in host functions
+///
+/// The keccak scratch area is always reserved below the locals, the
+/// labels for reserving memory are kept for the compatibility.
 #[derive(Clone, Copy, Debug, PartialOrd, Ord, PartialEq, Eq)]
 pub enum CompilerLabel {
     ReserveMemory32,
@@ -0,0 +1,154 @@
+//! WASM linear memory
+
+use crate::{Error, Result};
+
+/// Size of a WASM page in bytes.
+pub const PAGE_SIZE: usize = 0x10000;
+
+/// EVM offset of the free memory pointer, the same slot as solc.
+pub const FREE_MEMORY_POINTER: usize = 0x40;
+
+/// Default size of the heap in bytes, which is only reserved if the
+/// module uses the heap.
+pub const HEAP_SIZE: usize = 0x2000;
+
+/// Memory slots reserved below the locals, the keccak scratch area
+/// and the free memory pointer.
+pub const RESERVED_SLOTS: u32 = 3;
+
+/// WASM linear memory mapped onto the EVM memory.
+///
+/// The initial pages of the linear memory are placed right above the
+/// static region that the codegen reserves for the keccak scratch area
+/// and the locals, and the heap, and they are addressed in reverse order:
+/// the byte at address `a` lives at the EVM offset `bound - 1 - a`.
+///
+/// Since WASM stores numbers in little-endian, the lowest significant
+/// byte of a value at address `a` ends up at the highest EVM offset,
+/// so `MLOAD(bound - 32 - a)` yields the value in its lowest significant
+/// bytes without any byte swapping, and the highest addresses (where
+/// rustc places the shadow stack and the data segments) are the cheapest
+/// ones to expand to.
+///
+/// The pages grown by `memory.grow` live in a separate region above the
+/// initial pages, in reverse order as well, so the budget never moves
+/// the initial pages. `memory.grow` only bumps the counter of grown pages
+/// stored right above the static region, the EVM memory is zero-initialized
+/// anyway, and the accesses are checked against the grown pages at runtime.
+///
+/// The heap is reserved between the static region and the linear memory,
+/// so allocations never expand the EVM memory past the pages, it is
+/// allocated at runtime through the free memory pointer at
+/// [`FREE_MEMORY_POINTER`].
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Memory {
+    /// The end of the static region in EVM memory.
+    pub offset: usize,
+    /// The initial size of the linear memory in pages.
+    pub pages: usize,
+    /// The maximum size of the linear memory declared by the module.
+    pub maximum: Option<usize>,
+    /// The pages that the linear memory could grow to.
+    pub budget: usize,
+    /// The size of the heap in bytes, zero if the module doesn't
+    /// use the heap.
+    pub heap: usize,
+}
+
+impl Memory {
+    /// Set the page budget of the linear memory.
+    ///
+    /// The budget never goes below the initial pages or above the
+    /// maximum pages declared by the module.
+    pub fn with_budget(&mut self, pages: usize) {
+        self.budget = pages.min(self.maximum.unwrap_or(pages)).max(self.pages);
+    }
+
+    /// The pages that could be grown at runtime.
+    pub fn growable(&self) -> usize {
+        self.budget.saturating_sub(self.pages)
+    }
+
+    /// The size of the page budget in bytes.
+    pub fn size(&self) -> usize {
+        self.budget.max(self.pages) * PAGE_SIZE
+    }
+
+    /// The size of the initial pages in bytes.
+    pub fn initial(&self) -> usize {
+        self.pages * PAGE_SIZE
+    }
+
+    /// EVM offset of the word which counts the grown pages.
+    pub fn grown(&self) -> usize {
+        self.offset
+    }
+
+    /// EVM offset of the word which holds the pointer of the latest
+    /// frame saved by recursive calls, the frames live on the heap.
+    pub fn frames(&self) -> usize {
+        self.offset + 0x20
+    }
+
+    /// The start of the heap in EVM memory.
+    ///
+    /// The free memory pointer is zero until the first allocation, which
+    /// stands for the start of the heap.
+    pub fn heap(&self) -> usize {
+        self.offset + 0x40
+    }
+
+    /// The end of the heap in EVM memory.
+    pub fn heap_end(&self) -> usize {
+        self.heap() + self.heap
+    }
+
+    /// The end of the initial pages in EVM memory.
+    ///
+    /// One extra word is kept between the heap and the top of the
+    /// initial pages so loading words from the top addresses never
+    /// reaches the heap.
+    pub fn bound(&self) -> usize {
+        self.heap_end() + 0x20 + self.initial()
+    }
+
+    /// The end of the grown pages in EVM memory.
+    ///
+    /// The grown pages start one word above [`Memory::bound`] for the
+    /// same reason as the initial pages.
+    pub fn grown_bound(&self) -> usize {
+        self.bound() + 0x20 + self.growable() * PAGE_SIZE
+    }
+
+    /// The distance between the EVM offsets of the same range in the
+    /// grown pages and in the initial pages.
+    ///
+    /// The byte at address `a` of the grown pages lives at the EVM offset
+    /// `grown_bound - 1 - (a - initial)`, which is `bound - 1 - a + distance`.
+    pub fn distance(&self) -> usize {
+        self.grown_bound() + self.initial() - self.bound()
+    }
+
+    /// EVM offset of the word which ends with the byte at `address`
+    /// of the initial pages.
+    ///
+    /// The address from the stack should be subtracted from it.
+    pub fn word(&self, address: u64) -> Result<usize> {
+        self.ptr(address, 0x20)
+    }
+
+    /// EVM offset of the byte at `address` of the initial pages.
+    ///
+    /// The address from the stack should be subtracted from it.
+    pub fn byte(&self, address: u64) -> Result<usize> {
+        self.ptr(address, 1)
+    }
+
+    fn ptr(&self, address: u64, size: usize) -> Result<usize> {
+        usize::try_from(address)
+            .ok()
+            .filter(|address| *address < self.size())
+            .and_then(|address| self.bound().checked_sub(address + size))
+            .ok_or(Error::MemoryOutOfBounds)
+    }
+}
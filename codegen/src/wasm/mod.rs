@@ -3,21 +3,28 @@
 mod abi;
 mod data;
 mod func;
+mod global;
 mod host;
+mod memory;
 
 pub use self::{
-    abi::{ToLSBytes, Type},
+    abi::{check_type, ToLSBytes, Type},
     data::Data,
     func::{Function, Functions},
+    global::{Global, Globals},
     host::HostFunc,
+    memory::{Memory, FREE_MEMORY_POINTER, HEAP_SIZE, PAGE_SIZE, RESERVED_SLOTS},
 };
 use crate::{Error, Result};
-use host::CompilerLabel;
+use opcodes::Upgrade;
 use smallvec::SmallVec;
-use std::collections::BTreeMap;
-use wasmparser::Operator;
+use std::collections::{BTreeMap, BTreeSet};
+use wasmparser::{BlockType, FuncType, Operator};
 use zabi::Abi;
 
+/// The reserved prefix of the exported ABIs of custom errors.
+pub const ERROR_PREFIX: &str = "__zink_error_";
+
 macro_rules! impl_deref {
     ($doc:literal, $name:ident, $target:ty) => {
         #[derive(Clone, Debug, Default)]
@@ -47,7 +54,11 @@ impl_deref! {
     ("WASM import section", Imports, BTreeMap<u32, HostFunc>),
     ("WASM export section", Exports, BTreeMap<u32, String>),
     ("WASM slot registry", Slots, BTreeMap<u32, u32>),
-    ("WASM function registry", Funcs, BTreeMap<u32, (u32, u32)>)
+    ("WASM function registry", Funcs, BTreeMap<u32, (u32, u32)>),
+    ("WASM type section", Types, BTreeMap<u32, FuncType>),
+    ("WASM function type indexes", Signatures, BTreeMap<u32, u32>),
+    ("WASM function table", Table, BTreeMap<u32, u32>),
+    ("WASM call graph", Calls, BTreeMap<u32, BTreeSet<u32>>)
 }
 
 /// A struct that holds the environment wasm module.
@@ -61,10 +72,28 @@ pub struct Env {
     pub slots: Slots,
     /// Function params count
     pub funcs: Funcs,
+    /// WASM function types
+    pub types: Types,
+    /// Type indexes of functions
+    pub signatures: Signatures,
+    /// WASM function table
+    pub table: Table,
+    /// Functions called by each function
+    pub calls: Calls,
     /// WASM data slots
     pub data: Data,
+    /// WASM globals
+    pub globals: Globals,
+    /// WASM linear memory
+    pub memory: Memory,
     /// Current function index
     pub index: Option<u32>,
+    /// If compile float operators with soft float
+    pub soft_float: bool,
+    /// The fork that the bytecode targets
+    pub target: Upgrade,
+    /// If lower the bytecode to EOF
+    pub eof: bool,
 }
 
 impl Env {
@@ -135,6 +164,59 @@ impl Env {
         self.imports.len() as u32 == index
     }
 
+    /// Get the type of the function
+    pub fn func_type(&self, index: u32) -> Option<&FuncType> {
+        self.types.get(self.signatures.get(&index)?)
+    }
+
+    /// Get the counts of the params and the results of a block type.
+    pub fn block_type(&self, blockty: BlockType) -> Result<(u16, u16)> {
+        match blockty {
+            BlockType::Empty => Ok((0, 0)),
+            BlockType::Type(_) => Ok((0, 1)),
+            BlockType::FuncType(index) => {
+                let ty = self
+                    .types
+                    .get(&index)
+                    .ok_or(Error::InvalidTypeIndex(index))?;
+                Ok((ty.params().len() as u16, ty.results().len() as u16))
+            }
+        }
+    }
+
+    /// If calling the function could reach the current function again,
+    /// which requires the locals of the current function to be saved.
+    pub fn is_recursive(&self, callee: u32) -> bool {
+        self.index
+            .is_some_and(|caller| self.reaches(callee, caller))
+    }
+
+    /// If the module uses the heap, for the allocations, the payloads
+    /// of logs or the frames of recursive calls.
+    pub fn uses_heap(&self) -> bool {
+        self.imports.uses_heap()
+            || self.calls.iter().any(|(caller, callees)| {
+                callees.iter().any(|callee| self.reaches(*callee, *caller))
+            })
+    }
+
+    /// If calling the function `from` could reach the function `to`.
+    fn reaches(&self, from: u32, to: u32) -> bool {
+        let mut visited = BTreeSet::new();
+        let mut queue = vec![from];
+        while let Some(func) = queue.pop() {
+            if func == to {
+                return true;
+            }
+
+            if visited.insert(func) {
+                queue.extend(self.calls.get(&func).into_iter().flatten());
+            }
+        }
+
+        false
+    }
+
     /// Clone a new environment with function index provided
     pub fn with_index(&self, index: u32) -> Self {
         let mut this = self.clone();
@@ -168,20 +250,9 @@ impl Imports {
         self.get(&index) == Some(&HostFunc::EmitABI)
     }
 
-    /// Get reserved slots in memory for storage calculations
-    pub fn reserved(&self) -> u32 {
-        let mut reserved = 0;
-        for host_fn in self.0.values() {
-            match *host_fn {
-                HostFunc::Label(CompilerLabel::ReserveMemory32) => reserved = 1,
-                HostFunc::Label(CompilerLabel::ReserveMemory64) => {
-                    return 2;
-                }
-                _ => {}
-            }
-        }
-
-        reserved
+    /// If any imported function uses the heap.
+    pub fn uses_heap(&self) -> bool {
+        self.values().any(HostFunc::uses_heap)
     }
 }
 
@@ -198,4 +269,20 @@ impl Exports {
             })
             .collect::<Vec<_>>()
     }
+
+    /// Get all custom errors
+    ///
+    /// The ABIs of custom errors are exported with the reserved prefix
+    /// [`ERROR_PREFIX`] by `#[derive(Error)]`.
+    pub fn errors(&self) -> Vec<u32> {
+        self.iter()
+            .filter_map(|(index, export)| {
+                if export.starts_with(ERROR_PREFIX) {
+                    Some(*index)
+                } else {
+                    None
+                }
+            })
+            .collect::<Vec<_>>()
+    }
 }
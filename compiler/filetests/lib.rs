@@ -28,12 +28,9 @@ impl Test {
         let Test { module, name, wasm } = self;
         tracing::info!("Compiling {module}::{name}");
 
-        let compiler = zinkc::Compiler::default();
-        // TODO: after #248
-        if name == "fibonacci" {
-            return Ok(());
-        }
-        compiler.compile(&wasm)?;
+        let config = zinkc::Config::default().soft_float(module == "float");
+        let compiler = zinkc::Compiler::new(config);
+        compiler.compile(wasm)?;
         Ok(())
     }
 }
@@ -0,0 +1,5 @@
+(module
+  (func (export "main") (param i32) (result i32)
+    (i32.clz (local.get 0))
+  )
+)
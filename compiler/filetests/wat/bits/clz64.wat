@@ -0,0 +1,5 @@
+(module
+  (func (export "main") (param i64) (result i64)
+    (i64.clz (local.get 0))
+  )
+)
@@ -0,0 +1,5 @@
+(module
+  (func (export "main") (param i32 i32) (result i32)
+    (i32.rotl (local.get 0) (local.get 1))
+  )
+)
@@ -0,0 +1,5 @@
+(module
+  (func (export "main") (param i64 i64) (result i64)
+    (i64.rotr (local.get 0) (local.get 1))
+  )
+)
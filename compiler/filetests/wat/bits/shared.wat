@@ -0,0 +1,8 @@
+(module
+  ;; both variants of popcnt share the function in code section
+  (func (export "main") (param i32 i64) (result i32)
+    (i32.popcnt (local.get 0))
+    (i32.wrap_i64 (i64.popcnt (local.get 1)))
+    (i32.add)
+  )
+)
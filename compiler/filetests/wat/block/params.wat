@@ -0,0 +1,19 @@
+;;! target = "evm"
+(module
+  (func (export "main") (param i32 i32) (result i32)
+    (local.get 0)
+    (local.get 1)
+    (block (param i32 i32) (result i32)
+      (i32.add)
+      ;; carries the sum if the second param is not zero.
+      (br_if 0 (local.get 1))
+      (i32.const 100)
+      (i32.add))
+    (local.get 1)
+    (if (param i32) (result i32)
+      (then
+        (i32.const 1)
+        (i32.add))
+      (else
+        (i32.const 2)
+        (i32.mul)))))
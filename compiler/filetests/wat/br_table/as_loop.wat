@@ -0,0 +1,15 @@
+(module
+  (func (export "as_loop") (param i32) (result i32)
+    (local i32 i32)
+    (local.set 2 (local.get 0))
+    (block
+      (loop
+        ;; count down the param
+        (local.set 1 (i32.add (local.get 1) (i32.const 2)))
+        (local.set 2 (i32.sub (local.get 2) (i32.const 1)))
+        (br_table 1 0 (i32.ne (local.get 2) (i32.const 0)))
+      )
+    )
+    (local.get 1)
+  )
+)
@@ -0,0 +1,37 @@
+(module
+  (func (export "dense") (param i32) (result i32)
+    (local i32)
+    (block $out
+      (block $a6
+        (block $a5
+          (block $a4
+            (block $a3
+              (block $a2
+                (block $a1
+                  (block $a0
+                    (br_table $a0 $a1 $a2 $a3 $a4 $a5 $a6 (local.get 0))
+                  )
+                  (local.set 1 (i32.const 10))
+                  (br $out)
+                )
+                (local.set 1 (i32.const 11))
+                (br $out)
+              )
+              (local.set 1 (i32.const 12))
+              (br $out)
+            )
+            (local.set 1 (i32.const 13))
+            (br $out)
+          )
+          (local.set 1 (i32.const 14))
+          (br $out)
+        )
+        (local.set 1 (i32.const 15))
+        (br $out)
+      )
+      (local.set 1 (i32.const 16))
+      (br $out)
+    )
+    (local.get 1)
+  )
+)
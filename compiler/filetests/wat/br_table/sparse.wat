@@ -0,0 +1,21 @@
+(module
+  (func (export "sparse") (param i32) (result i32)
+    (local i32)
+    (block $out
+      (block $a2
+        (block $a1
+          (block $a0
+            (br_table $a0 $a0 $a0 $a0 $a1 $a1 $a1 $a1 $a1 $a1 $a1 $a1 $a0 $a2 (local.get 0))
+          )
+          (local.set 1 (i32.const 10))
+          (br $out)
+        )
+        (local.set 1 (i32.const 11))
+        (br $out)
+      )
+      (local.set 1 (i32.const 12))
+      (br $out)
+    )
+    (local.get 1)
+  )
+)
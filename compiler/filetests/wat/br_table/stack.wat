@@ -0,0 +1,14 @@
+(module
+  (func (export "stack") (param i32) (result i32)
+    (block (result i32)
+      (i32.const 100)
+      (block (result i32)
+        ;; dropped by the branches
+        (i32.const 200)
+        (i32.const 7)
+        (br_table 1 0 1 (local.get 0))
+      )
+      (i32.add)
+    )
+  )
+)
@@ -0,0 +1,18 @@
+;;! target = "evm"
+(module
+  (func (export "main") (param i32) (result i32)
+    (local.get 0)
+    (call $nested))
+
+  ;; returns the param from nested frames if it is not zero.
+  (func $nested (param i32) (result i32)
+    (i32.const 7)
+    (block (result i32)
+      (i32.const 9)
+      (if (local.get 0)
+        (then
+          (local.get 0)
+          (return)))
+      (drop)
+      (i32.const 3))
+    (i32.add)))
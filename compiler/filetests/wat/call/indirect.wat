@@ -0,0 +1,28 @@
+(module
+  (type $binop (func (param i32 i32) (result i32)))
+  (type $unop (func (param i32) (result i32)))
+  (table 4 funcref)
+  (elem (i32.const 0) $add $sub $neg)
+
+  (func (export "main") (param i32) (param i32) (param i32) (result i32)
+    (call_indirect (type $binop) (local.get 1) (local.get 2) (local.get 0))
+  )
+
+  (func $add (type $binop)
+    (local.get 0)
+    (local.get 1)
+    (i32.add)
+  )
+
+  (func $sub (type $binop)
+    (local.get 0)
+    (local.get 1)
+    (i32.sub)
+  )
+
+  (func $neg (type $unop)
+    (i32.const 0)
+    (local.get 0)
+    (i32.sub)
+  )
+)
@@ -0,0 +1,21 @@
+(module
+  (type $fac (func (param i32) (result i32)))
+  (table 1 funcref)
+  (elem (i32.const 0) $fac)
+
+  (func (export "main") (param i32) (result i32)
+    (call_indirect (type $fac) (local.get 0) (i32.const 0))
+  )
+
+  ;; the local is read after the recursive call
+  (func $fac (type $fac) (param i32) (result i32)
+    (local i32)
+    (local.set 1 (local.get 0))
+    (if (result i32) (i32.eqz (local.get 0))
+      (then (i32.const 1))
+      (else
+        (i32.mul
+          (local.get 1)
+          (call_indirect (type $fac) (i32.sub (local.get 0) (i32.const 1)) (i32.const 0)))))
+  )
+)
@@ -0,0 +1,24 @@
+(module
+  (func $main (export "main") (param i32) (result i32)
+    local.get 0
+    call $check)
+
+  (func $check (param i32) (result i32)
+    local.get 0
+    i32.eqz
+    if
+      unreachable
+    end
+    local.get 0)
+
+  ;; A compile unit with a line program mapping all of the code
+  ;; section to src/lib.rs:42.
+  (@custom ".debug_abbrev" "\01\11\00\10\17\00\00\00")
+  (@custom ".debug_info" "\0c\00\00\00\04\00\00\00\00\00\04\01\00\00\00\00")
+  (@custom ".debug_line"
+    "\38\00\00\00\04\00\22\00\00\00\01\01\01\fb\0e\0d"
+    "\00\01\01\01\01\00\00\00\01\00\00\01"
+    "src\00\00"
+    "lib.rs\00\01\00\00\00"
+    "\00\05\02\00\00\00\00\03\29\01\02\80\20\00\01\01")
+)
@@ -0,0 +1,22 @@
+(module
+  (import "zinkc" "emit_abi" (func $emit_abi (param i32 i32)))
+  (memory 1)
+  (data (i32.const 0) "0x036164640102017806017906010006")
+  (data (i32.const 32) "0x037375620102017806017906010006")
+
+  (func (export "add_selector")
+    (call $emit_abi (i32.const 0) (i32.const 32))
+  )
+
+  (func (export "sub_selector")
+    (call $emit_abi (i32.const 32) (i32.const 32))
+  )
+
+  (func (export "add") (param i32 i32) (result i32)
+    (i32.add (local.get 0) (local.get 1))
+  )
+
+  (func (export "sub") (param i32 i32) (result i32)
+    (i32.sub (local.get 0) (local.get 1))
+  )
+)
@@ -0,0 +1,94 @@
+(module
+  (import "zinkc" "emit_abi" (func $emit_abi (param i32 i32)))
+  (memory 1)
+  (data (i32.const 0) "0x0266300100010006")
+  (data (i32.const 32) "0x0266310100010006")
+  (data (i32.const 64) "0x0266320100010006")
+  (data (i32.const 96) "0x0266330100010006")
+  (data (i32.const 128) "0x0266340100010006")
+  (data (i32.const 160) "0x0266350100010006")
+  (data (i32.const 192) "0x0266360100010006")
+  (data (i32.const 224) "0x0266370100010006")
+  (data (i32.const 256) "0x0266380100010006")
+  (data (i32.const 288) "0x0266390100010006")
+
+  (func (export "f0_selector")
+    (call $emit_abi (i32.const 0) (i32.const 18))
+  )
+
+  (func (export "f1_selector")
+    (call $emit_abi (i32.const 32) (i32.const 18))
+  )
+
+  (func (export "f2_selector")
+    (call $emit_abi (i32.const 64) (i32.const 18))
+  )
+
+  (func (export "f3_selector")
+    (call $emit_abi (i32.const 96) (i32.const 18))
+  )
+
+  (func (export "f4_selector")
+    (call $emit_abi (i32.const 128) (i32.const 18))
+  )
+
+  (func (export "f5_selector")
+    (call $emit_abi (i32.const 160) (i32.const 18))
+  )
+
+  (func (export "f6_selector")
+    (call $emit_abi (i32.const 192) (i32.const 18))
+  )
+
+  (func (export "f7_selector")
+    (call $emit_abi (i32.const 224) (i32.const 18))
+  )
+
+  (func (export "f8_selector")
+    (call $emit_abi (i32.const 256) (i32.const 18))
+  )
+
+  (func (export "f9_selector")
+    (call $emit_abi (i32.const 288) (i32.const 18))
+  )
+
+  (func (export "f0") (result i32)
+    (i32.const 0)
+  )
+
+  (func (export "f1") (result i32)
+    (i32.const 1)
+  )
+
+  (func (export "f2") (result i32)
+    (i32.const 2)
+  )
+
+  (func (export "f3") (result i32)
+    (i32.const 3)
+  )
+
+  (func (export "f4") (result i32)
+    (i32.const 4)
+  )
+
+  (func (export "f5") (result i32)
+    (i32.const 5)
+  )
+
+  (func (export "f6") (result i32)
+    (i32.const 6)
+  )
+
+  (func (export "f7") (result i32)
+    (i32.const 7)
+  )
+
+  (func (export "f8") (result i32)
+    (i32.const 8)
+  )
+
+  (func (export "f9") (result i32)
+    (i32.const 9)
+  )
+)
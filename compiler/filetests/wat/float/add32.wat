@@ -0,0 +1,8 @@
+(module
+  (func (export "main") (param i32 i32) (result i32)
+    (i32.reinterpret_f32
+      (f32.add
+        (f32.reinterpret_i32 (local.get 0))
+        (f32.reinterpret_i32 (local.get 1))))
+  )
+)
@@ -0,0 +1,5 @@
+(module
+  (func (export "main") (param i64) (result i64)
+    (i64.reinterpret_f64 (f64.convert_i64_s (local.get 0)))
+  )
+)
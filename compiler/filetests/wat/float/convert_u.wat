@@ -0,0 +1,5 @@
+(module
+  (func (export "main") (param i32) (result i32)
+    (i32.reinterpret_f32 (f32.convert_i32_u (local.get 0)))
+  )
+)
@@ -0,0 +1,5 @@
+(module
+  (func (export "main") (param i64) (result i32)
+    (i32.reinterpret_f32 (f32.demote_f64 (f64.reinterpret_i64 (local.get 0))))
+  )
+)
@@ -0,0 +1,7 @@
+(module
+  (func (export "main") (param i64 i64) (result i32)
+    (f64.eq
+      (f64.reinterpret_i64 (local.get 0))
+      (f64.reinterpret_i64 (local.get 1)))
+  )
+)
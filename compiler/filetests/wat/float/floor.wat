@@ -0,0 +1,6 @@
+(module
+  (func (export "main") (param i64) (result i64)
+    (i64.reinterpret_f64
+      (f64.floor (f64.reinterpret_i64 (local.get 0))))
+  )
+)
@@ -0,0 +1,8 @@
+(module
+  (func (export "main") (param i64 i64) (result i64)
+    (i64.reinterpret_f64
+      (f64.min
+        (f64.reinterpret_i64 (local.get 0))
+        (f64.reinterpret_i64 (local.get 1))))
+  )
+)
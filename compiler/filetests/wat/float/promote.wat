@@ -0,0 +1,5 @@
+(module
+  (func (export "main") (param i32) (result i64)
+    (i64.reinterpret_f64 (f64.promote_f32 (f32.reinterpret_i32 (local.get 0))))
+  )
+)
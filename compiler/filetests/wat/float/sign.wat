@@ -0,0 +1,5 @@
+(module
+  (func (export "main") (result i64)
+    (i64.reinterpret_f64 (f64.neg (f64.abs (f64.const -1.5))))
+  )
+)
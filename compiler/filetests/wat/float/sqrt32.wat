@@ -0,0 +1,6 @@
+(module
+  (func (export "main") (param i32) (result i32)
+    (i32.reinterpret_f32
+      (f32.sqrt (f32.reinterpret_i32 (local.get 0))))
+  )
+)
@@ -0,0 +1,5 @@
+(module
+  (func (export "main") (param i64) (result i32)
+    (i32.trunc_f64_s (f64.reinterpret_i64 (local.get 0)))
+  )
+)
@@ -0,0 +1,5 @@
+(module
+  (func (export "main") (param i64) (result i64)
+    (i64.trunc_f64_u (f64.reinterpret_i64 (local.get 0)))
+  )
+)
@@ -0,0 +1,13 @@
+(module
+  (global $counter (mut i32) (i32.const 10))
+  (global $step i32 (i32.const 100))
+  (func (export "counter") (param i32) (result i32)
+    (global.set $counter (i32.add (global.get $counter) (local.get 0)))
+    (call $incr)
+    (i32.add (global.get $counter) (global.get $step))
+  )
+
+  (func $incr
+    (global.set $counter (i32.add (global.get $counter) (i32.const 1)))
+  )
+)
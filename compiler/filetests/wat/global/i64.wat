@@ -0,0 +1,7 @@
+(module
+  (global $value (mut i64) (i64.const -1))
+  (func (export "i64") (param i64) (result i64)
+    (global.set $value (i64.sub (global.get $value) (local.get 0)))
+    (global.get $value)
+  )
+)
@@ -0,0 +1,19 @@
+(module
+  (memory 17)
+  (global $__stack_pointer (mut i32) (i32.const 1048576))
+  (func (export "stack_pointer") (param i32) (result i32)
+    (local i32)
+
+    ;; allocate a stack frame of 16 bytes
+    (local.set 1 (i32.sub (global.get $__stack_pointer) (i32.const 16)))
+    (global.set $__stack_pointer (local.get 1))
+    (i32.store offset=12 (local.get 1) (local.get 0))
+
+    ;; release the stack frame
+    (global.set $__stack_pointer (i32.add (local.get 1) (i32.const 16)))
+
+    (i32.add
+      (i32.load offset=12 (local.get 1))
+      (i32.eq (global.get $__stack_pointer) (i32.const 1048576)))
+  )
+)
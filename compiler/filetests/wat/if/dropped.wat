@@ -0,0 +1,14 @@
+(module
+  (func (export "dropped") (param i32) (result i32)
+    (drop (i32.const 1))
+    (if (result i32) (local.get 0)
+      (then
+        ;; more expensive until the drops are optimized out.
+        (drop (i32.const 2))
+        (drop (i32.const 3))
+        (drop (i32.const 4))
+        (i32.const 5))
+      (else
+        (i32.add (i32.const 6) (i32.const 7))))
+  )
+)
@@ -0,0 +1,10 @@
+(module
+  (func (export "early_return") (param i32) (result i32)
+    ;; left on the stack by the early return.
+    (i32.const 5)
+    (if (local.get 0)
+      (then
+        (i32.mul (local.get 0) (i32.const 3))
+        (return)))
+    (drop)
+    (i32.const 7)))
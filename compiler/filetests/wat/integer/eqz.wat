@@ -0,0 +1,6 @@
+(module
+  ;; the sum overflows to zero in i32
+  (func (export "main") (param i32) (result i32)
+    (i32.eqz (i32.add (local.get 0) (i32.const 1)))
+  )
+)
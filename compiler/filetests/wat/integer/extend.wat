@@ -0,0 +1,5 @@
+(module
+  (func (export "main") (param i64) (result i64)
+    (i64.extend_i32_s (i32.wrap_i64 (local.get 0)))
+  )
+)
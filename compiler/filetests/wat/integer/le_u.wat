@@ -0,0 +1,7 @@
+(module
+  (func (export "main") (param i32 i32) (result i32)
+    (local.get 0)
+    (local.get 1)
+    (i32.le_u)
+  )
+)
@@ -0,0 +1,7 @@
+(module
+  (func (export "main") (param i64 i64) (result i64)
+    (local.get 0)
+    (local.get 1)
+    (i64.mul)
+  )
+)
@@ -0,0 +1,12 @@
+(module
+  (type (;0;) (func))
+  (type (;1;) (func (param i32 i32 i32)))
+  (import "evm" "log1" (func (;0;) (type 1)))
+  (import "env" "memory" (memory (;0;) 17))
+  (func (;1;) (type 0)
+    i32.const 1
+    i32.const 1048576
+    i32.const 67
+    call 0)
+  (export "long" (func 1))
+  (data (;0;) (i32.const 1048576) "The payload of this log is longer than two words in the EVM memory."))
@@ -0,0 +1,15 @@
+;;! target = "evm"
+(module
+  (func (export "main") (param i32) (result i32)
+    (local i32)
+    (i32.const 0)
+    (local.get 0)
+    ;; [sum, n] -> [sum + n, n - 1]
+    (loop (param i32 i32) (result i32)
+      (local.set 1)
+      (local.get 1)
+      (i32.add)
+      (i32.sub (local.get 1) (i32.const 1))
+      (local.tee 1)
+      (br_if 0 (local.get 1))
+      (drop))))
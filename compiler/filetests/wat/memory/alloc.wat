@@ -0,0 +1,10 @@
+(module
+  (import "zinkc" "alloc" (func $alloc (param i32) (result i32)))
+  (import "zinkc" "free_memory_pointer" (func $free_memory_pointer (result i32)))
+
+  ;; the size allocated on the heap
+  (func (param i32) (result i32) (local i32)
+    (local.set 1 (call $alloc (local.get 0)))
+    (i32.sub (call $free_memory_pointer) (local.get 1))
+  )
+)
@@ -0,0 +1,11 @@
+(module
+  (memory 1)
+  (func (export "bounds") (param i32) (result i32)
+    (local i32)
+    (local.set 1 (i32.const 7))
+
+    ;; accesses beyond the page trap instead of reaching the locals
+    (i32.store (local.get 0) (i32.const 0x11223344))
+    (i32.add (local.get 1) (i32.load8_u offset=3 (local.get 0)))
+  )
+)
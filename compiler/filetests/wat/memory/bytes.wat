@@ -0,0 +1,9 @@
+(module
+  (memory 1)
+  (func (export "bytes") (param i32) (result i32)
+    (i32.store (i32.const 100) (i32.const 0x11223344))
+
+    ;; values are stored in little-endian
+    (i32.load8_u offset=100 (local.get 0))
+  )
+)
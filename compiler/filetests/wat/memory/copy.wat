@@ -0,0 +1,10 @@
+(module
+  (memory 1)
+
+  ;; copy the word at 8 to the address, the regions could overlap
+  (func (param i32) (result i64)
+    (i64.store (i32.const 8) (i64.const 0x0807060504030201))
+    (memory.copy (local.get 0) (i32.const 8) (i32.const 8))
+    (i64.load (local.get 0))
+  )
+)
@@ -0,0 +1,9 @@
+(module
+  (memory 1)
+
+  ;; fill bytes from 8 with the value
+  (func (param i32 i32) (result i64)
+    (memory.fill (i32.const 8) (local.get 0) (local.get 1))
+    (i64.load (i32.const 8))
+  )
+)
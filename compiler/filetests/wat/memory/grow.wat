@@ -0,0 +1,6 @@
+(module
+  (memory 1 3)
+  (func (export "grow") (param i32) (result i32)
+    (memory.grow (local.get 0))
+  )
+)
@@ -0,0 +1,9 @@
+(module
+  (memory 1 2)
+  (func (export "grown") (param i32 i32) (result i32)
+    (if (local.get 0)
+      (then (drop (memory.grow (i32.const 1)))))
+    (i32.store (local.get 1) (i32.const 42))
+    (i32.load (local.get 1))
+  )
+)
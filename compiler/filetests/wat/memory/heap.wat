@@ -0,0 +1,13 @@
+(module
+  (import "zinkc" "alloc" (func $alloc (param i32) (result i32)))
+  (import "evm" "log0" (func $log0 (param i32 i32)))
+  (import "env" "memory" (memory 17))
+
+  ;; log above the bytes allocated on the heap
+  (func (param i32)
+    (drop (call $alloc (local.get 0)))
+    (call $log0 (i32.const 1048576) (i32.const 4))
+  )
+
+  (data (i32.const 1048576) "Ping")
+)
@@ -0,0 +1,11 @@
+(module
+  (memory 1)
+  (data $bytes "\01\02\03\04\05\06\07\08")
+
+  ;; load 4 bytes of the passive segment from the offset
+  (func (param i32) (result i64)
+    (memory.init $bytes (i32.const 16) (local.get 0) (i32.const 4))
+    (data.drop $bytes)
+    (i64.load (i32.const 16))
+  )
+)
@@ -0,0 +1,7 @@
+(module
+  (memory 1)
+  (func (export "load_s") (param i32) (result i64)
+    (i32.store16 (i32.const 0) (local.get 0))
+    (i64.load16_s (i32.const 0))
+  )
+)
@@ -0,0 +1,9 @@
+(module
+  (memory 1)
+  (func (export "rodata") (param i32) (result i32)
+    ;; the constant table of rustc, indexed at runtime
+    (i32.load
+      (i32.add (i32.const 1024) (i32.shl (local.get 0) (i32.const 2))))
+  )
+  (data (i32.const 1024) "\11\00\00\00\22\02\00\00\33\03\03\00\44\04\04\04")
+)
@@ -0,0 +1,14 @@
+(module
+  (memory 1)
+  (func (export "size") (param i32) (result i32)
+    (local i32)
+    (drop (memory.grow (local.get 0)))
+
+    ;; the last word of the grown memory
+    (local.set 1
+      (i32.sub (i32.shl (memory.size) (i32.const 16)) (i32.const 4)))
+    (i32.store (local.get 1) (i32.const 42))
+
+    (i32.add (memory.size) (i32.load (local.get 1)))
+  )
+)
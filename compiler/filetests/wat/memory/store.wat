@@ -0,0 +1,15 @@
+(module
+  (memory 1)
+  (func (export "store") (param i32) (result i32)
+    (local i32)
+    (local.set 1 (i32.const 42))
+
+    (i32.store (i32.const 16) (local.get 0))
+    (i32.store (i32.const 20) (i32.const 7))
+
+    ;; locals should not be touched by the memory operations
+    (i32.add
+      (i32.load (i32.const 16))
+      (local.get 1))
+  )
+)
@@ -0,0 +1,8 @@
+(module
+  (memory 1)
+  (func (export "store8") (result i64)
+    (i64.store (i32.const 8) (i64.const 0x0102030405060708))
+    (i32.store8 (i32.const 10) (i32.const 0x1ff))
+    (i64.load (i32.const 8))
+  )
+)
@@ -0,0 +1,13 @@
+(module
+  (func (param i32 i32) (result i32)
+    (local.get 0)
+    (local.get 1)
+    (block (param i32 i32) (result i32 i32)
+      ;; branch with both of the params if the first one is zero
+      (br_if 0 (i32.eqz (local.get 0)))
+      (i32.add)
+      (i32.const 1)
+    )
+    (i32.mul)
+  )
+)
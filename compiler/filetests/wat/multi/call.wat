@@ -0,0 +1,15 @@
+(module
+  (func (param i32 i32 i32) (result i32 i32 i32)
+    (call $rotate (local.get 0) (local.get 1) (local.get 2))
+  )
+
+  ;; rotates the params unless the first one is zero
+  (func $rotate (param i32 i32 i32) (result i32 i32 i32)
+    (if (i32.eqz (local.get 0))
+      (then (return (local.get 0) (local.get 1) (local.get 2)))
+    )
+    (local.get 1)
+    (local.get 2)
+    (local.get 0)
+  )
+)
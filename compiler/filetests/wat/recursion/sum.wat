@@ -0,0 +1,18 @@
+(module
+  (func (export "main") (param i32) (result i32)
+    (call $sum (local.get 0))
+  )
+
+  ;; the local is read after the recursive call returns
+  (func $sum (param i32) (result i32) (local i32)
+    (local.set 1 (local.get 0))
+    (if (result i32) (i32.eqz (local.get 0))
+      (then (i32.const 0))
+      (else
+        (call $sum (i32.sub (local.get 0) (i32.const 1)))
+        (local.get 1)
+        (i32.add)
+      )
+    )
+  )
+)
@@ -0,0 +1,50 @@
+(module
+  (type (;0;) (func (param i32 i32)))
+  (type (;1;) (func (param i32) (result i32)))
+  (type (;2;) (func))
+  (type (;3;) (func (param i32)))
+  (import "zinkc" "emit_abi" (func (;0;) (type 0)))
+  (import "zinkc" "alloc" (func (;1;) (type 1)))
+  (import "asm" "push_u32" (func (;2;) (type 3)))
+  (import "evm" "mstore" (func (;3;) (type 2)))
+  (import "evm" "revert" (func (;4;) (type 2)))
+  (memory (;0;) 17)
+  (func (;5;) (type 3) (param i32)
+    (local i32)
+    i32.const 64
+    call 1
+    local.set 1
+    i32.const -396228608
+    call 2
+    local.get 1
+    call 2
+    call 3
+    i32.const 7
+    call 2
+    local.get 1
+    i32.const 32
+    i32.add
+    call 2
+    call 3
+    local.get 0
+    call 2
+    local.get 1
+    i32.const 64
+    i32.add
+    call 2
+    call 3
+    i32.const 68
+    call 2
+    local.get 1
+    i32.const 28
+    i32.add
+    call 2
+    call 4
+    unreachable)
+  (func (;6;) (type 2)
+    i32.const 1048576
+    i32.const 68
+    call 0)
+  (export "main" (func 5))
+  (export "__zink_error_errors_insufficient" (func 6))
+  (data (;0;) (i32.const 1048576) "0x0c496e73756666696369656e7402020762616c616e636508066e65656465640800"))
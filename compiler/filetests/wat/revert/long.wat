@@ -0,0 +1,11 @@
+(module
+  (type (;0;) (func))
+  (type (;1;) (func (param i32 i32)))
+  (import "asm" "revert" (func (;0;) (type 1)))
+  (import "env" "memory" (memory (;0;) 17))
+  (func (;1;) (type 0)
+    i32.const 1048576
+    i32.const 133
+    call 0)
+  (export "long" (func 1))
+  (data (;0;) (i32.const 1048576) "A descriptive revert message which is longer than the four words that the revert macro used to be limited to, so it takes five words."))
@@ -0,0 +1,15 @@
+(module
+  (type (;0;) (func (param i32)))
+  (type (;1;) (func (param i32) (result i32)))
+  (import "zinkc" "panic" (func (;0;) (type 0)))
+  (func (;1;) (type 1) (param i32) (result i32)
+    local.get 0
+    i32.eqz
+    if
+      i32.const 18
+      call 0
+    end
+    i32.const 42
+    local.get 0
+    i32.div_u)
+  (export "main" (func 1)))
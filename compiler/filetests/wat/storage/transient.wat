@@ -0,0 +1,11 @@
+(module
+  (type (;0;) (func (param i32) (result i32)))
+  (type (;1;) (func (param i32 i32)))
+  (import "evm" "tstore" (func (;0;) (type 1)))
+  (import "evm" "tload" (func (;1;) (type 0)))
+  (func (type 0) (param i32) (result i32)
+        local.get 0
+        i32.const 0
+        call 0
+        i32.const 0
+        call 1))
@@ -0,0 +1,12 @@
+;;! target = "evm"
+(module
+  (func (export "main") (param i32) (result i32)
+    (block (result i32)
+      (local.get 0)
+      (br 0)
+      ;; the operands of the dead code are polymorphic.
+      (i32.add)
+      (drop)
+      (i32.const 1))
+    (i32.const 1)
+    (i32.add)))
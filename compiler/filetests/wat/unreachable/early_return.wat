@@ -0,0 +1,15 @@
+;;! target = "evm"
+(module
+  (func (export "main") (param i32) (result i32)
+    (local.get 0)
+    (call $clamp))
+
+  (func $clamp (param i32) (result i32)
+    (i32.gt_u (local.get 0) (i32.const 10))
+    (if (result i32)
+      (then
+        (i32.const 10)
+        (return)
+        (i32.const 7))
+      (else
+        (local.get 0)))))
@@ -0,0 +1,13 @@
+;;! target = "evm"
+(module
+  (func (export "main") (param i32) (result i32)
+    (block
+      (br_if 0 (local.get 0))
+      (unreachable)
+      (loop
+        (br 0))
+      (if (i32.const 1)
+        (then (nop))
+        (else (unreachable)))
+      (drop (i32.const 1)))
+    (i32.const 42)))
@@ -1,7 +1,9 @@
 //! Zink compiler artifact
 
-use crate::Config;
+use crate::{Config, GasEstimate, SourceMap};
+use std::collections::BTreeMap;
 use zabi::Abi;
+use zingen::Stats;
 
 /// Zink compiler artifact
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
@@ -11,6 +13,14 @@ pub struct Artifact {
     pub abi: Vec<Abi>,
     /// Compiler configuration.
     pub config: Config,
+    /// Static gas estimations of the exported functions.
+    pub gas: BTreeMap<String, GasEstimate>,
+    /// Statistics of the peephole optimizer by rules, empty if the
+    /// optimizer is disabled.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub peephole: BTreeMap<String, Stats>,
     /// Runtime bytecode of the contract.
     pub runtime_bytecode: Vec<u8>,
+    /// Source map of the runtime bytecode.
+    pub source_map: SourceMap,
 }
@@ -1,7 +1,7 @@
 //! Zink compiler command line interface.
 #![cfg(feature = "cli")]
 
-use crate::{Compiler, Config};
+use crate::{Compiler, Config, DispatchStrategy, Upgrade};
 use ccli::{clap, Parser};
 use std::{env, fs, path::PathBuf};
 
@@ -21,6 +21,30 @@ pub struct Compile {
     /// If enable dispatcher.
     #[clap(short, long)]
     dispatcher: bool,
+    /// The strategy of the dispatcher, `linear` or `binary-search`.
+    #[clap(long, default_value_t = DispatchStrategy::Linear)]
+    dispatch: DispatchStrategy,
+    /// The pages that the linear memory could grow to.
+    #[clap(long, default_value_t = 0)]
+    max_pages: usize,
+    /// The size of the heap in bytes.
+    #[clap(long, default_value_t = 0)]
+    heap_size: usize,
+    /// If emulate float operators in software.
+    #[clap(long)]
+    soft_float: bool,
+    /// If run the peephole optimizer over the bytecode.
+    #[clap(long)]
+    peephole: bool,
+    /// The fork that the bytecode targets.
+    #[clap(long, default_value_t = Upgrade::Cancun)]
+    target: Upgrade,
+    /// If emit an EOF container, requires the prague target.
+    #[clap(long)]
+    eof: bool,
+    /// Write source map to disk.
+    #[clap(long)]
+    source_map: bool,
 }
 
 impl Compile {
@@ -32,12 +56,29 @@ impl Compile {
             env::current_dir()?.join(self.input.with_extension(""))
         };
 
-        let compiler = Compiler::new(Config::default().dispatcher(self.dispatcher));
+        let compiler = Compiler::new(
+            Config::default()
+                .dispatcher(self.dispatcher)
+                .dispatch(self.dispatch)
+                .max_pages(self.max_pages)
+                .heap_size(self.heap_size)
+                .soft_float(self.soft_float)
+                .peephole(self.peephole)
+                .target(self.target)
+                .eof(self.eof),
+        );
         let artifact = compiler.compile(&fs::read(&self.input)?)?;
 
         output.parent().map(fs::create_dir_all);
         fs::write(&output, artifact.runtime_bytecode)?;
 
+        if self.source_map {
+            fs::write(
+                output.with_extension("map.json"),
+                serde_json::to_string_pretty(&artifact.source_map)?,
+            )?;
+        }
+
         if !self.abi {
             return Ok(());
         }
@@ -1,10 +1,13 @@
 //! Zink compiler
 
-use crate::{parser::Parser, Artifact, Config, Error, Result};
+use crate::{parser::Parser, Artifact, Config, Error, GasEstimate, Result, SourceMap};
+use std::collections::BTreeMap;
 use zabi::Abi;
 use zingen::{
-    wasm::{self, Env},
-    Buffer, Dispatcher, Function, JumpTable, BUFFER_LIMIT,
+    eof::Eof,
+    wasm::{self, Env, Type},
+    Buffer, Dispatcher, Function, Gas, GasBlock, JumpTable, Peephole, Prologue, Stats, Upgrade,
+    BUFFER_LIMIT,
 };
 
 /// Zink Compiler
@@ -16,6 +19,19 @@ pub struct Compiler {
     pub(crate) buffer: Buffer,
     /// Compiler configuration.
     pub config: Config,
+    /// EOF container emitter.
+    eof: Eof,
+    /// Code of the dispatcher run to reach each external function.
+    dispatch: BTreeMap<u32, Vec<GasBlock>>,
+    /// Gas of the compiled functions.
+    gas: BTreeMap<u32, Gas>,
+    /// Program counters of the compiled functions before the peephole
+    /// optimization.
+    offsets: BTreeMap<u32, u16>,
+    /// Statistics of the peephole optimizer.
+    peephole: BTreeMap<String, Stats>,
+    /// Code of the prologue which copies the active data segments.
+    prologue: Option<GasBlock>,
     /// Global jump table.
     table: JumpTable,
 }
@@ -33,27 +49,105 @@ impl Compiler {
     ///
     /// Returns runtime bytecode.
     pub fn compile(mut self, wasm: &[u8]) -> Result<Artifact> {
+        if self.config.eof && self.config.target < Upgrade::Prague {
+            return Err(Error::EofUnavailable(self.config.target));
+        }
+
         let mut parser = Parser::try_from(wasm)?;
+        parser.env.memory.with_budget(self.config.max_pages);
+        if parser.env.uses_heap() {
+            parser.env.memory.heap = match self.config.heap_size {
+                0 => wasm::HEAP_SIZE,
+                size => size.align(),
+            };
+        }
+        parser.env.soft_float = self.config.soft_float;
+        parser.env.target = self.config.target;
+        parser.env.eof = self.config.eof;
         let env = parser.env.clone();
 
+        if parser.reads_memory()? {
+            self.compile_prologue(&env)?;
+        }
+
+        // custom errors are only included in the ABI.
+        let errors = parser.drain_errors();
         self.compile_dispatcher(&mut parser)?;
+        self.abi.append(&mut env.load_abis(&errors)?);
+
         for func in parser.funcs.into_funcs() {
             self.compile_func(env.with_index(func.index()), func)?;
         }
 
-        self.table.code_offset(self.buffer.len() as u16);
-        self.table.relocate(&mut self.buffer)?;
-        self.artifact()
+        let source_map = if self.config.eof {
+            self.buffer = std::mem::take(&mut self.eof).finish()?.encode()?.into();
+            if self.buffer.len() > BUFFER_LIMIT {
+                return Err(Error::BufferOverflow(self.buffer.len()));
+            }
+
+            // program counters of EOF are relative to the code sections.
+            parser.debug.source_map(&[])
+        } else {
+            if self.config.peephole {
+                let saved = Self::optimize(&mut self.buffer, &mut self.table, &mut self.peephole);
+                for (index, gas) in self.gas.iter_mut() {
+                    if let Some(pc) = self.offsets.get(index) {
+                        gas.optimize(*pc, &saved);
+                    }
+                }
+
+                for block in self
+                    .prologue
+                    .iter_mut()
+                    .chain(self.dispatch.values_mut().flatten())
+                {
+                    block.optimize(0, &saved);
+                }
+            }
+
+            self.table.code_offset(self.buffer.len() as u16);
+            self.table.relocate(&mut self.buffer)?;
+            parser.debug.source_map(self.table.sources())
+        };
+
+        // the prologue copies the data segments into the top of the
+        // linear memory.
+        let (prologue, memory) = match &self.prologue {
+            Some(block) => (block.gas, env.memory.bound()),
+            None => (0, 0),
+        };
+
+        let gas = env
+            .exports
+            .iter()
+            .filter(|(index, _)| self.gas.contains_key(index))
+            .map(|(index, name)| {
+                let dispatch = self
+                    .dispatch
+                    .get(index)
+                    .map(|path| path.iter().map(|block| block.gas).sum::<u64>())
+                    .unwrap_or_default();
+                let estimate = GasEstimate::resolve(&self.gas, *index, prologue + dispatch, memory);
+                (name.clone(), estimate)
+            })
+            .collect();
+
+        self.artifact(source_map, gas)
     }
 
     /// Generate artifact
     ///
     /// yields runtime bytecode and construct bytecode
-    fn artifact(self) -> Result<Artifact> {
+    fn artifact(
+        self,
+        source_map: SourceMap,
+        gas: BTreeMap<String, GasEstimate>,
+    ) -> Result<Artifact> {
         let Compiler {
             abi,
             buffer,
             config,
+            peephole,
             ..
         } = self;
 
@@ -61,10 +155,64 @@ impl Compiler {
         Ok(Artifact {
             abi,
             config,
+            gas,
+            peephole,
             runtime_bytecode: buffer.to_vec(),
+            source_map,
         })
     }
 
+    /// Run the peephole optimizer over the bytecode and add up the
+    /// statistics, returns the gas saved at the program counters of
+    /// the original bytecode.
+    fn optimize(
+        buffer: &mut Buffer,
+        table: &mut JumpTable,
+        stats: &mut BTreeMap<String, Stats>,
+    ) -> BTreeMap<u16, u64> {
+        let mut peephole = Peephole::default();
+        peephole.run(buffer, table);
+
+        for (rule, rule_stats) in peephole.stats() {
+            tracing::debug!(
+                "peephole {rule}: {} hits, {} bytes saved",
+                rule_stats.hits,
+                rule_stats.saved
+            );
+
+            let total = stats.entry(rule.to_string()).or_default();
+            total.hits += rule_stats.hits;
+            total.saved += rule_stats.saved;
+        }
+
+        peephole.saved().clone()
+    }
+
+    /// Compile the prologue which copies the active data segments
+    /// into the linear memory.
+    fn compile_prologue(&mut self, env: &Env) -> Result<()> {
+        if env
+            .data
+            .active_segments()
+            .all(|(_, _, data)| data.is_empty())
+        {
+            return Ok(());
+        }
+
+        if self.config.eof {
+            return Err(zingen::Error::Eof(
+                "active data segments are copied from the code section".into(),
+            )
+            .into());
+        }
+
+        let mut prologue = Prologue::new(env.clone());
+        let buffer = prologue.finish(&mut self.table)?;
+        self.prologue = Some(prologue.gas());
+        self.buffer.extend_from_slice(&buffer);
+        Ok(())
+    }
+
     /// Compile EVM dispatcher.
     ///
     /// Drain selectors anyway, compile dispatcher if it is enabled.
@@ -77,8 +225,33 @@ impl Compiler {
             return Ok(());
         }
 
-        let mut dispatcher = Dispatcher::new(env, &parser.funcs)?;
-        let buffer = dispatcher.finish(selectors, &mut self.table)?;
+        let mut dispatcher = Dispatcher::new(env, &parser.funcs)?.strategy(self.config.dispatch);
+        if self.config.eof {
+            let mut table = JumpTable::default();
+            let mut buffer = dispatcher.finish(selectors, &mut table)?.into();
+            if self.config.peephole {
+                let saved = Self::optimize(&mut buffer, &mut table, &mut self.peephole);
+                for block in dispatcher.paths.values_mut().flatten() {
+                    block.optimize(0, &saved);
+                }
+            }
+
+            self.dispatch = dispatcher.paths;
+            self.eof.entry(buffer, table);
+            self.abi.append(&mut dispatcher.abi);
+            return Ok(());
+        }
+
+        let pc = self.buffer.len() as u16;
+        let mut table = JumpTable::default();
+        let buffer = dispatcher.finish(selectors, &mut table)?;
+        self.table.merge(table, pc)?;
+
+        // the paths are shifted to the program counters of the buffer.
+        for block in dispatcher.paths.values_mut().flatten() {
+            block.range = block.range.start + pc..block.range.end + pc;
+        }
+        self.dispatch = std::mem::take(&mut dispatcher.paths);
         self.buffer.extend_from_slice(&buffer);
         if self.buffer.len() > BUFFER_LIMIT {
             return Err(Error::BufferOverflow(self.buffer.len()));
@@ -103,6 +276,7 @@ impl Compiler {
 
         codegen.emit_locals(&mut locals_reader, &mut func.validator)?;
         codegen.emit_operators(&mut ops_reader, &mut func.validator)?;
+        self.gas.insert(func_index, codegen.gas());
 
         self.emit_buffer(func_index, codegen)?;
         Ok(())
@@ -110,9 +284,14 @@ impl Compiler {
 
     /// Emit buffer to the inner buffer.
     fn emit_buffer(&mut self, func_index: u32, codegen: Function) -> Result<()> {
+        if self.config.eof {
+            return self.emit_eof(func_index, codegen);
+        }
+
         let buffer = codegen.finish(&mut self.table, self.buffer.len() as u16)?;
         self.table
             .call_offset(func_index, self.buffer.len() as u16)?;
+        self.offsets.insert(func_index, self.buffer.len() as u16);
         self.buffer.extend_from_slice(&buffer);
 
         if self.buffer.len() > BUFFER_LIMIT {
@@ -122,6 +301,34 @@ impl Compiler {
         Ok(())
     }
 
+    /// Emit function to the EOF container emitter, the main function
+    /// is the entry of the container.
+    fn emit_eof(&mut self, func_index: u32, codegen: Function) -> Result<()> {
+        let is_main = codegen.is_main;
+        let results = if is_main || codegen.abi.is_some() {
+            None
+        } else {
+            Some(codegen.ty.results().len() as u8)
+        };
+
+        let mut table = JumpTable::default();
+        let mut buffer = codegen.finish(&mut table, 0)?;
+        if self.config.peephole {
+            let saved = Self::optimize(&mut buffer, &mut table, &mut self.peephole);
+            if let Some(gas) = self.gas.get_mut(&func_index) {
+                gas.optimize(0, &saved);
+            }
+        }
+
+        if is_main {
+            self.eof.entry(buffer, table);
+        } else {
+            self.eof.func(func_index, buffer, table, results);
+        }
+
+        Ok(())
+    }
+
     /// Get abi from env and function index
     fn abi(&self, env: &Env, index: u32) -> Option<Abi> {
         let name = env.exports.get(&index)?;
@@ -2,6 +2,7 @@
 
 #[cfg(feature = "cli")]
 use ccli::clap;
+use zingen::{DispatchStrategy, Upgrade};
 
 /// Zink compiler configuration.
 #[derive(Debug, Default)]
@@ -11,6 +12,50 @@ pub struct Config {
     /// If enable dispatcher.
     #[cfg_attr(feature = "cli", clap(long))]
     pub dispatcher: bool,
+    /// The strategy of the dispatcher.
+    ///
+    /// `binary-search` searches the sorted selectors, which is cheaper
+    /// for the contracts with many functions.
+    #[cfg_attr(feature = "cli", clap(long, default_value_t = DispatchStrategy::Linear))]
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub dispatch: DispatchStrategy,
+    /// The pages that the linear memory could grow to.
+    ///
+    /// Defaults to the initial pages of the module, `memory.grow`
+    /// returns -1 once the budget is exceeded.
+    #[cfg_attr(feature = "cli", clap(long, default_value_t = 0))]
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub max_pages: usize,
+    /// The size of the heap in bytes.
+    ///
+    /// Defaults to 8KiB, the heap is only reserved if the module uses
+    /// it, allocations beyond the heap trap.
+    #[cfg_attr(feature = "cli", clap(long, default_value_t = 0))]
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub heap_size: usize,
+    /// If emulate float operators in software.
+    ///
+    /// Float operators are rejected at compile time if disabled.
+    #[cfg_attr(feature = "cli", clap(long))]
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub soft_float: bool,
+    /// If run the peephole optimizer over the bytecode.
+    #[cfg_attr(feature = "cli", clap(long))]
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub peephole: bool,
+    /// The fork that the bytecode targets.
+    ///
+    /// `PUSH0` is substituted with `PUSH1 0` before shanghai, other
+    /// opcodes introduced after the target are rejected.
+    #[cfg_attr(feature = "cli", clap(long, default_value_t = Upgrade::Cancun))]
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub target: Upgrade,
+    /// If emit an EOF container instead of the legacy bytecode.
+    ///
+    /// Requires the prague target.
+    #[cfg_attr(feature = "cli", clap(long))]
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub eof: bool,
 }
 
 impl Config {
@@ -19,4 +64,46 @@ impl Config {
         self.dispatcher = dispatcher;
         self
     }
+
+    /// With the strategy of the dispatcher.
+    pub fn dispatch(mut self, dispatch: DispatchStrategy) -> Self {
+        self.dispatch = dispatch;
+        self
+    }
+
+    /// With the page budget of the linear memory.
+    pub fn max_pages(mut self, max_pages: usize) -> Self {
+        self.max_pages = max_pages;
+        self
+    }
+
+    /// With the size of the heap in bytes.
+    pub fn heap_size(mut self, heap_size: usize) -> Self {
+        self.heap_size = heap_size;
+        self
+    }
+
+    /// With soft float value.
+    pub fn soft_float(mut self, soft_float: bool) -> Self {
+        self.soft_float = soft_float;
+        self
+    }
+
+    /// With peephole optimizer value.
+    pub fn peephole(mut self, peephole: bool) -> Self {
+        self.peephole = peephole;
+        self
+    }
+
+    /// With the fork that the bytecode targets.
+    pub fn target(mut self, target: Upgrade) -> Self {
+        self.target = target;
+        self
+    }
+
+    /// With EOF value.
+    pub fn eof(mut self, eof: bool) -> Self {
+        self.eof = eof;
+        self
+    }
 }
@@ -0,0 +1,99 @@
+//! Static gas estimation of the external functions.
+
+use std::{collections::BTreeMap, fmt};
+use zingen::Gas;
+
+/// Static gas estimation of an external function.
+///
+/// Only the base gas of the opcodes and the memory expansion are
+/// estimated, loops and recursive calls run unknown times, so they are
+/// left as symbols.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct GasEstimate {
+    /// Base gas of the code run before the function, the prologue
+    /// copying the data segments and the dispatcher.
+    pub entry: u64,
+    /// Base gas of the function and the functions it calls, only the
+    /// more expensive branch of each if-else is counted, loops are
+    /// counted once.
+    pub fixed: u64,
+    /// Memory expansion cost.
+    pub memory: u64,
+    /// Base gas of one more iteration of each loop.
+    pub loops: Vec<u64>,
+    /// If the function calls itself, the recursive calls are not counted.
+    pub recursive: bool,
+}
+
+impl GasEstimate {
+    /// Resolve the estimation of a function with the functions it calls,
+    /// `entry` is the base gas and `memory` the highest memory offset of
+    /// the code run before the function.
+    pub fn resolve(funcs: &BTreeMap<u32, Gas>, index: u32, entry: u64, mut memory: usize) -> Self {
+        let mut estimate = Self {
+            entry,
+            ..Default::default()
+        };
+        estimate.walk(funcs, index, &mut vec![], &mut memory);
+
+        let words = memory.div_ceil(0x20) as u64;
+        estimate.memory = 3 * words + words * words / 512;
+        estimate
+    }
+
+    /// Add the gas of the function and its callees.
+    fn walk(
+        &mut self,
+        funcs: &BTreeMap<u32, Gas>,
+        index: u32,
+        path: &mut Vec<u32>,
+        memory: &mut usize,
+    ) {
+        if path.contains(&index) {
+            self.recursive = true;
+            return;
+        }
+
+        let Some(gas) = funcs.get(&index) else {
+            return;
+        };
+
+        self.fixed += gas.fixed;
+        self.loops.extend_from_slice(&gas.loops);
+        *memory = (*memory).max(gas.memory);
+
+        path.push(index);
+        for callee in gas.calls.iter() {
+            self.walk(funcs, *callee, path, memory);
+        }
+        path.pop();
+    }
+
+    /// The total gas if every loop runs once.
+    pub fn total(&self) -> u64 {
+        self.entry + self.fixed + self.memory
+    }
+
+    /// If the estimation is an upper bound of the gas.
+    pub fn is_bounded(&self) -> bool {
+        self.loops.is_empty() && !self.recursive
+    }
+}
+
+impl fmt::Display for GasEstimate {
+    /// Formats as `total + gas * n0 + .. (+ recursion)`, `n{i}` is the
+    /// count of the extra iterations of the loop.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.total())?;
+        for (index, gas) in self.loops.iter().enumerate() {
+            write!(f, " + {gas} * n{index}")?;
+        }
+
+        if self.recursive {
+            write!(f, " + recursion")?;
+        }
+
+        Ok(())
+    }
+}
@@ -5,14 +5,18 @@ pub use crate::{
     artifact::Artifact,
     compiler::Compiler,
     config::Config,
+    gas::GasEstimate,
     result::{Error, Result},
+    source_map::{Mapping, SourceMap},
 };
-pub use zingen::{Constructor, InitStorage};
+pub use zingen::{Constructor, DispatchStrategy, InitStorage, Upgrade};
 
 mod artifact;
 pub mod cli;
 mod compiler;
 mod config;
+mod gas;
 mod parser;
 mod result;
+mod source_map;
 pub mod utils;
@@ -1,12 +1,16 @@
 //! Zink parser
 
-use crate::{Error, Result};
-use std::iter::IntoIterator;
+use crate::{source_map::DebugInfo, Error, Result};
+use std::{collections::BTreeMap, iter::IntoIterator};
 use wasmparser::{
-    Data, DataKind, Export, ExternalKind, Import, Operator, Payload, SectionLimited, TypeRef,
-    ValidPayload, Validator,
+    CompositeType, Data, DataKind, Element, ElementItems, ElementKind, Export, ExternalKind,
+    Global, Import, MemoryType, Operator, Payload, RecGroup, SectionLimited, TypeRef, ValidPayload,
+    Validator,
+};
+use zingen::wasm::{
+    Calls, Data as DataSet, Env, Exports, Funcs, Functions, Global as GlobalVar, Globals, HostFunc,
+    Imports, Memory, Signatures, Table, ToLSBytes, Types, RESERVED_SLOTS,
 };
-use zingen::wasm::{Data as DataSet, Env, Exports, Functions, HostFunc, Imports};
 
 /// WASM module parser
 #[derive(Default)]
@@ -15,12 +19,15 @@ pub struct Parser<'p> {
     pub env: Env,
     /// All functions
     pub funcs: Functions<'p>,
+    /// Debug info for the source map.
+    pub debug: DebugInfo,
 }
 
 impl<'p> Parser<'p> {
     /// Parse WASM module.
     pub fn parse(&mut self, wasm: &'p [u8]) -> Result<()> {
         let mut validator = Validator::new();
+        let mut dwarf = BTreeMap::new();
 
         // Compile functions.
         for payload in wasmparser::Parser::new(0).parse_all(wasm) {
@@ -28,9 +35,31 @@ impl<'p> Parser<'p> {
             let valid_payload = validator.payload(&payload)?;
 
             match &payload {
-                Payload::ImportSection(reader) => self.env.imports = Self::imports(reader)?,
+                Payload::TypeSection(reader) => self.env.types = Self::types(reader)?,
+                Payload::ImportSection(reader) => {
+                    self.env.imports = Self::imports(reader)?;
+                    self.env.funcs = Self::imported_funcs(reader, &self.env.types)?;
+                    if let Some(memory) = Self::imported_memory(reader)? {
+                        self.env.memory = memory;
+                    }
+                }
+                Payload::FunctionSection(reader) => {
+                    self.env.signatures = Self::signatures(reader, self.env.imports.len() as u32)?
+                }
+                Payload::ElementSection(reader) => self.env.table = Self::table(reader)?,
                 Payload::DataSection(reader) => self.env.data = Self::data(reader)?,
                 Payload::ExportSection(reader) => self.env.exports = Self::exports(reader)?,
+                Payload::GlobalSection(reader) => self.env.globals = Self::globals(reader)?,
+                Payload::MemorySection(reader) => self.env.memory = Self::memory(reader)?,
+                Payload::CodeSectionStart { range, .. } => self.debug.code(range.start),
+                Payload::CustomSection(reader) if reader.name() == "name" => {
+                    if let Err(e) = self.debug.names(reader) {
+                        tracing::warn!("invalid name section: {e}");
+                    }
+                }
+                Payload::CustomSection(reader) if reader.name().starts_with(".debug_") => {
+                    dwarf.insert(reader.name(), reader.data());
+                }
                 _ => {}
             }
 
@@ -40,8 +69,13 @@ impl<'p> Parser<'p> {
             }
         }
 
+        if let Err(e) = self.debug.dwarf(&dwarf) {
+            tracing::warn!("invalid DWARF sections: {e}");
+        }
+
         // compute slots from functions
-        let mut slots = self.env.imports.reserved();
+        let mut slots = RESERVED_SLOTS;
+        let mut end = slots;
         for (idx, fun) in self.funcs.iter() {
             let sig = fun.sig()?;
             let locals = fun.body.get_locals_reader()?.get_count();
@@ -56,6 +90,7 @@ impl<'p> Parser<'p> {
                 .funcs
                 .insert(fun.index(), (params as u32, sig.results().len() as u32));
 
+            end = end.max(slots + params as u32 + locals);
             slots += locals;
 
             // process prarams for internal functions only
@@ -64,37 +99,242 @@ impl<'p> Parser<'p> {
             }
         }
 
+        self.env.calls = self.calls()?;
+
+        // place the mutable globals and the linear memory above the slots of locals
+        self.env.memory.offset = self.env.globals.alloc(end as usize * 0x20);
         Ok(())
     }
 
+    /// Collect the functions called by each function.
+    ///
+    /// Indirect calls may reach any function in the table.
+    fn calls(&self) -> Result<Calls> {
+        let mut calls = Calls::default();
+        for (index, fun) in self.funcs.iter() {
+            let callees = calls.entry(*index).or_default();
+            let mut reader = fun.body.get_operators_reader()?;
+            while !reader.eof() {
+                match reader.read()? {
+                    Operator::Call { function_index } | Operator::ReturnCall { function_index } => {
+                        callees.insert(function_index);
+                    }
+                    Operator::CallIndirect { .. } | Operator::ReturnCallIndirect { .. } => {
+                        callees.extend(self.env.table.values());
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        Ok(calls)
+    }
+
+    /// If any function reads the linear memory, the active data
+    /// segments are only copied into it for the loads.
+    pub fn reads_memory(&self) -> Result<bool> {
+        for (_, fun) in self.funcs.iter() {
+            let mut reader = fun.body.get_operators_reader()?;
+            while !reader.eof() {
+                if matches!(
+                    reader.read()?,
+                    Operator::I32Load { .. }
+                        | Operator::I64Load { .. }
+                        | Operator::F32Load { .. }
+                        | Operator::F64Load { .. }
+                        | Operator::I32Load8S { .. }
+                        | Operator::I32Load8U { .. }
+                        | Operator::I32Load16S { .. }
+                        | Operator::I32Load16U { .. }
+                        | Operator::I64Load8S { .. }
+                        | Operator::I64Load8U { .. }
+                        | Operator::I64Load16S { .. }
+                        | Operator::I64Load16U { .. }
+                        | Operator::I64Load32S { .. }
+                        | Operator::I64Load32U { .. }
+                        | Operator::MemoryCopy { .. }
+                ) {
+                    return Ok(true);
+                }
+            }
+        }
+
+        Ok(false)
+    }
+
     /// Drain selectors from parsed functions
     pub fn drain_selectors(&mut self) -> Functions<'p> {
         self.funcs.drain_selectors(&self.env.exports)
     }
 
+    /// Drain custom errors from parsed functions
+    pub fn drain_errors(&mut self) -> Functions<'p> {
+        self.funcs.drain_errors(&self.env.exports)
+    }
+
     /// Parse data section.
     fn data(reader: &SectionLimited<Data>) -> Result<DataSet> {
         let mut dataset = DataSet::default();
         let mut iter = reader.clone().into_iter();
         while let Some(Ok(data)) = iter.next() {
-            if let DataKind::Active {
-                memory_index: _,
-                offset_expr,
-            } = data.kind
-            {
-                // [i32.const offset call_indirect]
-                let mut reader = offset_expr.get_binary_reader();
-                let Operator::I32Const { value: offset } = reader.read_operator()? else {
-                    return Err(Error::InvalidDataOffset);
-                };
-
-                dataset.insert(offset, data.data.into());
-            }
+            let offset = match data.kind {
+                DataKind::Active {
+                    memory_index: _,
+                    offset_expr,
+                } => {
+                    // [i32.const offset call_indirect]
+                    let mut reader = offset_expr.get_binary_reader();
+                    let Operator::I32Const { value: offset } = reader.read_operator()? else {
+                        return Err(Error::InvalidDataOffset);
+                    };
+
+                    Some(offset)
+                }
+                DataKind::Passive => None,
+            };
+
+            dataset.push(offset, data.data.into());
         }
 
         Ok(dataset)
     }
 
+    /// Parse type section.
+    fn types(reader: &SectionLimited<RecGroup>) -> Result<Types> {
+        let mut types = Types::default();
+        for ty in reader.clone().into_iter() {
+            for ty in ty?.into_types() {
+                if let CompositeType::Func(func) = ty.composite_type {
+                    let index = types.len() as u32;
+                    types.insert(index, func);
+                }
+            }
+        }
+
+        Ok(types)
+    }
+
+    /// Parse function section.
+    fn signatures(reader: &SectionLimited<u32>, imports: u32) -> Result<Signatures> {
+        let mut signatures = Signatures::default();
+        for (index, ty) in reader.clone().into_iter().enumerate() {
+            signatures.insert(imports + index as u32, ty?);
+        }
+
+        Ok(signatures)
+    }
+
+    /// Parse element section.
+    fn table(reader: &SectionLimited<Element>) -> Result<Table> {
+        let mut table = Table::default();
+        for element in reader.clone().into_iter() {
+            let element = element?;
+            let ElementKind::Active { offset_expr, .. } = element.kind else {
+                continue;
+            };
+
+            let Operator::I32Const { value: offset } =
+                offset_expr.get_binary_reader().read_operator()?
+            else {
+                return Err(Error::InvalidElementOffset);
+            };
+
+            let mut slot = offset as u32;
+            match element.items {
+                ElementItems::Functions(funcs) => {
+                    for func in funcs.into_iter() {
+                        table.insert(slot, func?);
+                        slot += 1;
+                    }
+                }
+                ElementItems::Expressions(_, exprs) => {
+                    for expr in exprs.into_iter() {
+                        if let Operator::RefFunc { function_index } =
+                            expr?.get_binary_reader().read_operator()?
+                        {
+                            table.insert(slot, function_index);
+                        }
+
+                        slot += 1;
+                    }
+                }
+            }
+        }
+
+        Ok(table)
+    }
+
+    /// Parse global section.
+    fn globals(reader: &SectionLimited<Global>) -> Result<Globals> {
+        let mut globals = Globals::default();
+        for (index, global) in reader.clone().into_iter().enumerate() {
+            let global = global?;
+            let index = index as u32;
+            let init = match global.init_expr.get_binary_reader().read_operator()? {
+                Operator::I32Const { value } => value.to_ls_bytes().to_vec(),
+                Operator::I64Const { value } => value.to_ls_bytes().to_vec(),
+                Operator::F32Const { value } => value.bits().to_ls_bytes().to_vec(),
+                Operator::F64Const { value } => value.bits().to_ls_bytes().to_vec(),
+                _ => return Err(Error::InvalidGlobalInit(index)),
+            };
+
+            globals.insert(
+                index,
+                GlobalVar {
+                    init: init.into(),
+                    mutable: global.ty.mutable,
+                    slot: 0,
+                },
+            );
+        }
+
+        Ok(globals)
+    }
+
+    /// Parse memory section.
+    fn memory(reader: &SectionLimited<MemoryType>) -> Result<Memory> {
+        let mut memory = Memory::default();
+        if let Some(ty) = reader.clone().into_iter().next() {
+            memory = Self::memory_type(ty?);
+        }
+
+        Ok(memory)
+    }
+
+    /// Parse the memory imported from the host, i.e. `env.memory` of rustc.
+    fn imported_memory(reader: &SectionLimited<Import>) -> Result<Option<Memory>> {
+        for import in reader.clone().into_iter() {
+            if let TypeRef::Memory(ty) = import?.ty {
+                return Ok(Some(Self::memory_type(ty)));
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// Get the params and the results of the imported functions.
+    fn imported_funcs(reader: &SectionLimited<Import>, types: &Types) -> Result<Funcs> {
+        let mut funcs = Funcs::default();
+        for import in reader.clone().into_iter() {
+            if let TypeRef::Func(ty) = import?.ty {
+                let ty = types.get(&ty).ok_or(zingen::Error::InvalidTypeIndex(ty))?;
+                let index = funcs.len() as u32;
+                funcs.insert(index, (ty.params().len() as u32, ty.results().len() as u32));
+            }
+        }
+
+        Ok(funcs)
+    }
+
+    /// Get the linear memory from its type.
+    fn memory_type(ty: MemoryType) -> Memory {
+        Memory {
+            pages: ty.initial as usize,
+            maximum: ty.maximum.map(|pages| pages as usize),
+            ..Default::default()
+        }
+    }
+
     /// Parse export section
     pub fn exports(reader: &SectionLimited<Export>) -> Result<Exports> {
         let mut exports = Exports::default();
@@ -122,21 +362,22 @@ impl<'p> Parser<'p> {
 
     /// Parse import section.
     pub fn imports(reader: &SectionLimited<Import>) -> Result<Imports> {
-        // TODO: use real index from WASM. (#122)
-        let mut index = 0;
-
         let mut imports = Imports::default();
-        let mut iter = reader.clone().into_iter();
-        while let Some(Ok(Import {
-            module,
-            name,
-            ty: TypeRef::Func(_),
-        })) = iter.next()
-        {
+        for import in reader.clone().into_iter() {
+            // the imported globals and tables would shift the indexes
+            // of the defined ones, and there is nothing to provide them.
+            let Import { module, name, ty } = import?;
+            let ty = match ty {
+                TypeRef::Func(ty) => ty,
+                TypeRef::Memory(_) => continue,
+                _ => return Err(Error::UnsupportedImport(module.into(), name.into())),
+            };
+
+            // TODO: use real index from WASM. (#122)
+            let index = imports.len() as u32;
             let func = HostFunc::try_from((module, name))?;
-            tracing::trace!("imported function: {}::{} at {index}", module, name);
+            tracing::trace!("imported function: {module}::{name} at {index}, type {ty}");
             imports.insert(index, func);
-            index += 1;
         }
 
         Ok(imports)
@@ -15,9 +15,21 @@ pub enum Error {
     /// Failed in code generation.
     #[error(transparent)]
     Codegen(#[from] zingen::Error),
+    /// Failed to emit EOF on a fork without it.
+    #[error("EOF is not available on the target fork {0}, requires prague")]
+    EofUnavailable(zingen::Upgrade),
     /// Failed to parse WASM data with data reader.
     #[error("Invalid data offset")]
     InvalidDataOffset,
+    /// Failed to parse WASM element with const expression.
+    #[error("Invalid element offset")]
+    InvalidElementOffset,
+    /// Failed to parse WASM global with const expression.
+    #[error("Invalid initializer of global {0}")]
+    InvalidGlobalInit(u32),
+    /// Failed to import globals or tables.
+    #[error("Unsupported import {0}::{1}, only functions and the memory could be imported")]
+    UnsupportedImport(String, String),
 }
 
 /// Zinkc result
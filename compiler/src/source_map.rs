@@ -0,0 +1,153 @@
+//! Source map of the runtime bytecode.
+//!
+//! Maps the program counters of the runtime bytecode back to the WASM
+//! operators, and to the Rust sources if the module carries DWARF.
+
+use gimli::{Dwarf, EndianSlice, LittleEndian, SectionId};
+use std::collections::BTreeMap;
+use wasmparser::{CustomSectionReader, Name, NameSectionReader};
+use zingen::Source;
+
+/// Source of a range of program counters.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Mapping {
+    /// The first program counter of the range.
+    pub start: u16,
+    /// The program counter right after the range.
+    pub end: u16,
+    /// Index of the WASM function.
+    pub func: u32,
+    /// Name of the WASM function from the name section.
+    pub name: Option<String>,
+    /// Byte offset of the WASM operator in the module.
+    pub offset: usize,
+    /// Source file from the DWARF line table.
+    pub file: Option<String>,
+    /// Source line from the DWARF line table.
+    pub line: Option<u64>,
+}
+
+/// Source map of the runtime bytecode, sorted by program counters.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SourceMap(pub Vec<Mapping>);
+
+impl SourceMap {
+    /// Get the source of the program counter.
+    ///
+    /// Program counters of the dispatcher and the shared functions of
+    /// the code section have no sources.
+    pub fn lookup(&self, pc: usize) -> Option<&Mapping> {
+        let index = self
+            .0
+            .partition_point(|mapping| (mapping.end as usize) <= pc);
+        self.0
+            .get(index)
+            .filter(|mapping| mapping.start as usize <= pc)
+    }
+}
+
+/// Debug info of a WASM module.
+#[derive(Default, Debug)]
+pub struct DebugInfo {
+    /// Function names from the name section.
+    names: BTreeMap<u32, String>,
+    /// Offset of the code section, which DWARF addresses are relative to.
+    code: usize,
+    /// Source lines starting at DWARF addresses, `None` for the end of
+    /// a sequence.
+    lines: BTreeMap<u64, Option<(String, u64)>>,
+}
+
+impl DebugInfo {
+    /// Register the offset of the code section.
+    pub fn code(&mut self, offset: usize) {
+        self.code = offset;
+    }
+
+    /// Parse function names from the name section.
+    pub fn names(&mut self, reader: &CustomSectionReader) -> wasmparser::Result<()> {
+        for name in NameSectionReader::new(reader.data(), reader.data_offset()) {
+            let Name::Function(names) = name? else {
+                continue;
+            };
+
+            for naming in names {
+                let naming = naming?;
+                self.names.insert(naming.index, naming.name.into());
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Parse source lines from the DWARF sections.
+    pub fn dwarf(&mut self, sections: &BTreeMap<&str, &[u8]>) -> gimli::Result<()> {
+        let dwarf = Dwarf::load(|id: SectionId| -> gimli::Result<_> {
+            let data = sections.get(id.name()).copied().unwrap_or_default();
+            Ok(EndianSlice::new(data, LittleEndian))
+        })?;
+
+        let mut units = dwarf.units();
+        while let Some(header) = units.next()? {
+            let unit = dwarf.unit(header)?;
+            let Some(program) = unit.line_program.clone() else {
+                continue;
+            };
+
+            let mut rows = program.rows();
+            while let Some((header, row)) = rows.next_row()? {
+                if row.end_sequence() {
+                    self.lines.insert(row.address(), None);
+                    continue;
+                }
+
+                let (Some(file), Some(line)) = (row.file(header), row.line()) else {
+                    continue;
+                };
+
+                let mut path = dwarf
+                    .attr_string(&unit, file.path_name())?
+                    .to_string_lossy()
+                    .to_string();
+                if let (false, Some(dir)) = (path.starts_with('/'), file.directory(header)) {
+                    let dir = dwarf.attr_string(&unit, dir)?;
+                    path = format!("{}/{path}", dir.to_string_lossy());
+                }
+
+                self.lines.insert(row.address(), Some((path, line.get())));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Get the source line of the operator at the offset of the module.
+    fn line(&self, offset: usize) -> Option<(String, u64)> {
+        let address = offset.checked_sub(self.code)? as u64;
+        self.lines.range(..=address).next_back()?.1.clone()
+    }
+
+    /// Build the source map from the sources of the program counters.
+    pub fn source_map(&self, sources: &[Source]) -> SourceMap {
+        let mut mappings = sources
+            .iter()
+            .map(|source| {
+                let (file, line) = self.line(source.offset).unzip();
+                Mapping {
+                    start: source.start,
+                    end: source.end,
+                    func: source.func,
+                    name: self.names.get(&source.func).cloned(),
+                    offset: source.offset,
+                    file,
+                    line,
+                }
+            })
+            .collect::<Vec<_>>();
+
+        mappings.sort_by_key(|mapping| mapping.start);
+        SourceMap(mappings)
+    }
+}
@@ -4,11 +4,14 @@
 use std::path::Path;
 
 /// Run wasm-opt on the given WASM file.
+///
+/// Bulk memory operations are enabled on top of the default features,
+/// `memory.copy` and `memory.fill` are cheaper than the loops in WASM.
 pub fn wasm_opt(input: impl AsRef<Path>, output: impl AsRef<Path>) -> anyhow::Result<()> {
     ::wasm_opt::OptimizationOptions::new_opt_level_4()
         .shrink_level(::wasm_opt::ShrinkLevel::Level2)
         .debug_info(false)
-        .mvp_features_only()
+        .enable_feature(::wasm_opt::Feature::BulkMemory)
         .set_converge()
         .run(&input, &output)
         .map_err(Into::into)
@@ -0,0 +1,68 @@
+//! Tests for unsupported operators, types and imports.
+
+use zingen::Error as CodegenError;
+use zinkc::{Compiler, Error};
+
+#[test]
+fn unsupported_operator() -> anyhow::Result<()> {
+    let wasm = wat::parse_str(
+        r#"(module
+  (func (export "main") (param i32) (result i32)
+    (i32.trunc_sat_f32_s (f32.reinterpret_i32 (local.get 0)))
+  )
+)"#,
+    )?;
+
+    let err = Compiler::default()
+        .compile(&wasm)
+        .expect_err("non-trapping float-to-int conversions are not supported");
+    let Error::Codegen(CodegenError::UnsupportedOperator(operator, function, offset)) = err else {
+        panic!("unexpected error: {err}");
+    };
+
+    assert_eq!(operator, "I32TruncSatF32S");
+    assert_eq!(function, "main");
+    assert_eq!(wasm[offset], 0xfc);
+    Ok(())
+}
+
+#[test]
+fn unsupported_type() -> anyhow::Result<()> {
+    let wasm = wat::parse_str(
+        r#"(module
+  (func (export "main") (param v128))
+)"#,
+    )?;
+
+    let err = Compiler::default()
+        .compile(&wasm)
+        .expect_err("vector types are not supported");
+    let Error::Codegen(CodegenError::UnsupportedType(ty)) = err else {
+        panic!("unexpected error: {err}");
+    };
+
+    assert_eq!(ty, "v128");
+    Ok(())
+}
+
+#[test]
+fn unsupported_import() -> anyhow::Result<()> {
+    let wasm = wat::parse_str(
+        r#"(module
+  (import "env" "g" (global i32))
+  (global i32 (i32.const 42))
+  (func (export "main") (result i32)
+    (global.get 1))
+)"#,
+    )?;
+
+    let err = Compiler::default()
+        .compile(&wasm)
+        .expect_err("globals could not be imported");
+    let Error::UnsupportedImport(module, name) = err else {
+        panic!("unexpected error: {err}");
+    };
+
+    assert_eq!((module.as_str(), name.as_str()), ("env", "g"));
+    Ok(())
+}
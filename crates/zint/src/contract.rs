@@ -1,10 +1,12 @@
 use crate::utils::{find_up, FoundryConfig};
-use crate::{lookup, Bytes32, Info, EVM};
+use crate::{evm::CONTRACT, lookup, Bytes32, Info, EVM};
 use anyhow::{anyhow, Context, Result};
 use serde::Deserialize;
 use std::fs;
 use std::path::PathBuf;
-use zinkc::{Artifact, Compiler, Config, Constructor, InitStorage};
+use zinkc::{
+    Artifact, Compiler, Config, Constructor, DispatchStrategy, InitStorage, Mapping, Upgrade,
+};
 
 /// Represents the bytecode object in Foundry output
 #[derive(Deserialize)]
@@ -30,6 +32,20 @@ impl FoundryOutput {
 pub struct Contract {
     /// If enable dispatcher.
     pub dispatcher: bool,
+    /// The strategy of the dispatcher.
+    pub dispatch: DispatchStrategy,
+    /// The page budget of the linear memory.
+    pub max_pages: usize,
+    /// The size of the heap in bytes.
+    pub heap_size: usize,
+    /// If emulate float operators in software.
+    pub soft_float: bool,
+    /// If run the peephole optimizer.
+    pub peephole: bool,
+    /// The fork that the contract targets.
+    pub target: Upgrade,
+    /// If compile the contract to an EOF container.
+    pub eof: bool,
     /// The artifact of the contract.
     pub artifact: Artifact,
     /// The source WASM of the contract.
@@ -117,10 +133,13 @@ impl Contract {
 
     /// Get the bytecode of the contract.
     pub fn bytecode(&self) -> Result<Vec<u8>> {
-        let bytecode = self
-            .constructor
-            .finish(self.artifact.runtime_bytecode.clone().into())
-            .map(|v| v.to_vec())?;
+        let runtime_bytecode = self.artifact.runtime_bytecode.clone().into();
+        let bytecode = if self.eof {
+            self.constructor.finish_eof(runtime_bytecode)
+        } else {
+            self.constructor.finish(runtime_bytecode)
+        }
+        .map(|v| v.to_vec())?;
 
         tracing::debug!("runtime bytecode: {}", hex::encode(&bytecode));
         Ok(bytecode)
@@ -135,9 +154,20 @@ impl Contract {
 
     /// Compile WASM to EVM bytecode.
     pub fn compile(mut self) -> Result<Self> {
-        let config = Config::default().dispatcher(self.dispatcher);
+        let config = Config::default()
+            .dispatcher(self.dispatcher)
+            .dispatch(self.dispatch)
+            .max_pages(self.max_pages)
+            .heap_size(self.heap_size)
+            .soft_float(self.soft_float)
+            .peephole(self.peephole)
+            .target(self.target)
+            .eof(self.eof);
         let compiler = Compiler::new(config);
         self.artifact = compiler.compile(&self.wasm)?;
+        if self.eof {
+            EVM::validate_eof(&self.artifact.runtime_bytecode)?;
+        }
 
         // tracing::debug!("abi: {:#}", self.json_abi()?);
         tracing::debug!("bytecode: {}", hex::encode(&self.artifact.runtime_bytecode));
@@ -146,7 +176,7 @@ impl Contract {
 
     /// Deploy self to evm
     pub fn deploy<'e>(&mut self) -> Result<EVM<'e>> {
-        let mut evm = EVM::default();
+        let mut evm = EVM::default().target(self.target).eof(self.eof);
         let info = evm.deploy(&self.bytecode()?)?;
 
         self.address.copy_from_slice(&info.address);
@@ -191,7 +221,20 @@ impl Contract {
     where
         Param: Bytes32,
     {
-        EVM::interp(&self.artifact.runtime_bytecode, &self.encode(inputs)?)
+        EVM::default()
+            .target(self.target)
+            .eof(self.eof)
+            .contract(&self.artifact.runtime_bytecode)
+            .calldata(&self.encode(inputs)?)
+            .call(CONTRACT)
+    }
+
+    /// Get the source of the program counter in the runtime bytecode.
+    ///
+    /// Use with [`Info::pc`] to locate the operator which reverted or
+    /// halted the execution.
+    pub fn source(&self, pc: usize) -> Option<&Mapping> {
+        self.artifact.source_map.lookup(pc)
     }
 
     /// Get the JSON ABI of the contract.
@@ -205,6 +248,49 @@ impl Contract {
         self
     }
 
+    /// Set the strategy of the dispatcher.
+    pub fn dispatch(mut self, dispatch: DispatchStrategy) -> Self {
+        self.dispatch = dispatch;
+        self
+    }
+
+    /// Set the page budget of the linear memory.
+    pub fn max_pages(mut self, max_pages: usize) -> Self {
+        self.max_pages = max_pages;
+        self
+    }
+
+    /// Set the size of the heap in bytes.
+    pub fn heap_size(mut self, heap_size: usize) -> Self {
+        self.heap_size = heap_size;
+        self
+    }
+
+    /// Enable soft float.
+    pub fn soft_float(mut self) -> Self {
+        self.soft_float = true;
+        self
+    }
+
+    /// Enable the peephole optimizer.
+    pub fn peephole(mut self) -> Self {
+        self.peephole = true;
+        self
+    }
+
+    /// Set the fork that the contract targets.
+    pub fn target(mut self, target: Upgrade) -> Self {
+        self.target = target;
+        self.constructor = Constructor::new(target);
+        self
+    }
+
+    /// Compile the contract to an EOF container, targeting prague.
+    pub fn eof(mut self) -> Self {
+        self.eof = true;
+        self.target(Upgrade::Prague)
+    }
+
     /// Search for zink contract in the target directory.
     pub fn search(name: &str) -> Result<Self> {
         // TODO(g4tianx): `Contract::search` to fail properly
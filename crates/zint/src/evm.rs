@@ -3,13 +3,19 @@
 use anyhow::{anyhow, Result};
 use revm::{
     db::EmptyDB,
+    inspector_handle_register,
+    interpreter::{
+        analysis::{validate_raw_eof_inner, CodeType},
+        Interpreter,
+    },
     primitives::{
         AccountInfo, Bytecode, Bytes, ExecutionResult, HaltReason, Log, Output, ResultAndState,
-        SuccessReason, TransactTo, TxKind, U256,
+        SpecId, SuccessReason, TransactTo, TxKind, U256,
     },
-    Database, Evm as Revm, InMemoryDB,
+    Database, Evm as Revm, EvmContext, InMemoryDB, Inspector,
 };
 use std::collections::HashMap;
+use zinkc::Upgrade;
 
 /// Transaction gas limit.
 const GAS_LIMIT: u64 = 1_000_000_000;
@@ -20,9 +26,38 @@ pub const ALICE: [u8; 20] = [0; 20];
 /// Contract address if any.
 pub const CONTRACT: [u8; 20] = [1; 20];
 
+/// Selector of `Error(string)`.
+const ERROR_SELECTOR: [u8; 4] = [0x08, 0xc3, 0x79, 0xa0];
+
+/// Selector of `Panic(uint256)`.
+const PANIC_SELECTOR: [u8; 4] = [0x4e, 0x48, 0x7b, 0x71];
+
+/// Opcode `JUMP`.
+const JUMP: u8 = 0x56;
+
+/// Tracer of the program counters executed by the called contract.
+#[derive(Default)]
+struct Trace {
+    /// Program counter of the last executed instruction.
+    pc: Option<usize>,
+    /// Program counter of the last executed `JUMP`.
+    jump: Option<usize>,
+}
+
+impl<DB: Database> Inspector<DB> for Trace {
+    fn step(&mut self, interp: &mut Interpreter, context: &mut EvmContext<DB>) {
+        if context.journaled_state.depth() == 1 {
+            self.pc = Some(interp.program_counter());
+            if interp.current_opcode() == JUMP {
+                self.jump = self.pc;
+            }
+        }
+    }
+}
+
 /// Wrapper of full REVM
 pub struct EVM<'e> {
-    inner: Revm<'e, (), InMemoryDB>,
+    inner: Revm<'e, Trace, InMemoryDB>,
     /// Caller for the execution
     pub caller: [u8; 20],
     /// If commit changes
@@ -34,7 +69,12 @@ impl<'e> Default for EVM<'e> {
         let mut db = InMemoryDB::default();
         db.insert_account_info(ALICE.into(), AccountInfo::from_balance(U256::MAX));
 
-        let evm = Revm::<'e, (), EmptyDB>::builder().with_db(db).build();
+        let evm = Revm::<'e, (), EmptyDB>::builder()
+            .with_db(db)
+            .with_external_context(Trace::default())
+            .append_handler_register(inspector_handle_register)
+            .with_spec_id(spec_id(Upgrade::default()))
+            .build();
         Self {
             inner: evm,
             caller: [0; 20],
@@ -66,6 +106,32 @@ impl EVM<'_> {
         self
     }
 
+    /// Set the fork that the execution runs on.
+    pub fn target(mut self, target: Upgrade) -> Self {
+        self.inner.modify_spec_id(spec_id(target));
+        self
+    }
+
+    /// Run on prague with EOF enabled.
+    pub fn eof(mut self, eof: bool) -> Self {
+        if eof {
+            self.inner.modify_spec_id(SpecId::PRAGUE_EOF);
+        }
+
+        self
+    }
+
+    /// Validate a runtime EOF container with the validator of revm.
+    pub fn validate_eof(container: &[u8]) -> Result<()> {
+        validate_raw_eof_inner(
+            Bytes::copy_from_slice(container),
+            Some(CodeType::ReturnOrStop),
+        )
+        .map_err(|e| anyhow!("Invalid EOF container: {e:?}"))?;
+
+        Ok(())
+    }
+
     /// Set caller for the execution
     pub fn caller(mut self, caller: [u8; 20]) -> Self {
         self.caller = caller;
@@ -78,20 +144,30 @@ impl EVM<'_> {
         self.inner.tx_mut().gas_limit = GAS_LIMIT;
         self.inner.tx_mut().transact_to = to;
         self.inner.tx_mut().caller = self.caller.into();
+        self.inner.context.external = Trace::default();
 
-        if self.commit {
-            self.inner.transact_commit()?.try_into()
+        let mut info: Info = if self.commit {
+            self.inner.transact_commit()?.try_into()?
         } else {
             let result = self.inner.transact().map_err(|e| anyhow!(e))?;
-            (result, to).try_into()
-        }
+            (result, to).try_into()?
+        };
+
+        info.pc = self.inner.context.external.pc;
+        info.jump = self.inner.context.external.jump;
+        Ok(info)
     }
 
     /// Interpret runtime bytecode with provided arguments
     pub fn deploy(&mut self, bytecode: &[u8]) -> Result<Info> {
         self.calldata(bytecode);
         self.inner.tx_mut().transact_to = TxKind::Create;
-        self.inner.transact_commit()?.try_into()
+        self.inner.context.external = Trace::default();
+
+        let mut info: Info = self.inner.transact_commit()?.try_into()?;
+        info.pc = self.inner.context.external.pc;
+        info.jump = self.inner.context.external.jump;
+        Ok(info)
     }
 
     /// Fill the calldata of the present transaction.
@@ -120,6 +196,22 @@ impl EVM<'_> {
     }
 }
 
+/// Get the spec id of the provided fork.
+fn spec_id(target: Upgrade) -> SpecId {
+    match target {
+        Upgrade::Frontier => SpecId::FRONTIER,
+        Upgrade::Byzantium => SpecId::BYZANTIUM,
+        Upgrade::Constantinople => SpecId::CONSTANTINOPLE,
+        Upgrade::Istanbul => SpecId::ISTANBUL,
+        Upgrade::Berlin => SpecId::BERLIN,
+        Upgrade::London => SpecId::LONDON,
+        Upgrade::Paris => SpecId::MERGE,
+        Upgrade::Shanghai => SpecId::SHANGHAI,
+        Upgrade::Cancun => SpecId::CANCUN,
+        Upgrade::Prague => SpecId::PRAGUE,
+    }
+}
+
 /// Interp execution result info.
 #[derive(Debug, Default)]
 pub struct Info {
@@ -127,7 +219,7 @@ pub struct Info {
     pub address: [u8; 20],
     /// Gas spent.
     pub gas: u64,
-    /// Return value.
+    /// Return value, or the output of the revert.
     pub ret: Vec<u8>,
     /// The storage.
     pub storage: HashMap<U256, U256>,
@@ -135,8 +227,17 @@ pub struct Info {
     pub logs: Vec<Log>,
     /// Transaction halt reason.
     pub halt: Option<HaltReason>,
-    /// The revert message.
+    /// The revert message, decoded from `Error(string)`, `Panic(0x..)`
+    /// for panics, or the raw message.
     pub revert: Option<String>,
+    /// The code of `Panic(uint256)` if the transaction panicked.
+    pub panic: Option<u64>,
+    /// Program counter of the last instruction executed by the contract,
+    /// i.e. the one reverted or halted the transaction.
+    pub pc: Option<usize>,
+    /// Program counter of the last `JUMP` executed by the contract, i.e.
+    /// the call site of the shared panic in the code section.
+    pub jump: Option<usize>,
 }
 
 impl TryFrom<ExecutionResult> for Info {
@@ -155,7 +256,10 @@ impl TryFrom<ExecutionResult> for Info {
                 output,
                 ..
             } => {
-                if reason != SuccessReason::Return {
+                if !matches!(
+                    reason,
+                    SuccessReason::Return | SuccessReason::EofReturnContract
+                ) {
                     return Err(anyhow!("Transaction is not returned: {reason:?}"));
                 }
                 info.logs = logs;
@@ -181,11 +285,15 @@ impl TryFrom<ExecutionResult> for Info {
             }
             ExecutionResult::Revert { gas_used, output } => {
                 info.gas = gas_used;
-                info.revert = Some(
-                    String::from_utf8_lossy(&output)
+                info.ret = output.to_vec();
+                info.panic = decode_panic(&output);
+                info.revert = Some(match (decode_error(&output), info.panic) {
+                    (Some(message), _) => message,
+                    (_, Some(code)) => format!("Panic(0x{code:02x})"),
+                    _ => String::from_utf8_lossy(&output)
                         .trim_start_matches("\0")
                         .to_string(),
-                );
+                });
             }
         }
 
@@ -193,6 +301,31 @@ impl TryFrom<ExecutionResult> for Info {
     }
 }
 
+/// Decode the message of `Error(string)`.
+fn decode_error(output: &[u8]) -> Option<String> {
+    let data = output.strip_prefix(&ERROR_SELECTOR)?;
+    let word = |offset: usize| -> Option<usize> {
+        let word = data.get(offset..offset.checked_add(32)?)?;
+        usize::try_from(U256::from_be_slice(word)).ok()
+    };
+
+    let offset = word(0)?;
+    let len = word(offset)?;
+    let start = offset.checked_add(32)?;
+    let message = data.get(start..start.checked_add(len)?)?;
+    Some(String::from_utf8_lossy(message).to_string())
+}
+
+/// Decode the code of `Panic(uint256)`.
+fn decode_panic(output: &[u8]) -> Option<u64> {
+    let data = output.strip_prefix(&PANIC_SELECTOR)?;
+    if data.len() != 32 {
+        return None;
+    }
+
+    u64::try_from(U256::from_be_slice(data)).ok()
+}
+
 impl TryFrom<(ResultAndState, TransactTo)> for Info {
     type Error = anyhow::Error;
 
@@ -19,6 +19,7 @@ pub use hex;
 pub use revm::primitives::{HaltReason, OutOfGasError, U256};
 pub use tracing as log;
 pub use zabi::selector::keccak256;
+pub use zinkc::DispatchStrategy;
 
 /// Set up the logger.
 pub fn setup_logger() {
@@ -42,14 +42,25 @@ impl From<&syn::Signature> for Abi {
             })
             .collect();
 
-        let outputs = if let syn::ReturnType::Type(_, ty) = &sig.output {
-            vec![Arg {
-                // TODO: how to name the output?
-                name: "output".into(),
-                ty: crate::Param::from(ty),
-            }]
-        } else {
-            vec![]
+        // tuples are returned as multiple outputs.
+        let outputs = match &sig.output {
+            syn::ReturnType::Type(_, ty) => match ty.as_ref() {
+                syn::Type::Tuple(tuple) => tuple
+                    .elems
+                    .iter()
+                    .enumerate()
+                    .map(|(index, ty)| Arg {
+                        name: format!("output{index}"),
+                        ty: crate::Param::from(ty),
+                    })
+                    .collect(),
+                ty => vec![Arg {
+                    // TODO: how to name the output?
+                    name: "output".into(),
+                    ty: crate::Param::from(ty),
+                }],
+            },
+            syn::ReturnType::Default => vec![],
         };
 
         let name = sig.ident.to_string();
@@ -62,6 +73,32 @@ impl From<&syn::Signature> for Abi {
     }
 }
 
+#[cfg(feature = "syn")]
+impl From<&syn::Variant> for Abi {
+    fn from(variant: &syn::Variant) -> Self {
+        let inputs = variant
+            .fields
+            .iter()
+            .enumerate()
+            .map(|(index, field)| Arg {
+                name: field
+                    .ident
+                    .as_ref()
+                    .map(ToString::to_string)
+                    .unwrap_or_else(|| format!("param_{index}")),
+                ty: crate::Param::from(&field.ty),
+            })
+            .collect();
+
+        Abi {
+            name: variant.ident.to_string(),
+            ty: Type::Error,
+            inputs,
+            outputs: vec![],
+        }
+    }
+}
+
 /// Solidity ABI type.
 #[derive(Clone, Debug, Default)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
@@ -72,12 +109,15 @@ pub enum Type {
     /// Function ABI.
     #[default]
     Function,
+    /// Custom error ABI.
+    Error,
 }
 
 impl From<&str> for Type {
     fn from(s: &str) -> Self {
         match s {
             "constructor" => Type::Constructor,
+            "error" => Type::Error,
             _ => Type::Function,
         }
     }
@@ -96,6 +136,7 @@ impl AsRef<str> for Type {
         match self {
             Type::Constructor => "constructor",
             Type::Function => "function",
+            Type::Error => "error",
         }
     }
 }
@@ -112,11 +112,18 @@ impl fmt::Display for Param {
 }
 
 #[cfg(feature = "syn")]
-impl From<&Box<syn::Type>> for Param {
-    fn from(ty: &Box<syn::Type>) -> Self {
+impl From<&syn::Type> for Param {
+    fn from(ty: &syn::Type) -> Self {
         use quote::ToTokens;
 
         let ident = ty.into_token_stream().to_string();
         Self::from(ident.as_str())
     }
 }
+
+#[cfg(feature = "syn")]
+impl From<&Box<syn::Type>> for Param {
+    fn from(ty: &Box<syn::Type>) -> Self {
+        Self::from(ty.as_ref())
+    }
+}
@@ -1,10 +1,14 @@
 //! Ethereum virtual machine opcode
 #![deny(missing_docs)]
 
+use core::{fmt, str::FromStr};
+
 mod cancun;
+mod prague;
 mod shanghai;
 
 pub use cancun::Cancun;
+pub use prague::Prague;
 pub use shanghai::ShangHai;
 
 /// Ethereum virtual machine opcode generator.
@@ -171,7 +175,12 @@ pub enum Group {
 }
 
 /// Ethereum upgrades.
-#[derive(Clone, Copy, Debug, PartialOrd, Ord, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, Default, PartialOrd, Ord, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "lowercase")
+)]
 pub enum Upgrade {
     /// Frontier
     Frontier,
@@ -185,10 +194,64 @@ pub enum Upgrade {
     Berlin,
     /// London
     London,
+    /// Paris, the merge
+    Paris,
     /// Shanghai
     Shanghai,
     /// Cancun
+    #[default]
     Cancun,
+    /// Prague
+    Prague,
+}
+
+impl Upgrade {
+    /// All of the upgrades in order.
+    pub const ALL: [Upgrade; 10] = [
+        Upgrade::Frontier,
+        Upgrade::Byzantium,
+        Upgrade::Constantinople,
+        Upgrade::Istanbul,
+        Upgrade::Berlin,
+        Upgrade::London,
+        Upgrade::Paris,
+        Upgrade::Shanghai,
+        Upgrade::Cancun,
+        Upgrade::Prague,
+    ];
+
+    /// The lowercase name of the upgrade.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Upgrade::Frontier => "frontier",
+            Upgrade::Byzantium => "byzantium",
+            Upgrade::Constantinople => "constantinople",
+            Upgrade::Istanbul => "istanbul",
+            Upgrade::Berlin => "berlin",
+            Upgrade::London => "london",
+            Upgrade::Paris => "paris",
+            Upgrade::Shanghai => "shanghai",
+            Upgrade::Cancun => "cancun",
+            Upgrade::Prague => "prague",
+        }
+    }
+}
+
+impl fmt::Display for Upgrade {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for Upgrade {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Upgrade::ALL
+            .into_iter()
+            .find(|upgrade| upgrade.name().eq_ignore_ascii_case(s))
+            .ok_or_else(|| format!("unknown upgrade {s}"))
+    }
 }
 
 /// Ethereum virtual machine opcode.
@@ -26,3 +26,18 @@ fn test() -> anyhow::Result<()> {
     assert_eq!(info.ret, 3u64.to_bytes32());
     Ok(())
 }
+
+#[test]
+fn eof() -> anyhow::Result<()> {
+    use zint::{Bytes32, Contract};
+    let mut contract = Contract::search("addition")?.eof().compile()?;
+
+    let info = contract.execute([
+        "addition(uint64,uint64)".as_bytes(),
+        &1u64.to_bytes32(),
+        &2u64.to_bytes32(),
+    ])?;
+
+    assert_eq!(info.ret, 3u64.to_bytes32());
+    Ok(())
+}
@@ -0,0 +1,48 @@
+//! Example of custom errors
+#![cfg_attr(target_arch = "wasm32", no_std)]
+#![cfg_attr(target_arch = "wasm32", no_main)]
+
+extern crate zink;
+
+use zink::primitives::{Address, U256};
+
+#[cfg(not(target_arch = "wasm32"))]
+fn main() {}
+
+/// Errors of the contract
+#[derive(zink::Error)]
+pub enum Errors {
+    Insufficient { balance: U256, needed: U256 },
+    Unauthorized(Address),
+}
+
+/// revert with `Insufficient(uint256,uint256)`
+#[zink::external]
+pub fn withdraw(amount: U256) {
+    zink::revert_with(Errors::Insufficient {
+        balance: U256::empty(),
+        needed: amount,
+    })
+}
+
+/// revert with `Unauthorized(address)`
+#[zink::external]
+pub fn unauthorized() {
+    zink::revert_with(Errors::Unauthorized(Address::caller()))
+}
+
+#[test]
+fn test_error() -> anyhow::Result<()> {
+    use zint::{Bytes32, Contract};
+    let mut contract = Contract::search("error")?.compile()?;
+
+    let info = contract.execute(["withdraw(uint256)".as_bytes(), &42.to_bytes32()])?;
+    let mut output = zink::keccak256(b"Insufficient(uint256,uint256)")[..4].to_vec();
+    output.extend_from_slice(&[0; 32]);
+    output.extend_from_slice(&42.to_bytes32());
+    assert_eq!(info.ret, output);
+
+    let abi = contract.json_abi()?;
+    assert!(abi.contains(r#""type": "error""#));
+    Ok(())
+}
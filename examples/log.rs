@@ -70,20 +70,14 @@ mod tests {
             // Test log0
             let info = contract.execute(&[b"test_log0()".to_vec()]).unwrap();
             assert!(!info.logs.is_empty());
-            assert_eq!(
-                info.logs[0].data.data.to_vec(),
-                name.to_vec().to_bytes32().to_vec()
-            );
+            assert_eq!(info.logs[0].data.data.to_vec(), name.to_vec());
 
             // Test log1
             let info = contract
                 .execute(&[b"test_log1(uint256)".to_vec(), value1.bytes32().to_vec()])
                 .expect("failed to execute test_log1");
             assert!(!info.logs.is_empty());
-            assert_eq!(
-                info.logs[0].data.data.to_vec(),
-                name.to_vec().to_bytes32().to_vec()
-            );
+            assert_eq!(info.logs[0].data.data.to_vec(), name.to_vec());
             assert_eq!(info.logs[0].topics()[0].to_vec(), value1.bytes32().to_vec());
 
             // Test log2
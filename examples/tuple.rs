@@ -0,0 +1,28 @@
+//! Tuple example.
+#![cfg_attr(target_arch = "wasm32", no_std)]
+#![cfg_attr(target_arch = "wasm32", no_main)]
+extern crate zink;
+
+use zink::primitives::U256;
+
+/// Returns the value and if it is zero.
+#[zink::external]
+pub fn is_zero(value: U256) -> (U256, bool) {
+    (value, value.eq(U256::empty()))
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn main() {}
+
+#[test]
+fn test() -> anyhow::Result<()> {
+    use zint::{Bytes32, Contract};
+    let mut contract = Contract::search("tuple")?.compile()?;
+
+    let info = contract.execute(["is_zero(uint256)".as_bytes(), &42u64.to_bytes32()])?;
+    assert_eq!(info.ret, [42u64.to_bytes32(), false.to_bytes32()].concat());
+
+    let info = contract.execute(["is_zero(uint256)".as_bytes(), &0u64.to_bytes32()])?;
+    assert_eq!(info.ret, [0u64.to_bytes32(), true.to_bytes32()].concat());
+    Ok(())
+}
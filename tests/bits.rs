@@ -0,0 +1,90 @@
+//! Bit counting and rotation tests for the zink compiler.
+#![cfg(test)]
+
+use anyhow::Result;
+use filetests::Test;
+use zint::{Bytes32, Contract};
+
+#[test]
+fn clz() -> Result<()> {
+    let mut contract = Contract::from(Test::BITS_CLZ).pure().compile()?;
+    for (input, clz) in [(vec![1], 31), (vec![0], 32), (vec![0x80, 0, 0, 0], 0)] {
+        let info = contract.execute([input])?;
+        assert_eq!(info.ret, clz.to_bytes32());
+    }
+
+    let mut contract = Contract::from(Test::BITS_CLZ64).pure().compile()?;
+    for (input, clz) in [(vec![1], 63), (vec![0], 64), (vec![1, 0, 0, 0, 0], 31)] {
+        let info = contract.execute([input])?;
+        assert_eq!(info.ret, clz.to_bytes32());
+    }
+    Ok(())
+}
+
+#[test]
+fn ctz() -> Result<()> {
+    let mut contract = Contract::from(Test::BITS_CTZ).pure().compile()?;
+    for (input, ctz) in [(vec![8], 3), (vec![0], 32), (vec![0x80, 0, 0, 0], 31)] {
+        let info = contract.execute([input])?;
+        assert_eq!(info.ret, ctz.to_bytes32());
+    }
+
+    let mut contract = Contract::from(Test::BITS_CTZ64).pure().compile()?;
+    for (input, ctz) in [(vec![0], 64), (vec![1, 0, 0, 0, 0, 0], 40)] {
+        let info = contract.execute([input])?;
+        assert_eq!(info.ret, ctz.to_bytes32());
+    }
+    Ok(())
+}
+
+#[test]
+fn popcnt() -> Result<()> {
+    let mut contract = Contract::from(Test::BITS_POPCNT).pure().compile()?;
+    for (input, popcnt) in [(vec![0xff; 4], 32), (vec![0], 0), (vec![0x10, 0x01], 2)] {
+        let info = contract.execute([input])?;
+        assert_eq!(info.ret, popcnt.to_bytes32());
+    }
+
+    let mut contract = Contract::from(Test::BITS_POPCNT64).pure().compile()?;
+    for (input, popcnt) in [(vec![0xff; 8], 64), (vec![0x0f; 8], 32)] {
+        let info = contract.execute([input])?;
+        assert_eq!(info.ret, popcnt.to_bytes32());
+    }
+    Ok(())
+}
+
+#[test]
+fn rotl() -> Result<()> {
+    let mut contract = Contract::from(Test::BITS_ROTL).pure().compile()?;
+    for (count, rotl) in [(1, vec![3]), (33, vec![3]), (0, vec![0x80, 0, 0, 1])] {
+        let info = contract.execute([vec![0x80, 0, 0, 1], vec![count]])?;
+        assert_eq!(info.ret, rotl.to_bytes32());
+    }
+    Ok(())
+}
+
+#[test]
+fn rotr() -> Result<()> {
+    let mut contract = Contract::from(Test::BITS_ROTR64).pure().compile()?;
+    for (count, rotr) in [(1, vec![0x80, 0, 0, 0, 0, 0, 0, 0]), (64, vec![1])] {
+        let info = contract.execute([vec![1], vec![count]])?;
+        assert_eq!(info.ret, rotr.to_bytes32());
+    }
+    Ok(())
+}
+
+#[test]
+fn shared() -> Result<()> {
+    let mut contract = Contract::from(Test::BITS_SHARED).pure().compile()?;
+    let info = contract.execute([vec![0xff; 4], vec![0xff; 8]])?;
+    assert_eq!(info.ret, 96.to_bytes32());
+
+    // the function is emitted once in the code section
+    let popcnt = contract
+        .artifact
+        .runtime_bytecode
+        .windows(8)
+        .filter(|w| *w == [0x55; 8]);
+    assert_eq!(popcnt.count(), 1);
+    Ok(())
+}
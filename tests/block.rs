@@ -0,0 +1,19 @@
+//! block tests for the zink compiler.
+#![cfg(test)]
+
+use anyhow::Result;
+use filetests::Test;
+use zint::{Bytes32, Contract};
+
+#[test]
+fn params() -> Result<()> {
+    let mut contract = Contract::from(Test::BLOCK_PARAMS).pure().compile()?;
+
+    // (a + b + 1) if b is not zero, otherwise (a + 100) * 2.
+    for (a, b, expected) in [(1, 2, 4), (5, 0, 210), (0, 7, 8)] {
+        let info = contract.execute([a, b])?;
+        assert_eq!(info.ret, expected.to_bytes32(), "{a}, {b}");
+    }
+
+    Ok(())
+}
@@ -0,0 +1,51 @@
+//! br_table tests for the zink compiler.
+
+use anyhow::Result;
+use filetests::Test;
+use zint::{Bytes32, Contract};
+
+#[test]
+fn dense() -> Result<()> {
+    let mut contract = Contract::from(Test::BR_TABLE_DENSE).pure().compile()?;
+
+    for (index, ret) in [10, 11, 12, 13, 14, 15, 16, 16, 16].into_iter().enumerate() {
+        let info = contract.execute([index])?;
+        assert_eq!(info.ret, ret.to_bytes32(), "index {index}");
+    }
+
+    Ok(())
+}
+
+#[test]
+fn sparse() -> Result<()> {
+    let mut contract = Contract::from(Test::BR_TABLE_SPARSE).pure().compile()?;
+
+    let expected = [10, 10, 10, 10, 11, 11, 11, 11, 11, 11, 11, 11, 10, 12, 12];
+    for (index, ret) in expected.into_iter().enumerate() {
+        let info = contract.execute([index])?;
+        assert_eq!(info.ret, ret.to_bytes32(), "index {index}");
+    }
+
+    Ok(())
+}
+
+#[test]
+fn stack() -> Result<()> {
+    let mut contract = Contract::from(Test::BR_TABLE_STACK).pure().compile()?;
+
+    for (index, ret) in [7, 107, 7, 7].into_iter().enumerate() {
+        let info = contract.execute([index])?;
+        assert_eq!(info.ret, ret.to_bytes32(), "index {index}");
+    }
+
+    Ok(())
+}
+
+#[test]
+fn as_loop() -> Result<()> {
+    let mut contract = Contract::from(Test::BR_TABLE_AS_LOOP).pure().compile()?;
+
+    let info = contract.execute([3])?;
+    assert_eq!(info.ret, 6.to_bytes32());
+    Ok(())
+}
@@ -40,3 +40,47 @@ fn as_if() -> Result<()> {
     assert_eq!(info.ret, 42.to_bytes32());
     Ok(())
 }
+
+#[test]
+fn indirect() -> Result<()> {
+    let mut contract = Contract::from(Test::CALL_INDIRECT).pure().compile()?;
+
+    let info = contract.execute([0, 5, 3])?;
+    assert_eq!(info.ret, 8.to_bytes32());
+
+    let info = contract.execute([1, 5, 3])?;
+    assert_eq!(info.ret, 2.to_bytes32());
+
+    // type mismatch, null slot and out-of-bounds index
+    for index in [2, 3, 7] {
+        let info = contract.execute([index, 5, 3])?;
+        assert!(info.revert.is_some());
+    }
+    Ok(())
+}
+
+#[test]
+fn indirect_recursion() -> Result<()> {
+    let mut contract = Contract::from(Test::CALL_INDIRECT_RECURSION)
+        .pure()
+        .compile()?;
+
+    for (input, expected) in [(0, 1), (1, 1), (5, 120)] {
+        let info = contract.execute([input])?;
+        assert_eq!(info.ret, expected.to_bytes32(), "{input}");
+    }
+    Ok(())
+}
+
+#[test]
+fn early_return() -> Result<()> {
+    let mut contract = Contract::from(Test::CALL_EARLY_RETURN).pure().compile()?;
+
+    // the values of the enclosing frames are dropped by the return.
+    for (input, expected) in [(0, 10), (1, 1), (42, 42)] {
+        let info = contract.execute([input])?;
+        assert_eq!(info.ret, expected.to_bytes32(), "{input}");
+    }
+
+    Ok(())
+}
@@ -0,0 +1,38 @@
+//! Source map tests for the zink compiler.
+#![cfg(test)]
+
+use anyhow::Result;
+use filetests::Test;
+use zint::{Bytes32, Contract};
+
+#[test]
+fn trap() -> Result<()> {
+    let mut contract = Contract::from(Test::DEBUG_TRAP).pure().compile()?;
+
+    let info = contract.execute([1])?;
+    assert_eq!(info.ret, 1.to_bytes32());
+
+    let info = contract.execute([0])?;
+    assert_eq!(info.panic, Some(0x01));
+
+    // the panic is shared in the code section, located by its call site.
+    let pc = info.jump.expect("jumped to the panic");
+    let mapping = contract.source(pc).expect("source of the trap").clone();
+    assert_eq!(mapping.func, 1);
+    assert_eq!(mapping.name.as_deref(), Some("check"));
+    assert_eq!(mapping.file.as_deref(), Some("src/lib.rs"));
+    assert_eq!(mapping.line, Some(42));
+
+    // the trap is the `unreachable` operator, jumping to `Panic(0x01)`
+    assert_eq!(contract.wasm[mapping.offset], 0x00);
+    assert_eq!(contract.artifact.runtime_bytecode[pc], 0x56);
+    let revert = info.pc.expect("reverted in the contract");
+    assert_eq!(contract.artifact.runtime_bytecode[revert], 0xfd);
+    assert!(contract.source(revert).is_none());
+
+    // every program counter of the functions has a source
+    let map = &contract.artifact.source_map;
+    assert!(map.0.windows(2).all(|m| m[0].end <= m[1].start));
+    assert_eq!(map.lookup(0).map(|m| m.name.as_deref()), Some(Some("main")));
+    Ok(())
+}
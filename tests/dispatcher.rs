@@ -0,0 +1,73 @@
+//! dispatcher tests for the zink compiler.
+#![cfg(test)]
+
+use anyhow::Result;
+use filetests::Test;
+use zint::{Bytes32, Contract, DispatchStrategy};
+
+#[test]
+fn arithmetic() -> Result<()> {
+    for eof in [false, true] {
+        let mut contract = Contract::from(Test::DISPATCHER_ARITHMETIC);
+        if eof {
+            contract = contract.eof();
+        }
+
+        let mut contract = contract.compile()?;
+        let info = contract.execute([
+            "add(uint32,uint32)".as_bytes(),
+            &1u32.to_bytes32(),
+            &2u32.to_bytes32(),
+        ])?;
+        assert_eq!(info.ret, 3u32.to_bytes32(), "eof {eof}");
+
+        let info = contract.execute([
+            "sub(uint32,uint32)".as_bytes(),
+            &5u32.to_bytes32(),
+            &2u32.to_bytes32(),
+        ])?;
+        assert_eq!(info.ret, 3u32.to_bytes32(), "eof {eof}");
+
+        // unknown selectors fall through to `INVALID` in EOF.
+        if eof {
+            let info = contract.execute(["mul(uint32,uint32)".as_bytes()])?;
+            assert!(info.halt.is_some());
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn binary_search() -> Result<()> {
+    let mut gas = Vec::new();
+    for (strategy, eof) in [
+        (DispatchStrategy::Linear, false),
+        (DispatchStrategy::BinarySearch, false),
+        (DispatchStrategy::BinarySearch, true),
+    ] {
+        let mut contract = Contract::from(Test::DISPATCHER_SEARCH).dispatch(strategy);
+        if eof {
+            contract = contract.eof();
+        }
+
+        let mut contract = contract.compile()?;
+        let mut max = 0;
+        for index in 0..10 {
+            let info = contract.execute([format!("f{index}()").as_bytes()])?;
+            assert_eq!(info.ret, index.to_bytes32(), "{strategy} eof {eof}");
+            max = max.max(info.gas);
+        }
+        gas.push(max);
+
+        // unknown selectors revert in the binary search.
+        if strategy == DispatchStrategy::BinarySearch {
+            let info = contract.execute(["f10()".as_bytes()])?;
+            assert_eq!(info.revert, Some(String::new()), "eof {eof}");
+        }
+    }
+
+    // the gas of the worst case is lower than the linear one.
+    assert!(gas[1] < gas[0], "{gas:?}");
+    Ok(())
+}
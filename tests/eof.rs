@@ -0,0 +1,119 @@
+//! EOF tests for the zink compiler.
+#![cfg(test)]
+
+use anyhow::Result;
+use filetests::Test;
+use opcodes::Upgrade;
+use zint::{Bytes32, Contract, U256};
+
+#[test]
+fn magic() -> Result<()> {
+    let contract = Contract::from(Test::CALL_DUMMY).pure().eof().compile()?;
+    assert_eq!(contract.artifact.runtime_bytecode[..3], [0xef, 0x00, 0x01]);
+
+    Ok(())
+}
+
+#[test]
+fn call() -> Result<()> {
+    let mut contract = Contract::from(Test::CALL_PARAMS).pure().eof().compile()?;
+    let info = contract.execute([1, 2])?;
+    assert_eq!(info.ret, 3.to_bytes32());
+
+    let mut contract = Contract::from(Test::CALL_AS_IF).pure().eof().compile()?;
+    for (input, ret) in [(0, 0), (1, 1), (2, 41), (3, 42)] {
+        let info = contract.execute([input])?;
+        assert_eq!(info.ret, ret.to_bytes32(), "input {input}");
+    }
+
+    // indirect calls compare the index against the slots.
+    let mut contract = Contract::from(Test::CALL_INDIRECT).pure().eof().compile()?;
+    let info = contract.execute([1, 5, 3])?;
+    assert_eq!(info.ret, 2.to_bytes32());
+
+    Ok(())
+}
+
+#[test]
+fn recursion() -> Result<()> {
+    let mut contract = Contract::from(Test::RECURSION_FIBONACCI)
+        .pure()
+        .eof()
+        .compile()?;
+
+    for (input, ret) in [(0, 0), (1, 1), (2, 1), (3, 2), (4, 3), (5, 5)] {
+        let info = contract.execute([input])?;
+        assert_eq!(info.ret, ret.to_bytes32(), "input {input}");
+    }
+
+    Ok(())
+}
+
+#[test]
+fn control() -> Result<()> {
+    let mut contract = Contract::from(Test::SELECT_PARAMS).pure().eof().compile()?;
+    let info = contract.execute([1, 2])?;
+    assert_eq!(info.ret, 2.to_bytes32());
+
+    let mut contract = Contract::from(Test::LOOP_SINGULAR).pure().eof().compile()?;
+    let info = contract.execute::<()>([])?;
+    assert_eq!(info.ret, 7.to_bytes32());
+
+    let mut contract = Contract::from(Test::BR_TABLE_DENSE)
+        .pure()
+        .eof()
+        .compile()?;
+    for (index, ret) in [10, 11, 12, 13, 14, 15, 16, 16, 16].into_iter().enumerate() {
+        let info = contract.execute([index])?;
+        assert_eq!(info.ret, ret.to_bytes32(), "index {index}");
+    }
+
+    Ok(())
+}
+
+#[test]
+fn ext() -> Result<()> {
+    let mut contract = Contract::from(Test::BITS_SHARED).pure().eof().compile()?;
+    let info = contract.execute([vec![0xff; 4], vec![0xff; 8]])?;
+    assert_eq!(info.ret, 96.to_bytes32());
+
+    // the shared panic never returns.
+    let mut contract = Contract::from(Test::UNREACHABLE_TRAP)
+        .pure()
+        .eof()
+        .compile()?;
+    let info = contract.execute([0])?;
+    assert_eq!(info.panic, Some(0x01));
+
+    Ok(())
+}
+
+#[test]
+fn deploy() -> Result<()> {
+    let mut contract = Contract::from(Test::STORAGE_LOAD).pure().eof().compile()?;
+    let mut evm = contract.deploy()?;
+    let info = evm
+        .calldata(&contract.encode([42])?)
+        .call(contract.address)?;
+    assert_eq!(info.ret, 42.to_bytes32());
+
+    let mut contract = Contract::from(Test::STORAGE_STORE).pure().eof().compile()?;
+    let info = contract.execute([42])?;
+    assert_eq!(info.storage.get(&U256::from(0)), Some(&U256::from(42)));
+
+    Ok(())
+}
+
+#[test]
+fn unavailable() -> Result<()> {
+    let error = Contract::from(Test::CALL_PARAMS)
+        .pure()
+        .eof()
+        .target(Upgrade::Cancun)
+        .compile()
+        .err()
+        .expect("EOF is not available on cancun");
+    assert!(error.to_string().contains("EOF"));
+
+    Ok(())
+}
@@ -0,0 +1,323 @@
+//! Soft float tests for the zink compiler.
+#![cfg(test)]
+
+use anyhow::Result;
+use filetests::Test;
+use zint::{Bytes32, Contract, HaltReason};
+
+const NAN32: u32 = 0x7fc0_0000;
+const NAN64: u64 = 0x7ff8_0000_0000_0000;
+
+/// Special values, edge cases of rounding and pseudo random values.
+fn values() -> Vec<f64> {
+    let mut values = vec![
+        0.0,
+        -0.0,
+        1.0,
+        -1.0,
+        0.5,
+        -0.5,
+        1.5,
+        2.5,
+        -2.5,
+        0.1,
+        3.0,
+        1e300,
+        -1e-300,
+        f64::MAX,
+        f64::MIN_POSITIVE,
+        f64::from_bits(1),
+        f64::from_bits(0x000f_ffff_ffff_ffff),
+        4503599627370497.0,
+        f64::INFINITY,
+        f64::NEG_INFINITY,
+        f64::NAN,
+    ];
+
+    let mut seed = 0x2545_f491_4f6c_dd1du64;
+    for index in 0..16 {
+        seed ^= seed << 13;
+        seed ^= seed >> 7;
+        seed ^= seed << 17;
+
+        // moderate exponents for the half of them
+        let bits = if index % 2 == 0 {
+            (seed & 0x800f_ffff_ffff_ffff) | ((990 + seed % 64) << 52)
+        } else {
+            seed
+        };
+        values.push(f64::from_bits(bits));
+    }
+
+    values
+}
+
+fn bits64(value: f64) -> Vec<u8> {
+    value.to_bits().to_be_bytes().to_vec()
+}
+
+fn bits32(value: f32) -> Vec<u8> {
+    value.to_bits().to_be_bytes().to_vec()
+}
+
+fn canonical64(value: f64) -> Vec<u8> {
+    if value.is_nan() {
+        NAN64.to_be_bytes().to_vec()
+    } else {
+        bits64(value)
+    }
+}
+
+fn canonical32(value: f32) -> Vec<u8> {
+    if value.is_nan() {
+        NAN32.to_be_bytes().to_vec()
+    } else {
+        bits32(value)
+    }
+}
+
+fn binary(test: impl AsRef<[u8]>, op: impl Fn(f64, f64) -> Vec<u8>) -> Result<()> {
+    let mut contract = Contract::from(test).pure().soft_float().compile()?;
+    for a in values() {
+        for b in values() {
+            let info = contract.execute([bits64(a), bits64(b)])?;
+            assert_eq!(info.ret, op(a, b).to_bytes32(), "{a:?}, {b:?}");
+        }
+    }
+
+    Ok(())
+}
+
+fn binary32(test: impl AsRef<[u8]>, op: impl Fn(f32, f32) -> f32) -> Result<()> {
+    let mut contract = Contract::from(test).pure().soft_float().compile()?;
+    let values: Vec<f32> = values().into_iter().map(|v| v as f32).collect();
+    for a in values.iter().copied() {
+        for b in values.iter().copied() {
+            let info = contract.execute([bits32(a), bits32(b)])?;
+            assert_eq!(info.ret, canonical32(op(a, b)).to_bytes32(), "{a:?}, {b:?}");
+        }
+    }
+
+    Ok(())
+}
+
+fn unary(test: impl AsRef<[u8]>, op: impl Fn(f64) -> f64) -> Result<()> {
+    let mut contract = Contract::from(test).pure().soft_float().compile()?;
+    for a in values() {
+        let info = contract.execute([bits64(a)])?;
+        assert_eq!(info.ret, canonical64(op(a)).to_bytes32(), "{a:?}");
+    }
+
+    Ok(())
+}
+
+#[test]
+fn disabled() -> Result<()> {
+    let err = Contract::from(Test::FLOAT_ADD)
+        .pure()
+        .compile()
+        .err()
+        .expect("float operators should be rejected");
+    assert!(err.to_string().contains("F64Add"));
+
+    // operators only touching the bits are always supported
+    let mut contract = Contract::from(Test::FLOAT_SIGN).pure().compile()?;
+    let info = contract.execute::<()>([])?;
+    assert_eq!(info.ret, bits64(-1.5).to_bytes32());
+    Ok(())
+}
+
+#[test]
+fn add() -> Result<()> {
+    binary(Test::FLOAT_ADD, |a, b| canonical64(a + b))
+}
+
+#[test]
+fn sub() -> Result<()> {
+    binary(Test::FLOAT_SUB, |a, b| canonical64(a - b))
+}
+
+#[test]
+fn mul() -> Result<()> {
+    binary(Test::FLOAT_MUL, |a, b| canonical64(a * b))
+}
+
+#[test]
+fn div() -> Result<()> {
+    binary(Test::FLOAT_DIV, |a, b| canonical64(a / b))
+}
+
+#[test]
+fn min() -> Result<()> {
+    binary(Test::FLOAT_MIN, |a, b| {
+        if a.is_nan() || b.is_nan() {
+            canonical64(f64::NAN)
+        } else if a == b {
+            bits64(f64::from_bits(a.to_bits() | b.to_bits()))
+        } else {
+            bits64(a.min(b))
+        }
+    })
+}
+
+#[test]
+fn max() -> Result<()> {
+    binary(Test::FLOAT_MAX, |a, b| {
+        if a.is_nan() || b.is_nan() {
+            canonical64(f64::NAN)
+        } else if a == b {
+            bits64(f64::from_bits(a.to_bits() & b.to_bits()))
+        } else {
+            bits64(a.max(b))
+        }
+    })
+}
+
+#[test]
+fn copysign() -> Result<()> {
+    binary(Test::FLOAT_COPYSIGN, |a, b| bits64(a.copysign(b)))
+}
+
+#[test]
+fn compare() -> Result<()> {
+    binary(Test::FLOAT_EQ, |a, b| vec![(a == b) as u8])?;
+    binary(Test::FLOAT_NE, |a, b| vec![(a != b) as u8])?;
+    binary(Test::FLOAT_LT, |a, b| vec![(a < b) as u8])?;
+    binary(Test::FLOAT_LE, |a, b| vec![(a <= b) as u8])?;
+    binary(Test::FLOAT_GE, |a, b| vec![(a >= b) as u8])
+}
+
+#[test]
+fn sqrt() -> Result<()> {
+    unary(Test::FLOAT_SQRT, f64::sqrt)
+}
+
+#[test]
+fn round() -> Result<()> {
+    unary(Test::FLOAT_FLOOR, f64::floor)?;
+    unary(Test::FLOAT_CEIL, f64::ceil)?;
+    unary(Test::FLOAT_TRUNC, f64::trunc)?;
+    unary(Test::FLOAT_NEAREST, f64::round_ties_even)
+}
+
+#[test]
+fn float32() -> Result<()> {
+    binary32(Test::FLOAT_ADD32, |a, b| a + b)?;
+    binary32(Test::FLOAT_MUL32, |a, b| a * b)?;
+    binary32(Test::FLOAT_DIV32, |a, b| a / b)?;
+
+    let mut contract = Contract::from(Test::FLOAT_SQRT32)
+        .pure()
+        .soft_float()
+        .compile()?;
+    for a in values().into_iter().map(|v| v as f32) {
+        let info = contract.execute([bits32(a)])?;
+        assert_eq!(info.ret, canonical32(a.sqrt()).to_bytes32(), "{a:?}");
+    }
+
+    Ok(())
+}
+
+#[test]
+fn demote() -> Result<()> {
+    let mut contract = Contract::from(Test::FLOAT_DEMOTE)
+        .pure()
+        .soft_float()
+        .compile()?;
+
+    // rounding to subnormals and the halfway cases
+    let edges = [
+        f64::from(f32::MIN_POSITIVE) / 3.0,
+        f64::from_bits(0x3ff0_0000_1000_0000),
+        f64::from_bits(0x3ff0_0000_3000_0000),
+        f64::from(f32::MAX) * 1.0000001,
+    ];
+    for a in values().into_iter().chain(edges) {
+        let info = contract.execute([bits64(a)])?;
+        assert_eq!(info.ret, canonical32(a as f32).to_bytes32(), "{a:?}");
+    }
+
+    Ok(())
+}
+
+#[test]
+fn promote() -> Result<()> {
+    let mut contract = Contract::from(Test::FLOAT_PROMOTE)
+        .pure()
+        .soft_float()
+        .compile()?;
+    for a in values().into_iter().map(|v| v as f32) {
+        let info = contract.execute([bits32(a)])?;
+        assert_eq!(info.ret, canonical64(a as f64).to_bytes32(), "{a:?}");
+    }
+
+    Ok(())
+}
+
+#[test]
+fn convert() -> Result<()> {
+    let mut contract = Contract::from(Test::FLOAT_CONVERT_S)
+        .pure()
+        .soft_float()
+        .compile()?;
+    for i in [
+        0,
+        1,
+        -1,
+        3,
+        i64::MAX,
+        i64::MIN,
+        (1 << 53) + 1,
+        -(1 << 60) - 3,
+    ] {
+        let info = contract.execute([i.to_be_bytes().to_vec()])?;
+        assert_eq!(info.ret, bits64(i as f64).to_bytes32(), "{i}");
+    }
+
+    let mut contract = Contract::from(Test::FLOAT_CONVERT_U)
+        .pure()
+        .soft_float()
+        .compile()?;
+    for i in [0, 1, 3, u32::MAX, 1 << 31, (1 << 24) + 1, (1 << 25) + 6] {
+        let info = contract.execute([i.to_be_bytes().to_vec()])?;
+        assert_eq!(info.ret, bits32(i as f32).to_bytes32(), "{i}");
+    }
+
+    Ok(())
+}
+
+#[test]
+fn trunc() -> Result<()> {
+    let mut contract = Contract::from(Test::FLOAT_TRUNC_S)
+        .pure()
+        .soft_float()
+        .compile()?;
+    for a in values()
+        .into_iter()
+        .chain([2147483647.9, -2147483648.9, 2147483648.0])
+    {
+        let info = contract.execute([bits64(a)])?;
+        if a.is_nan() || a.trunc() < i32::MIN as f64 || a.trunc() > i32::MAX as f64 {
+            assert_eq!(info.halt, Some(HaltReason::InvalidJump), "{a:?}");
+        } else {
+            let expected = (a as i32).to_be_bytes().to_vec();
+            assert_eq!(info.ret, expected.to_bytes32(), "{a:?}");
+        }
+    }
+
+    let mut contract = Contract::from(Test::FLOAT_TRUNC_U)
+        .pure()
+        .soft_float()
+        .compile()?;
+    for a in values().into_iter().chain([18446744073709549568.0, -0.9]) {
+        let info = contract.execute([bits64(a)])?;
+        if a.is_nan() || a.trunc() < 0.0 || a.trunc() >= 18446744073709551616.0 {
+            assert_eq!(info.halt, Some(HaltReason::InvalidJump), "{a:?}");
+        } else {
+            let expected = (a as u64).to_be_bytes().to_vec();
+            assert_eq!(info.ret, expected.to_bytes32(), "{a:?}");
+        }
+    }
+
+    Ok(())
+}
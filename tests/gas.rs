@@ -0,0 +1,184 @@
+//! Static gas estimation tests for the zink compiler.
+#![cfg(test)]
+
+use anyhow::Result;
+use filetests::Test;
+use zint::{Bytes32, Contract, DispatchStrategy};
+
+/// Gas used by the contract, excluding the intrinsic gas.
+fn used<Param: Bytes32>(contract: &mut Contract, input: &[Param]) -> Result<u64> {
+    let calldata = contract
+        .encode(input)?
+        .into_iter()
+        .map(|byte| if byte == 0 { 4 } else { 16 })
+        .sum::<u64>();
+
+    let info = contract.execute(input)?;
+    Ok(info.gas - 21000 - calldata)
+}
+
+#[test]
+fn straight_line() -> Result<()> {
+    let mut contract = Contract::from(Test::INTEGER_ADD).pure().compile()?;
+    let gas = contract.artifact.gas["main"].clone();
+
+    assert!(gas.is_bounded());
+    assert_eq!(gas.to_string(), gas.total().to_string());
+    assert_eq!(gas.total(), used(&mut contract, &[1, 2])?);
+    Ok(())
+}
+
+#[test]
+fn calls() -> Result<()> {
+    let mut contract = Contract::from(Test::CALL_PARAMS).pure().compile()?;
+    let gas = contract.artifact.gas["main"].clone();
+
+    assert!(gas.is_bounded());
+    assert_eq!(gas.total(), used(&mut contract, &[1, 2])?);
+    Ok(())
+}
+
+#[test]
+fn branches() -> Result<()> {
+    let mut contract = Contract::from(Test::IF_SINGULAR).pure().compile()?;
+    let gas = contract.artifact.gas["singular"].clone();
+    assert!(gas.is_bounded());
+
+    // the more expensive branch is counted.
+    let then = used(&mut contract, &[1])?;
+    let otherwise = used(&mut contract, &[0])?;
+    assert_eq!(gas.total(), then.max(otherwise));
+    Ok(())
+}
+
+#[test]
+fn loops() -> Result<()> {
+    let mut contract = Contract::from(Test::LOOP_PARAMS).pure().compile()?;
+    let gas = contract.artifact.gas["main"].clone();
+    assert!(!gas.is_bounded());
+    assert_eq!(gas.loops.len(), 1);
+    assert_eq!(
+        gas.to_string(),
+        format!("{} + {} * n0", gas.total(), gas.loops[0])
+    );
+
+    for n in [1, 4, 10] {
+        let bound = gas.total() + gas.loops[0] * (n as u64 - 1);
+        assert!(used(&mut contract, &[n])? <= bound, "{n}");
+    }
+
+    Ok(())
+}
+
+#[test]
+fn recursion() -> Result<()> {
+    let contract = Contract::from(Test::RECURSION_SUM).pure().compile()?;
+    let gas = &contract.artifact.gas["main"];
+
+    assert!(gas.recursive);
+    assert!(gas.to_string().ends_with(" + recursion"));
+    Ok(())
+}
+
+#[test]
+fn peephole() -> Result<()> {
+    let mut plain = Contract::from(Test::IF_DROPPED).pure().compile()?;
+    let mut optimized = Contract::from(Test::IF_DROPPED)
+        .pure()
+        .peephole()
+        .compile()?;
+
+    // the more expensive branch changes with the optimization.
+    for contract in [&mut plain, &mut optimized] {
+        let gas = contract.artifact.gas["dropped"].clone();
+        let then = used(contract, &[1])?;
+        let otherwise = used(contract, &[0])?;
+        assert_eq!(gas.total(), then.max(otherwise));
+    }
+
+    assert!(optimized.artifact.gas["dropped"].total() < plain.artifact.gas["dropped"].total());
+    Ok(())
+}
+
+#[test]
+fn memory() -> Result<()> {
+    // the whole linear memory is counted since the addresses are unknown.
+    for (test, name) in [
+        (&Test::MEMORY_STORE[..], "store"),
+        (&Test::MEMORY_BYTES, "bytes"),
+    ] {
+        let mut contract = Contract::from(test).pure().compile()?;
+        let gas = contract.artifact.gas[name].clone();
+        assert!(gas.is_bounded());
+        for index in 0..4 {
+            assert!(gas.total() >= used(&mut contract, &[index])?, "{name}");
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn dispatcher() -> Result<()> {
+    // the selector matching before each function is counted.
+    for (peephole, eof) in [(false, false), (true, false), (false, true), (true, true)] {
+        let mut contract = Contract::from(Test::DISPATCHER_ARITHMETIC);
+        if peephole {
+            contract = contract.peephole();
+        }
+
+        if eof {
+            contract = contract.eof();
+        }
+
+        let mut contract = contract.compile()?;
+        for name in ["add", "sub"] {
+            let gas = contract.artifact.gas[name].clone();
+            let signature = format!("{name}(uint32,uint32)");
+            let used = used(
+                &mut contract,
+                &[signature.as_bytes(), &5u32.to_bytes32(), &2u32.to_bytes32()],
+            )?;
+            // the jumps of EOF are cheaper than the legacy ones counted.
+            let name = format!("{name} peephole {peephole} eof {eof}");
+            if eof {
+                assert!(gas.total() >= used, "{name}");
+            } else {
+                assert_eq!(gas.total(), used, "{name}");
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn binary_search() -> Result<()> {
+    // the comparisons on the path to each function are counted.
+    for strategy in DispatchStrategy::ALL {
+        let mut contract = Contract::from(Test::DISPATCHER_SEARCH)
+            .dispatch(strategy)
+            .compile()?;
+
+        for index in 0..10 {
+            let gas = contract.artifact.gas[&format!("f{index}")].clone();
+            let used = used(&mut contract, &[format!("f{index}()").as_bytes()])?;
+            assert_eq!(gas.total(), used, "{strategy} f{index}");
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn prologue() -> Result<()> {
+    // the data segments are copied before the function.
+    let mut contract = Contract::from(Test::MEMORY_RODATA).pure().compile()?;
+    let gas = contract.artifact.gas["rodata"].clone();
+    assert!(gas.entry > 0);
+    for index in 0..4 {
+        assert!(gas.total() >= used(&mut contract, &[index])?);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,40 @@
+//! Tests for WASM globals.
+
+use anyhow::Result;
+use filetests::Test;
+use zint::{Bytes32, Contract};
+
+#[test]
+fn counter() -> Result<()> {
+    let mut contract = Contract::from(Test::GLOBAL_COUNTER).pure().compile()?;
+
+    let info = contract.execute([5])?;
+    assert_eq!(info.ret, 116.to_bytes32());
+    Ok(())
+}
+
+#[test]
+fn stack_pointer() -> Result<()> {
+    let mut contract = Contract::from(Test::GLOBAL_STACK_POINTER)
+        .pure()
+        .compile()?;
+
+    let info = contract.execute([41])?;
+    assert_eq!(info.ret, 42.to_bytes32());
+    Ok(())
+}
+
+#[test]
+fn i64() -> Result<()> {
+    let mut contract = Contract::from(Test::GLOBAL_I64).pure().compile()?;
+
+    let info = contract.execute([0])?;
+    assert_eq!(info.ret, vec![0xff; 8].to_bytes32());
+
+    let info = contract.execute([2])?;
+    assert_eq!(
+        info.ret,
+        vec![0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xfd].to_bytes32()
+    );
+    Ok(())
+}
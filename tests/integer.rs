@@ -0,0 +1,147 @@
+//! Integer width tests for the zink compiler.
+#![cfg(test)]
+
+use anyhow::Result;
+use filetests::Test;
+use zint::{Bytes32, Contract};
+
+#[test]
+fn add() -> Result<()> {
+    let mut contract = Contract::from(Test::INTEGER_ADD).pure().compile()?;
+
+    // 0xffffffff + 1
+    let info = contract.execute([vec![0xff; 4], vec![1]])?;
+    assert_eq!(info.ret, 0.to_bytes32());
+    Ok(())
+}
+
+#[test]
+fn mul() -> Result<()> {
+    let mut contract = Contract::from(Test::INTEGER_MUL).pure().compile()?;
+
+    // 0x100000001 * 0x100000001
+    let info = contract.execute([vec![1, 0, 0, 0, 1], vec![1, 0, 0, 0, 1]])?;
+    assert_eq!(info.ret, vec![2, 0, 0, 0, 1].to_bytes32());
+    Ok(())
+}
+
+#[test]
+fn eqz() -> Result<()> {
+    let mut contract = Contract::from(Test::INTEGER_EQZ).pure().compile()?;
+
+    let info = contract.execute([vec![0xff; 4]])?;
+    assert_eq!(info.ret, 1.to_bytes32());
+
+    // -1 in 256 bits is -1 in i32 as well
+    let info = contract.execute([vec![0xff; 32]])?;
+    assert_eq!(info.ret, 1.to_bytes32());
+
+    let info = contract.execute([vec![1]])?;
+    assert_eq!(info.ret, 0.to_bytes32());
+    Ok(())
+}
+
+#[test]
+fn lt_s() -> Result<()> {
+    let mut contract = Contract::from(Test::INTEGER_LT_S).pure().compile()?;
+
+    // -1 < 1
+    let info = contract.execute([vec![0xff; 4], vec![1]])?;
+    assert_eq!(info.ret, 1.to_bytes32());
+
+    // 1 < -1
+    let info = contract.execute([vec![1], vec![0xff; 4]])?;
+    assert_eq!(info.ret, 0.to_bytes32());
+    Ok(())
+}
+
+#[test]
+fn le_u() -> Result<()> {
+    let mut contract = Contract::from(Test::INTEGER_LE_U).pure().compile()?;
+
+    for (a, b, le) in [(3, 3, 1), (4, 3, 0), (3, 4, 1), (0, 0, 1)] {
+        let info = contract.execute([a, b])?;
+        assert_eq!(info.ret, le.to_bytes32());
+    }
+
+    // 1 <= 0xffffffff
+    let info = contract.execute([vec![1], vec![0xff; 4]])?;
+    assert_eq!(info.ret, 1.to_bytes32());
+    Ok(())
+}
+
+#[test]
+fn ge_u() -> Result<()> {
+    let mut contract = Contract::from(Test::INTEGER_GE_U).pure().compile()?;
+
+    for (a, b, ge) in [(3, 3, 1), (4, 3, 1), (3, 4, 0), (0, 0, 1)] {
+        let info = contract.execute([a, b])?;
+        assert_eq!(info.ret, ge.to_bytes32());
+    }
+    Ok(())
+}
+
+#[test]
+fn div_s() -> Result<()> {
+    let mut contract = Contract::from(Test::INTEGER_DIV_S).pure().compile()?;
+
+    // -6 / 2
+    let info = contract.execute([vec![0xff, 0xff, 0xff, 0xfa], vec![2]])?;
+    assert_eq!(info.ret, vec![0xff, 0xff, 0xff, 0xfd].to_bytes32());
+
+    let info = contract.execute([7, 2])?;
+    assert_eq!(info.ret, 3.to_bytes32());
+    Ok(())
+}
+
+#[test]
+fn rem_s() -> Result<()> {
+    let mut contract = Contract::from(Test::INTEGER_REM_S).pure().compile()?;
+
+    // -7 % 2
+    let info = contract.execute([vec![0xff, 0xff, 0xff, 0xf9], vec![2]])?;
+    assert_eq!(info.ret, vec![0xff; 4].to_bytes32());
+
+    let info = contract.execute([7, 4])?;
+    assert_eq!(info.ret, 3.to_bytes32());
+    Ok(())
+}
+
+#[test]
+fn shr_s() -> Result<()> {
+    let mut contract = Contract::from(Test::INTEGER_SHR_S).pure().compile()?;
+
+    // -8 >> 1, the shift count wraps to the bits of i32.
+    for count in [1, 33] {
+        let info = contract.execute([vec![0xff, 0xff, 0xff, 0xf8], vec![count]])?;
+        assert_eq!(info.ret, vec![0xff, 0xff, 0xff, 0xfc].to_bytes32());
+    }
+    Ok(())
+}
+
+#[test]
+fn extend() -> Result<()> {
+    let mut contract = Contract::from(Test::INTEGER_EXTEND).pure().compile()?;
+
+    let info = contract.execute([vec![1, 0x80, 0, 0, 0]])?;
+    assert_eq!(
+        info.ret,
+        vec![0xff, 0xff, 0xff, 0xff, 0x80, 0, 0, 0].to_bytes32()
+    );
+
+    let info = contract.execute([vec![1, 0x7f, 0, 0, 0]])?;
+    assert_eq!(info.ret, vec![0x7f, 0, 0, 0].to_bytes32());
+    Ok(())
+}
+
+#[test]
+fn extend8_s() -> Result<()> {
+    let mut contract = Contract::from(Test::INTEGER_EXTEND8_S).pure().compile()?;
+
+    let info = contract.execute([vec![1, 0x80]])?;
+    assert_eq!(info.ret, vec![0xff, 0xff, 0xff, 0x80].to_bytes32());
+
+    let info = contract.execute([vec![0xff, 0x7f]])?;
+    assert_eq!(info.ret, 0x7f.to_bytes32());
+    Ok(())
+}
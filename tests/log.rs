@@ -89,3 +89,13 @@ fn log4() -> Result<()> {
     assert_eq!(topics[3].to_vec(), b"pong".to_vec().to_bytes32());
     Ok(())
 }
+
+#[test]
+fn long() -> Result<()> {
+    let mut contract = Contract::from(Test::LOG_LONG).pure().compile()?;
+    let info = contract.execute::<()>([])?;
+
+    let message = b"The payload of this log is longer than two words in the EVM memory.";
+    assert_eq!(info.logs[0].data.data.to_vec(), message.to_vec());
+    Ok(())
+}
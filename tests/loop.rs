@@ -25,3 +25,15 @@ fn as_br_if() -> Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn params() -> Result<()> {
+    let mut contract = Contract::from(Test::LOOP_PARAMS).pure().compile()?;
+
+    for (n, expected) in [(1, 1), (4, 10), (10, 55)] {
+        let info = contract.execute([n])?;
+        assert_eq!(info.ret, expected.to_bytes32(), "{n}");
+    }
+
+    Ok(())
+}
@@ -0,0 +1,279 @@
+//! Tests for the linear memory.
+
+use anyhow::Result;
+use filetests::Test;
+use opcodes::Upgrade;
+use zint::{Bytes32, Contract, HaltReason};
+
+#[test]
+fn store() -> Result<()> {
+    let mut contract = Contract::from(Test::MEMORY_STORE).pure().compile()?;
+
+    let info = contract.execute([1])?;
+    assert_eq!(info.ret, 43.to_bytes32());
+
+    let info = contract.execute([vec![0x12, 0x34]])?;
+    assert_eq!(info.ret, vec![0x12, 0x5e].to_bytes32());
+    Ok(())
+}
+
+#[test]
+fn bytes() -> Result<()> {
+    let mut contract = Contract::from(Test::MEMORY_BYTES).pure().compile()?;
+
+    for (index, byte) in [0x44, 0x33, 0x22, 0x11, 0].into_iter().enumerate() {
+        let info = contract.execute([index])?;
+        assert_eq!(info.ret, byte.to_bytes32());
+    }
+
+    Ok(())
+}
+
+#[test]
+fn rodata() -> Result<()> {
+    let mut contract = Contract::from(Test::MEMORY_RODATA).pure().compile()?;
+
+    for (index, value) in [0x11, 0x0222, 0x030333, 0x04040444, 0]
+        .into_iter()
+        .enumerate()
+    {
+        let info = contract.execute([index])?;
+        assert_eq!(info.ret, (value as u32).to_be_bytes().to_vec().to_bytes32());
+    }
+
+    Ok(())
+}
+
+#[test]
+fn store8() -> Result<()> {
+    let mut contract = Contract::from(Test::MEMORY_STORE8).pure().compile()?;
+
+    let info = contract.execute::<()>([])?;
+    assert_eq!(
+        info.ret,
+        vec![0x01, 0x02, 0x03, 0x04, 0x05, 0xff, 0x07, 0x08].to_bytes32()
+    );
+    Ok(())
+}
+
+#[test]
+fn load_s() -> Result<()> {
+    let mut contract = Contract::from(Test::MEMORY_LOAD_S).pure().compile()?;
+
+    let info = contract.execute([vec![0x7f, 0xff]])?;
+    assert_eq!(info.ret, vec![0x7f, 0xff].to_bytes32());
+
+    let info = contract.execute([vec![0x01, 0x80, 0x00]])?;
+    assert_eq!(
+        info.ret,
+        vec![0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x80, 0x00].to_bytes32()
+    );
+    Ok(())
+}
+
+#[test]
+fn grow() -> Result<()> {
+    let mut contract = Contract::from(Test::MEMORY_GROW).pure().compile()?;
+
+    // no pages could be grown by default
+    let info = contract.execute([0])?;
+    assert_eq!(info.ret, 1.to_bytes32());
+    let info = contract.execute([1])?;
+    assert_eq!(info.ret, vec![0xff; 4].to_bytes32());
+
+    // the budget is limited by the maximum pages of the module
+    let mut contract = Contract::from(Test::MEMORY_GROW)
+        .pure()
+        .max_pages(5)
+        .compile()?;
+    let info = contract.execute([2])?;
+    assert_eq!(info.ret, 1.to_bytes32());
+    let info = contract.execute([3])?;
+    assert_eq!(info.ret, vec![0xff; 4].to_bytes32());
+    Ok(())
+}
+
+#[test]
+fn bounds() -> Result<()> {
+    let mut contract = Contract::from(Test::MEMORY_BOUNDS).pure().compile()?;
+
+    for address in [0u32, 0xfffc] {
+        let info = contract.execute([address.to_be_bytes().to_vec()])?;
+        assert_eq!(info.ret, (7 + 0x11).to_bytes32(), "{address:x}");
+    }
+
+    for address in [0xfffd_u32, 0x10000, 0x10060, u32::MAX] {
+        let info = contract.execute([address.to_be_bytes().to_vec()])?;
+        assert_eq!(info.halt, Some(HaltReason::InvalidJump), "{address:x}");
+    }
+
+    Ok(())
+}
+
+#[test]
+fn grown() -> Result<()> {
+    let mut contract = Contract::from(Test::MEMORY_GROWN)
+        .pure()
+        .max_pages(2)
+        .compile()?;
+
+    for (grow, address, ok) in [
+        (0, 8u32, true),
+        (0, 0xfffc, true),
+        // the pages which are not grown yet are out of bounds
+        (0, 0x10008, false),
+        (1, 0x10008, true),
+        (1, 0x1fffc, true),
+        (1, 0x1fffe, false),
+        // a word could not straddle the initial and the grown pages
+        (1, 0xfffe, false),
+    ] {
+        let info = contract.execute([vec![grow], address.to_be_bytes().to_vec()])?;
+        if ok {
+            assert_eq!(info.ret, 42.to_bytes32(), "{grow} {address:x}");
+        } else {
+            assert_eq!(
+                info.halt,
+                Some(HaltReason::InvalidJump),
+                "{grow} {address:x}"
+            );
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn budget() -> Result<()> {
+    // the budget doesn't move the initial pages, where the top
+    // addresses are the cheapest ones
+    for pages in [1, 17] {
+        let mut contract = Contract::from(Test::MEMORY_GROWN)
+            .pure()
+            .max_pages(pages)
+            .compile()?;
+        let info = contract.execute([vec![0], vec![0xff, 0xfc]])?;
+        assert_eq!(info.ret, 42.to_bytes32());
+        assert!(info.gas < 22_000, "{pages}: {}", info.gas);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn size() -> Result<()> {
+    let mut contract = Contract::from(Test::MEMORY_SIZE)
+        .pure()
+        .max_pages(2)
+        .compile()?;
+
+    let info = contract.execute([1])?;
+    assert_eq!(info.ret, 44.to_bytes32());
+
+    // failed grow keeps the size
+    let info = contract.execute([2])?;
+    assert_eq!(info.ret, 43.to_bytes32());
+    Ok(())
+}
+
+#[test]
+fn alloc() -> Result<()> {
+    let mut contract = Contract::from(Test::MEMORY_ALLOC).pure().compile()?;
+
+    // allocations are rounded up to words
+    for (size, allocated) in [(0, 0), (1, 0x20), (0x20, 0x20), (0x21, 0x40)] {
+        let info = contract.execute([size])?;
+        assert_eq!(info.ret, allocated.to_bytes32(), "size {size}");
+    }
+
+    Ok(())
+}
+
+#[test]
+fn heap() -> Result<()> {
+    // the heap is below the linear memory, so the heap of a module with
+    // the 17 pages of rustc is as cheap as the one without memory.
+    let mut contract = Contract::from(Test::MEMORY_HEAP).pure().compile()?;
+    let info = contract.execute([vec![0x01, 0x00]])?;
+    assert_eq!(info.logs[0].data.data.to_vec(), b"Ping".to_vec());
+    assert!(info.gas < 22_000, "{}", info.gas);
+
+    // allocations and logs beyond the heap trap
+    let mut contract = Contract::from(Test::MEMORY_HEAP)
+        .pure()
+        .heap_size(0x40)
+        .compile()?;
+    let info = contract.execute([0x20])?;
+    assert_eq!(info.logs[0].data.data.to_vec(), b"Ping".to_vec());
+    for size in [0x21, 0x40, 0x41] {
+        let info = contract.execute([size])?;
+        assert_eq!(info.halt, Some(HaltReason::InvalidJump), "{size}");
+    }
+
+    Ok(())
+}
+
+#[test]
+fn init() -> Result<()> {
+    let mut contract = Contract::from(Test::MEMORY_INIT).pure().compile()?;
+
+    let info = contract.execute([0])?;
+    assert_eq!(info.ret, vec![4, 3, 2, 1].to_bytes32());
+
+    let info = contract.execute([2])?;
+    assert_eq!(info.ret, vec![6, 5, 4, 3].to_bytes32());
+
+    // the range is out of the segment
+    let info = contract.execute([4])?;
+    assert_eq!(info.ret, vec![8, 7, 6, 5].to_bytes32());
+    let info = contract.execute([5])?;
+    assert_eq!(info.halt, Some(HaltReason::InvalidJump));
+    Ok(())
+}
+
+#[test]
+fn copy() -> Result<()> {
+    // `MCOPY` since cancun, a loop before.
+    for target in [Upgrade::Cancun, Upgrade::Shanghai] {
+        let mut contract = Contract::from(Test::MEMORY_COPY)
+            .pure()
+            .target(target)
+            .compile()?;
+
+        // the destination is above, below and away from the source
+        for dst in [10, 6, 32] {
+            let info = contract.execute([dst])?;
+            assert_eq!(
+                info.ret,
+                vec![8, 7, 6, 5, 4, 3, 2, 1].to_bytes32(),
+                "{target} {dst}"
+            );
+        }
+
+        // the destination is out of the memory
+        let info = contract.execute([vec![0xff, 0xf9]])?;
+        assert_eq!(info.halt, Some(HaltReason::InvalidJump), "{target}");
+    }
+
+    Ok(())
+}
+
+#[test]
+fn fill() -> Result<()> {
+    let mut contract = Contract::from(Test::MEMORY_FILL).pure().compile()?;
+
+    let info = contract.execute([vec![0xab], vec![3]])?;
+    assert_eq!(info.ret, vec![0xab; 3].to_bytes32());
+
+    // only the lowest byte of the value is filled
+    let info = contract.execute([vec![1, 0xff], vec![8]])?;
+    assert_eq!(info.ret, vec![0xff; 8].to_bytes32());
+
+    let info = contract.execute([vec![0xab], vec![0]])?;
+    assert_eq!(info.ret, 0.to_bytes32());
+
+    // the range is out of the memory
+    let info = contract.execute([vec![0xab], vec![0xff, 0xf9]])?;
+    assert_eq!(info.halt, Some(HaltReason::InvalidJump));
+    Ok(())
+}
@@ -0,0 +1,37 @@
+//! Multi-value tests for the zink compiler.
+#![cfg(test)]
+
+use anyhow::Result;
+use filetests::Test;
+use zint::{Bytes32, Contract};
+
+#[test]
+fn call() -> Result<()> {
+    let mut contract = Contract::from(Test::MULTI_CALL).pure().compile()?;
+
+    // the results are returned as a tuple
+    let info = contract.execute([1, 2, 3])?;
+    assert_eq!(
+        info.ret,
+        [2.to_bytes32(), 3.to_bytes32(), 1.to_bytes32()].concat()
+    );
+
+    let info = contract.execute([0, 2, 3])?;
+    assert_eq!(
+        info.ret,
+        [0.to_bytes32(), 2.to_bytes32(), 3.to_bytes32()].concat()
+    );
+    Ok(())
+}
+
+#[test]
+fn block() -> Result<()> {
+    let mut contract = Contract::from(Test::MULTI_BLOCK).pure().compile()?;
+
+    let info = contract.execute([2, 3])?;
+    assert_eq!(info.ret, 5.to_bytes32());
+
+    let info = contract.execute([0, 5])?;
+    assert_eq!(info.ret, 0.to_bytes32());
+    Ok(())
+}
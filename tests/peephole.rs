@@ -0,0 +1,91 @@
+//! Peephole optimizer tests for the zink compiler.
+#![cfg(test)]
+
+use anyhow::Result;
+use filetests::Test;
+use zint::Contract;
+
+/// Run the test with and without the optimizer, returns the sizes of
+/// the bytecode.
+fn compare(wasm: &[u8], inputs: &[Vec<i32>]) -> Result<(usize, usize)> {
+    let mut plain = Contract::from(wasm).pure().compile()?;
+    let mut optimized = Contract::from(wasm).pure().peephole().compile()?;
+
+    for input in inputs {
+        let expected = plain.execute(input)?;
+        let info = optimized.execute(input)?;
+        assert_eq!(info.ret, expected.ret, "{input:?}");
+        assert_eq!(info.halt, expected.halt, "{input:?}");
+        assert!(info.gas <= expected.gas, "{input:?}");
+    }
+
+    assert!(plain.artifact.peephole.is_empty());
+
+    Ok((
+        plain.artifact.runtime_bytecode.len(),
+        optimized.artifact.runtime_bytecode.len(),
+    ))
+}
+
+#[test]
+fn recursion() -> Result<()> {
+    let inputs = (0..8).map(|n| vec![n]).collect::<Vec<_>>();
+    let (plain, optimized) = compare(&Test::RECURSION_FIBONACCI, &inputs)?;
+    assert!(optimized < plain);
+
+    // the statistics are reported in the artifact.
+    let contract = Contract::from(Test::RECURSION_FIBONACCI)
+        .pure()
+        .peephole()
+        .compile()?;
+    let saved = contract
+        .artifact
+        .peephole
+        .values()
+        .map(|stats| stats.saved)
+        .sum::<usize>();
+    assert!(saved > 0);
+
+    compare(&Test::RECURSION_SUM, &inputs)?;
+    compare(&Test::RECURSION_MUTUAL, &inputs)?;
+    Ok(())
+}
+
+#[test]
+fn control_flow() -> Result<()> {
+    let inputs = (0..16).map(|n| vec![n]).collect::<Vec<_>>();
+    for test in [
+        &Test::BR_TABLE_DENSE[..],
+        &Test::BR_TABLE_SPARSE,
+        &Test::BR_TABLE_STACK,
+        &Test::BR_IF_AS_BLOCK_LAST,
+        &Test::CALL_AS_IF,
+        &Test::IF_BASIC,
+    ] {
+        compare(test, &inputs)?;
+    }
+
+    // zero loops forever in both of them
+    compare(&Test::BR_TABLE_AS_LOOP, &inputs[1..5])?;
+    compare(&Test::LOOP_AS_BR_IF, &inputs[1..3])?;
+
+    let inputs = (0..4).map(|n| vec![n, 5, 3]).collect::<Vec<_>>();
+    compare(&Test::CALL_INDIRECT, &inputs)?;
+    Ok(())
+}
+
+#[test]
+fn locals() -> Result<()> {
+    let inputs = [vec![1, 2], vec![-7, 42]];
+    for test in [
+        &Test::I32ADD_TEE[..],
+        &Test::I32ADD_LOCALS,
+        &Test::I64ADD_TEE,
+        &Test::SELECT_PARAMS,
+        &Test::CALL_PARAMS,
+    ] {
+        compare(test, &inputs)?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,62 @@
+//! Tests for the revert host functions.
+
+use anyhow::Result;
+use filetests::Test;
+use zint::{Bytes32, Contract};
+
+#[test]
+fn long() -> Result<()> {
+    let mut contract = Contract::from(Test::REVERT_LONG).pure().compile()?;
+    let info = contract.execute::<()>([])?;
+
+    assert_eq!(
+        info.revert,
+        Some(
+            "A descriptive revert message which is longer than the four words \
+             that the revert macro used to be limited to, so it takes five words."
+                .into()
+        )
+    );
+    assert_eq!(info.panic, None);
+    Ok(())
+}
+
+#[test]
+fn panic() -> Result<()> {
+    let mut contract = Contract::from(Test::REVERT_PANIC).pure().compile()?;
+
+    let info = contract.execute([2])?;
+    assert_eq!(info.ret, 21.to_bytes32());
+
+    let info = contract.execute([0])?;
+    assert_eq!(info.panic, Some(0x12));
+    assert_eq!(info.revert.as_deref(), Some("Panic(0x12)"));
+    Ok(())
+}
+
+#[test]
+fn error() -> Result<()> {
+    let mut contract = Contract::from(Test::REVERT_ERROR).pure().compile()?;
+    let info = contract.execute([42])?;
+
+    let mut output = zink::keccak256(b"Insufficient(uint256,uint256)")[..4].to_vec();
+    output.extend_from_slice(&7.to_bytes32());
+    output.extend_from_slice(&42.to_bytes32());
+    assert_eq!(info.ret, output);
+
+    // the custom errors are included in the ABI.
+    let abi = serde_json::to_value(&contract.artifact.abi)?;
+    assert_eq!(
+        abi,
+        serde_json::json!([{
+            "name": "Insufficient",
+            "type": "error",
+            "inputs": [
+                { "name": "balance", "type": "uint256" },
+                { "name": "needed", "type": "uint256" },
+            ],
+            "outputs": [],
+        }])
+    );
+    Ok(())
+}
@@ -0,0 +1,75 @@
+//! Hardfork targeting tests.
+#![cfg(test)]
+
+use anyhow::Result;
+use filetests::Test;
+use opcodes::Upgrade;
+use zint::{Bytes32, Contract, U256};
+
+#[test]
+fn paris() -> Result<()> {
+    let mut cancun = Contract::from(Test::STORAGE_STORE).pure().compile()?;
+    let mut paris = Contract::from(Test::STORAGE_STORE)
+        .pure()
+        .target(Upgrade::Paris)
+        .compile()?;
+
+    // `PUSH0` is emitted as `PUSH1 0`.
+    assert!(paris.artifact.runtime_bytecode.len() > cancun.artifact.runtime_bytecode.len());
+
+    let info = paris.execute([42])?;
+    assert!(info.halt.is_none());
+    assert_eq!(info.storage.get(&U256::from(0)), Some(&U256::from(42)));
+
+    // `PUSH0` halts the execution on paris.
+    cancun.target = Upgrade::Paris;
+    assert!(cancun.execute([42])?.halt.is_some());
+
+    Ok(())
+}
+
+#[test]
+fn deploy() -> Result<()> {
+    let mut contract = Contract::from(Test::STORAGE_LOAD)
+        .pure()
+        .target(Upgrade::Paris)
+        .compile()?;
+
+    let mut evm = contract.deploy()?;
+    let info = evm
+        .calldata(&contract.encode([42])?)
+        .call(contract.address)?;
+    assert_eq!(info.ret, 42.to_bytes32());
+
+    Ok(())
+}
+
+#[test]
+fn prague() -> Result<()> {
+    let mut contract = Contract::from(Test::CALL_PARAMS)
+        .pure()
+        .target(Upgrade::Prague)
+        .compile()?;
+
+    let info = contract.execute([1, 2])?;
+    assert_eq!(info.ret, 3.to_bytes32());
+
+    Ok(())
+}
+
+#[test]
+fn transient() -> Result<()> {
+    let mut contract = Contract::from(Test::STORAGE_TRANSIENT).pure().compile()?;
+    let info = contract.execute([42])?;
+    assert_eq!(info.ret, 42.to_bytes32());
+
+    let error = Contract::from(Test::STORAGE_TRANSIENT)
+        .pure()
+        .target(Upgrade::Shanghai)
+        .compile()
+        .err()
+        .expect("TSTORE is not available on shanghai");
+    assert!(error.to_string().contains("not available"));
+
+    Ok(())
+}
@@ -0,0 +1,55 @@
+//! Unreachable code tests for the zink compiler.
+#![cfg(test)]
+
+use anyhow::Result;
+use filetests::Test;
+use zint::{Bytes32, Contract};
+
+#[test]
+fn br() -> Result<()> {
+    let mut contract = Contract::from(Test::UNREACHABLE_BR).pure().compile()?;
+
+    for input in [0, 1, 41] {
+        let info = contract.execute([input])?;
+        assert_eq!(info.ret, (input + 1).to_bytes32());
+    }
+
+    Ok(())
+}
+
+#[test]
+fn early_return() -> Result<()> {
+    let mut contract = Contract::from(Test::UNREACHABLE_EARLY_RETURN)
+        .pure()
+        .compile()?;
+
+    for (input, expected) in [(0, 0), (5, 5), (42, 10)] {
+        let info = contract.execute([input])?;
+        assert_eq!(info.ret, expected.to_bytes32(), "{input}");
+    }
+
+    // the dead constant of the then branch is not emitted.
+    assert!(!contract
+        .artifact
+        .runtime_bytecode
+        .windows(2)
+        .any(|w| w == [0x60, 0x07]));
+    Ok(())
+}
+
+#[test]
+fn trap() -> Result<()> {
+    let mut contract = Contract::from(Test::UNREACHABLE_TRAP).pure().compile()?;
+
+    let info = contract.execute([1])?;
+    assert_eq!(info.ret, 42.to_bytes32());
+
+    let info = contract.execute([0])?;
+    assert_eq!(info.panic, Some(0x01));
+    assert_eq!(info.revert.as_deref(), Some("Panic(0x01)"));
+
+    // only the first trap is emitted.
+    let bytecode = &contract.artifact.runtime_bytecode;
+    assert_eq!(bytecode.iter().filter(|b| **b == 0xfd).count(), 1);
+    Ok(())
+}
@@ -273,35 +273,22 @@ fn generate_function_implementation(func: &AbiFunction) -> proc_macro2::TokenStr
 /// - Methods for each function in the ABI, which encode parameters, call the contract, and decode the results.
 ///
 /// # Example
-/// ```rust
-/// #[cfg(feature = "abi-import")]
-/// use zink::import;
+/// ```rust,ignore
+/// use zink::{import, primitives::address::Address};
+/// use zint::revm;
 ///
-/// #[cfg(test)]
-/// mod tests {
-///     use zink::primitives::address::Address;
-///     use zint::revm;
+/// // Single argument: uses default contract name "ERC20"
+/// import!("examples/ERC20.json");
+/// let contract_address = Address::from(revm::CONTRACT);
+/// let token = ERC20::new(contract_address);
+/// let decimals = token.decimals()?;
+/// assert_eq!(decimals, 18);
 ///
-///     #[test]
-///     fn test_contract() -> anyhow::Result<()> {
-///         #[cfg(feature = "abi-import")]
-///         {
-///             // Single argument: uses default contract name "ERC20"
-///             import!("examples/ERC20.json");
-///             let contract_address = Address::from(revm::CONTRACT);
-///             let token = ERC20::new(contract_address);
-///             let decimals = token.decimals()?;
-///             assert_eq!(decimals, 18);
-///
-///             // Two arguments: specifies custom contract name "my_erc20"
-///             import!("examples/ERC20.json", "my_erc20");
-///             let token = MyERC20::new(contract_address);
-///             let decimals = token.decimals()?;
-///             assert_eq!(decimals, 8);
-///         }
-///         Ok(())
-///     }
-/// }
+/// // Two arguments: specifies custom contract name "my_erc20"
+/// import!("examples/ERC20.json", "my_erc20");
+/// let token = MyERC20::new(contract_address);
+/// let decimals = token.decimals()?;
+/// assert_eq!(decimals, 8);
 /// ```
 ///
 /// # Requirements
@@ -75,3 +75,10 @@ impl From<&syn::Signature> for Abi {
         Self(sol_abi::Abi::from(sig))
     }
 }
+
+#[cfg(feature = "syn")]
+impl From<&syn::Variant> for Abi {
+    fn from(variant: &syn::Variant) -> Self {
+        Self(sol_abi::Abi::from(variant))
+    }
+}
@@ -0,0 +1,108 @@
+//! Custom error interface
+
+use heck::ToSnakeCase;
+use proc_macro::TokenStream;
+use proc_macro2::{Ident, Literal, Span, TokenStream as TokenStream2};
+use quote::quote;
+use syn::{Data, DeriveInput, Fields, ItemFn, Variant};
+use zabi::Abi;
+
+/// Expand the custom error interface
+pub fn parse(item: DeriveInput) -> TokenStream {
+    let name = &item.ident;
+    let Data::Enum(error_enum) = &item.data else {
+        panic!("Error can only be derived for enums");
+    };
+
+    let arms = error_enum.variants.iter().map(impl_variant_arm);
+    let abis = error_enum
+        .variants
+        .iter()
+        .map(|variant| impl_variant_abi(&name.to_string(), variant));
+
+    quote! {
+        impl zink::Error for #name {
+            fn revert(self) -> ! {
+                match self {
+                    #(#arms)*
+                }
+            }
+        }
+
+        #(#abis)*
+    }
+    .into()
+}
+
+/// Revert with the selector and the fields of the variant.
+///
+/// The selector is stored at the end of the first word, the fields
+/// follow it word by word.
+fn impl_variant_arm(variant: &Variant) -> TokenStream2 {
+    let name = &variant.ident;
+    let abi = Abi::from(variant);
+    let signature = abi.signature();
+    let selector = Literal::u32_suffixed(u32::from_be_bytes(abi.selector()));
+
+    let fields = variant
+        .fields
+        .iter()
+        .enumerate()
+        .map(|(index, field)| {
+            field
+                .ident
+                .clone()
+                .unwrap_or(Ident::new(&format!("param_{index}"), Span::call_site()))
+        })
+        .collect::<Vec<_>>();
+    let offsets = (1..=fields.len() as u32).map(|index| index * 0x20);
+    let size = (fields.len() as u32 + 1) * 0x20;
+    let len = size - 0x1c;
+
+    let pattern = match &variant.fields {
+        Fields::Named(_) => quote!(Self::#name { #(#fields),* }),
+        Fields::Unnamed(_) => quote!(Self::#name(#(#fields),*)),
+        Fields::Unit => quote!(Self::#name),
+    };
+
+    quote! {
+        #pattern => {
+            #[cfg(target_arch = "wasm32")]
+            {
+                let ptr = zink::heap::alloc(#size);
+                zink::heap::store(ptr, #selector);
+                #(zink::heap::store(ptr + #offsets, #fields);)*
+                zink::heap::revert(ptr + 0x1c, #len)
+            }
+            #[cfg(not(target_arch = "wasm32"))]
+            {
+                #(let _ = #fields;)*
+                panic!(#signature)
+            }
+        }
+    }
+}
+
+/// Emit the ABI of the variant for `zinkc`.
+fn impl_variant_abi(error: &str, variant: &Variant) -> ItemFn {
+    let name = variant.ident.to_string();
+    // the prefix is reserved for errors by `zinkc`
+    let ident = Ident::new(
+        &format!("__zink_error_{}", format!("{error}_{name}").to_snake_case()),
+        Span::call_site(),
+    );
+    let abi = Abi::from(variant).to_hex().expect("ABI is not supported");
+    let abi_len = abi.len() as u32;
+    let doc = " EVM ABI for the error `".to_string() + &name + "`";
+
+    syn::parse_quote! {
+        #[no_mangle]
+        #[cfg(target_arch = "wasm32")]
+        #[doc = #doc]
+        pub extern "C" fn #ident() {
+            unsafe {
+                zink::ffi::emit_abi(#abi.as_ptr() as u32, #abi_len);
+            }
+        }
+    }
+}
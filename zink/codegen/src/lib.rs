@@ -9,6 +9,7 @@ use quote::ToTokens;
 use syn::{parse_macro_input, Attribute, DeriveInput, Expr, ItemFn, ItemStruct, LitStr};
 
 mod contract;
+mod error;
 mod event;
 mod revert;
 mod selector;
@@ -70,6 +71,34 @@ pub fn event(input: TokenStream) -> TokenStream {
     event::parse(input)
 }
 
+/// Custom error interface
+///
+/// ```ignore
+/// use zink::{primitives::U256, Error};
+///
+/// /// Errors of the contract.
+/// #[derive(Error)]
+/// enum Errors {
+///     Insufficient { balance: U256, needed: U256 },
+/// }
+///
+/// #[zink::external]
+/// pub fn withdraw(amount: U256) {
+///     zink::revert_with(Errors::Insufficient {
+///         balance: U256::empty(),
+///         needed: amount,
+///     });
+/// }
+/// ```
+///
+/// reverts with the selector of `Insufficient(uint256,uint256)` and the
+/// ABI-encoded fields, the errors are included in the ABI of the contract.
+#[proc_macro_derive(Error)]
+pub fn error(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    error::parse(input)
+}
+
 /// Declare on-chain storage
 ///
 /// ```ignore
@@ -5,35 +5,18 @@ use proc_macro2::{Literal, Span};
 use quote::{quote, ToTokens};
 use syn::{
     parse::{Parse, ParseStream},
-    parse2, Expr, Ident, LitStr, Token,
+    parse2, Expr, LitStr, Token,
 };
 
 /// Revert with message
 pub fn parse(input: LitStr) -> TokenStream {
     let message = input.value();
-    let len = message.len() as i32;
-    if len > 128 {
-        panic!("Only support revert message less than 128 bytes atm.");
-    }
 
     // TODO: handle the string correctly
     let lit = Literal::string(&message.replace("\"", ""));
-    let rev = Ident::new(
-        &format!(
-            "revert{}",
-            match len {
-                len if len > 96 => 4,
-                len if len > 64 => 3,
-                len if len > 32 => 2,
-                len if len > 0 => 1,
-                _ => panic!("Only support revert message less than 128 bytes atm."),
-            },
-        ),
-        Span::call_site(),
-    );
 
     quote! {
-        unsafe { zink::ffi::asm::#rev(#lit) }
+        unsafe { zink::ffi::asm::revert(#lit) }
     }
     .into()
 }
@@ -3,20 +3,22 @@
 use proc_macro::TokenStream;
 use proc_macro2::{Ident, Span};
 use quote::quote;
-use syn::{parse_quote, ItemFn};
+use syn::{parse_quote, ItemFn, ReturnType, Type};
 use zabi::Abi;
 
 /// Mark the function as external.
 pub fn external(mut item: ItemFn) -> TokenStream {
+    let abi = Abi::from(&item.sig);
     item.sig.abi = Some(parse_quote! { extern "C" });
     item.attrs.push(parse_quote! { #[no_mangle] });
     item.attrs
         .push(parse_quote! { #[allow(improper_ctypes_definitions)] });
+    tuple_return(&mut item);
 
     let selector: ItemFn = {
         let func = item.sig.ident.clone().to_string();
         let ident = Ident::new(&(func.clone() + "_selector"), Span::call_site());
-        let abi = Abi::from(&item.sig).to_hex().expect("ABI is not supported");
+        let abi = abi.to_hex().expect("ABI is not supported");
         let abi_len = abi.len() as u32;
         let doc = " EVM selector for the function `".to_string() + &func + "`";
 
@@ -39,3 +41,34 @@ pub fn external(mut item: ItemFn) -> TokenStream {
     }
     .into()
 }
+
+/// Return tuples as ABI tuples from the heap, since the C ABI of WASM
+/// returns tuples through the linear memory.
+fn tuple_return(item: &mut ItemFn) {
+    let ReturnType::Type(_, ty) = &item.sig.output else {
+        return;
+    };
+
+    let Type::Tuple(tuple) = ty.as_ref() else {
+        return;
+    };
+
+    if tuple.elems.is_empty() {
+        return;
+    }
+
+    let size = tuple.elems.len() as u32 * 0x20;
+    let values = (0..tuple.elems.len())
+        .map(|index| Ident::new(&format!("output{index}"), Span::call_site()))
+        .collect::<Vec<_>>();
+    let offsets = (0..tuple.elems.len() as u32).map(|index| index * 0x20);
+    let block = &item.block;
+
+    item.block = parse_quote! {{
+        let (#(#values),*): #ty = (move || #block)();
+        let ptr = zink::heap::alloc(#size);
+        #(zink::heap::store(ptr + #offsets, #values);)*
+        zink::heap::ret(ptr, #size);
+    }};
+    item.sig.output = ReturnType::Default;
+}
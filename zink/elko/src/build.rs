@@ -71,10 +71,30 @@ impl Build {
 
         // Compile the wasm to evm bytecode.
         let wasm = fs::read(&wasm_path)?;
-        let config = Config::default().dispatcher(self.config.dispatcher);
+        let config = Config::default()
+            .dispatcher(self.config.dispatcher)
+            .max_pages(self.config.max_pages)
+            .heap_size(self.config.heap_size)
+            .soft_float(self.config.soft_float)
+            .peephole(self.config.peephole)
+            .target(self.config.target)
+            .eof(self.config.eof);
         let artifact = Compiler::new(config).compile(&wasm)?;
         let dst = wasm_path.with_extension("bin");
 
+        // Report the static gas estimations.
+        for (name, gas) in artifact.gas.iter() {
+            println!("gas {name}: {gas}");
+        }
+
+        // Report the effect of the peephole optimizer.
+        for (rule, stats) in artifact.peephole.iter() {
+            println!(
+                "peephole {rule}: {} hits, {} bytes saved",
+                stats.hits, stats.saved
+            );
+        }
+
         fs::write(dst, artifact.runtime_bytecode)?;
         Ok(())
     }
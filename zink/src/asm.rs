@@ -36,3 +36,14 @@ macro_rules! impl_asm {
 }
 
 impl_asm!(i8, u8, i16, u16, i32, u32, i64, u64);
+
+impl Asm for bool {
+    fn push(self) {
+        (self as u8).push()
+    }
+
+    #[cfg(not(target_family = "wasm"))]
+    fn bytes32(&self) -> [u8; 32] {
+        (*self as u8).bytes32()
+    }
+}
@@ -0,0 +1,12 @@
+//! Custom errors, the same as the errors of solidity.
+
+/// Custom error interface, see [`crate::Error`] for deriving it.
+pub trait Error {
+    /// Revert with the selector and the ABI-encoded fields of the error.
+    fn revert(self) -> !;
+}
+
+/// Revert with the custom error.
+pub fn revert_with(error: impl Error) -> ! {
+    error.revert()
+}
@@ -67,16 +67,19 @@ extern "C" {
     /// Emit opcode ADDMOD
     pub fn mulmod_u64(a: u64, b: u64, n: u64) -> u64;
 
-    /// Revert with message in 32 bytes
+    /// Revert with message of any length
+    pub fn revert(message: &'static str);
+
+    /// Revert with message in 32 bytes, same as `revert`
     pub fn revert1(message: &'static str);
 
-    /// Revert with message in 64 bytes
+    /// Revert with message in 64 bytes, same as `revert`
     pub fn revert2(message: &'static str);
 
-    /// Revert with message in 96 bytes
+    /// Revert with message in 96 bytes, same as `revert`
     pub fn revert3(message: &'static str);
 
-    /// Revert with message in 128 bytes
+    /// Revert with message in 128 bytes, same as `revert`
     pub fn revert4(message: &'static str);
 
     /// Load a 8-bit signed integer from the storage.
@@ -251,6 +254,10 @@ pub mod asm {
         unimplemented!("Only available in wasm32 target");
     }
 
+    pub fn revert(message: &'static str) {
+        panic!("Revert: {}", message);
+    }
+
     pub fn revert1(message: &'static str) {
         panic!("Revert: {}", message);
     }
@@ -140,6 +140,13 @@ extern "C" {
     /// Get the current blob base fee
     pub fn blobbasefee();
 
+    /// Halt execution returning output data
+    #[link_name = "return"]
+    pub fn return_();
+
+    /// Halt execution reverting state changes, returning output data
+    pub fn revert() -> !;
+
     /// Append log record with no topics
     pub fn log0(name: &'static [u8]);
 
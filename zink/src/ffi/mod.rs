@@ -33,6 +33,15 @@ extern "C" {
     /// Mulmod operation for addresses
     pub fn u256_mulmod(this: U256, other: U256, modulus: U256) -> U256;
 
+    /// Allocate `size` bytes on the heap, returns the memory offset
+    pub fn alloc(size: u32) -> u32;
+
+    /// Get the free memory pointer
+    pub fn free_memory_pointer() -> u32;
+
+    /// Revert with `Panic(uint256)` of the code, see [`crate::panic`]
+    pub fn panic(code: u32) -> !;
+
     /// Set up a label for reserving 32 bytes in memory
     pub fn label_reserve_mem_32();
 
@@ -0,0 +1,43 @@
+//! Zink heap allocator.
+//!
+//! The heap lives above the static memory of the contract, the free
+//! memory pointer is kept at `0x40` like solc, so the memory allocated
+//! by zink contracts could be shared with the assembly of solc.
+//!
+//! Memory on the heap is never freed, and the pointers are offsets in
+//! the EVM memory rather than addresses in the WASM linear memory.
+
+use crate::{ffi, Asm};
+
+/// Allocate `size` bytes on the heap, returns the memory offset.
+///
+/// The size is rounded up to words.
+pub fn alloc(size: u32) -> u32 {
+    unsafe { ffi::alloc(size) }
+}
+
+/// Get the free memory pointer, where the next allocation starts.
+pub fn free_memory_pointer() -> u32 {
+    unsafe { ffi::free_memory_pointer() }
+}
+
+/// Store a word at the memory offset.
+pub fn store(ptr: u32, value: impl Asm) {
+    value.push();
+    ptr.push();
+    unsafe { ffi::evm::mstore() }
+}
+
+/// Halt the execution returning `len` bytes at the memory offset.
+pub fn ret(ptr: u32, len: u32) {
+    len.push();
+    ptr.push();
+    unsafe { ffi::evm::return_() }
+}
+
+/// Revert the execution with `len` bytes at the memory offset.
+pub fn revert(ptr: u32, len: u32) -> ! {
+    len.push();
+    ptr.push();
+    unsafe { ffi::evm::revert() }
+}
@@ -6,13 +6,22 @@
 extern crate alloc;
 
 mod asm;
+mod error;
 mod event;
 pub mod ffi;
+pub mod heap;
+pub mod panic;
 pub mod primitives;
 pub mod storage;
-pub use self::{asm::Asm, event::Event};
+pub use self::{
+    asm::Asm,
+    error::{revert_with, Error},
+    event::Event,
+};
 pub use storage::{DoubleKeyMapping, Mapping, Storage, TransientStorage};
-pub use zink_codegen::{assert, external, revert, storage, transient_storage, Event, Storage};
+pub use zink_codegen::{
+    assert, external, revert, storage, transient_storage, Error, Event, Storage,
+};
 
 #[cfg(feature = "abi-import")]
 pub use zink_abi_macro::import;
@@ -52,5 +61,5 @@ pub fn to_bytes32(src: &[u8]) -> [u8; 32] {
 #[cfg(target_arch = "wasm32")]
 #[panic_handler]
 fn panic(_info: &core::panic::PanicInfo) -> ! {
-    loop {}
+    panic::panic(panic::ASSERT)
 }
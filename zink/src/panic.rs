@@ -0,0 +1,25 @@
+//! Panic codes, the same as solc.
+//!
+//! Contracts revert with `Panic(uint256)` of the codes on panics,
+//! which could be decoded by the tools of solidity.
+
+/// Failed assertions, the code of the rust panics.
+pub const ASSERT: u32 = 0x01;
+
+/// Arithmetic operations overflowed or underflowed.
+pub const ARITHMETIC: u32 = 0x11;
+
+/// Division or modulo by zero.
+pub const DIVISION_BY_ZERO: u32 = 0x12;
+
+/// Revert with `Panic(uint256)` of the code.
+#[cfg(target_arch = "wasm32")]
+pub fn panic(code: u32) -> ! {
+    unsafe { crate::ffi::panic(code) }
+}
+
+/// Revert with `Panic(uint256)` of the code.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn panic(code: u32) -> ! {
+    panic!("Panic(0x{code:02x})")
+}
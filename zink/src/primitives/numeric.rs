@@ -17,14 +17,16 @@ pub trait SafeNumeric: Copy + PartialOrd + Sized {
     fn safe_div(self, rhs: Self) -> Self;
 }
 
-macro_rules! local_revert {
-    ($msg:expr) => {
+/// Revert with `Panic(uint256)` of the code, or panic with the
+/// message out of wasm.
+macro_rules! local_panic {
+    ($code:expr, $msg:expr) => {
         #[cfg(target_arch = "wasm32")]
-        unsafe {
-            crate::ffi::asm::revert1($msg)
+        {
+            crate::panic::panic($code)
         }
         #[cfg(not(target_arch = "wasm32"))]
-        crate::ffi::asm::asm::revert1($msg)
+        crate::ffi::asm::asm::revert($msg)
     };
 }
 
@@ -79,7 +81,7 @@ macro_rules! impl_safe_numeric_signed {
                     let result = self.wrapping_add(rhs);
                     if (self > 0 && rhs > 0 && result < self) ||
                        (self < 0 && rhs < 0 && result > self) {
-                        local_revert!("addition overflow");
+                        local_panic!(crate::panic::ARITHMETIC, "addition overflow");
                     }
                     result
                 }
@@ -88,7 +90,7 @@ macro_rules! impl_safe_numeric_signed {
                 fn safe_sub(self, rhs: Self) -> Self {
                     let result = self.wrapping_sub(rhs);
                     if rhs < 0 && self > result {
-                        local_revert!("subtraction overflow");
+                        local_panic!(crate::panic::ARITHMETIC, "subtraction overflow");
                     }
                     result
                 }
@@ -97,7 +99,7 @@ macro_rules! impl_safe_numeric_signed {
                 fn safe_mul(self, rhs: Self) -> Self {
                     let result = self.wrapping_mul(rhs);
                     if rhs != 0 && result / rhs != self {
-                        local_revert!("multiplication overflow");
+                        local_panic!(crate::panic::ARITHMETIC, "multiplication overflow");
                     }
                     result
                 }
@@ -105,11 +107,11 @@ macro_rules! impl_safe_numeric_signed {
                 #[inline(always)]
                 fn safe_div(self, rhs: Self) -> Self {
                     if rhs == 0 {
-                        local_revert!("division by zero");
+                        local_panic!(crate::panic::DIVISION_BY_ZERO, "division by zero");
                     }
                     let result = self.wrapping_div(rhs);
                     if self == <Self as SafeNumeric>::min() && rhs == -1 {
-                        local_revert!("division overflow");
+                        local_panic!(crate::panic::ARITHMETIC, "division overflow");
                     }
                     result
                 }
@@ -132,7 +134,7 @@ macro_rules! impl_safe_numeric_unsigned {
                 fn safe_add(self, rhs: Self) -> Self {
                     let result = self.wrapping_add(rhs);
                     if result < self {
-                        local_revert!("addition overflow");
+                        local_panic!(crate::panic::ARITHMETIC, "addition overflow");
                     }
                     result
                 }
@@ -141,7 +143,7 @@ macro_rules! impl_safe_numeric_unsigned {
                 fn safe_sub(self, rhs: Self) -> Self {
                     let result = self.wrapping_sub(rhs);
                     if result > self {
-                        local_revert!("subtraction overflow");
+                        local_panic!(crate::panic::ARITHMETIC, "subtraction overflow");
                     }
                     result
                 }
@@ -150,7 +152,7 @@ macro_rules! impl_safe_numeric_unsigned {
                 fn safe_mul(self, rhs: Self) -> Self {
                     let result = self.wrapping_mul(rhs);
                     if rhs != 0 && result / rhs != self {
-                        local_revert!("multiplication overflow");
+                        local_panic!(crate::panic::ARITHMETIC, "multiplication overflow");
                     }
                     result
                 }
@@ -158,7 +160,7 @@ macro_rules! impl_safe_numeric_unsigned {
                 #[inline(always)]
                 fn safe_div(self, rhs: Self) -> Self {
                     if rhs == 0 {
-                        local_revert!("division by zero");
+                        local_panic!(crate::panic::DIVISION_BY_ZERO, "division by zero");
                     }
                     self / rhs
                 }
@@ -182,7 +184,7 @@ impl SafeNumeric for U256 {
     fn safe_add(self, rhs: Self) -> Self {
         let result = unsafe { ffi::u256_add(self, rhs) };
         if result < self {
-            local_revert!("addition overflow");
+            local_panic!(crate::panic::ARITHMETIC, "addition overflow");
         }
         result
     }
@@ -191,7 +193,7 @@ impl SafeNumeric for U256 {
     fn safe_sub(self, rhs: Self) -> Self {
         let result = unsafe { ffi::u256_sub(self, rhs) };
         if result > self {
-            local_revert!("subtraction overflow");
+            local_panic!(crate::panic::ARITHMETIC, "subtraction overflow");
         }
         result
     }
@@ -202,7 +204,7 @@ impl SafeNumeric for U256 {
         let result = unsafe { ffi::u256_mulmod(self, rhs, max) };
         // Check if result exceeds max when rhs > 1
         if rhs > Self::min() && result > self && result > rhs && result > max - self {
-            local_revert!("multiplication overflow");
+            local_panic!(crate::panic::ARITHMETIC, "multiplication overflow");
         }
         result
     }
@@ -210,7 +212,7 @@ impl SafeNumeric for U256 {
     #[inline(always)]
     fn safe_div(self, rhs: Self) -> Self {
         if rhs == Self::min() {
-            local_revert!("division by zero");
+            local_panic!(crate::panic::DIVISION_BY_ZERO, "division by zero");
         }
         unsafe { ffi::u256_div(self, rhs) }
     }